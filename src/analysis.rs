@@ -0,0 +1,1725 @@
+//! Text analyses over the counted content of a document.
+//!
+//! These reports work on the same text that is counted (see
+//! [`counter::extract_text`](crate::counter::extract_text)), so they reflect
+//! the rendered document rather than its source.
+
+use crate::cli::ExportFormat;
+use crate::config::Milestone;
+use crate::counter::{Audit, AuthorCount, Count, SectionCount, SourceLocation, TextChunk};
+use crate::progress::LogEntry;
+use crate::revision::Commit;
+use crate::wordlists::WordFilter;
+use chrono::NaiveDate;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+/// An acronym or abbreviation found in the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Acronym {
+    /// The acronym itself, e.g. `API`
+    pub name: String,
+    /// Number of times it occurs
+    pub occurrences: usize,
+    /// Whether the first occurrence appears to define it
+    ///
+    /// An acronym counts as defined if its first occurrence is either
+    /// parenthesized ("Application Programming Interface (API)") or followed by
+    /// a parenthesized expansion ("API (Application Programming Interface)").
+    pub defined: bool,
+}
+
+/// Finds all-caps acronyms in the given text.
+///
+/// Words of two to ten characters consisting of uppercase letters and digits,
+/// with at least two uppercase letters, are considered acronyms. A trailing
+/// plural `s` is ignored, so "APIs" counts as "API".
+///
+/// # Arguments
+///
+/// * `texts` - Text chunks in document order
+///
+/// # Returns
+///
+/// The acronyms in order of first occurrence.
+///
+/// # Examples
+///
+/// ```
+/// use typst_count::analysis::find_acronyms;
+///
+/// let acronyms = find_acronyms(&["The Portable Document Format (PDF) and PDFs."]);
+/// assert_eq!(acronyms[0].name, "PDF");
+/// assert_eq!(acronyms[0].occurrences, 2);
+/// assert!(acronyms[0].defined);
+/// ```
+#[must_use]
+pub fn find_acronyms<S: AsRef<str>>(texts: &[S]) -> Vec<Acronym> {
+    let mut acronyms: Vec<Acronym> = Vec::new();
+
+    for text in texts {
+        let words: Vec<&str> = text.as_ref().split_whitespace().collect();
+        for (i, word) in words.iter().enumerate() {
+            let Some(name) = acronym(word) else {
+                continue;
+            };
+
+            if let Some(existing) = acronyms.iter_mut().find(|a| a.name == name) {
+                existing.occurrences += 1;
+                continue;
+            }
+
+            let parenthesized = word.starts_with('(');
+            let expanded = words.get(i + 1).is_some_and(|next| next.starts_with('('));
+            acronyms.push(Acronym {
+                name: name.to_string(),
+                occurrences: 1,
+                defined: parenthesized || expanded,
+            });
+        }
+    }
+
+    acronyms
+}
+
+/// Returns the acronym a word represents, if any.
+fn acronym(word: &str) -> Option<&str> {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+    let word = word.strip_suffix('s').unwrap_or(word);
+
+    let uppercase = word.chars().filter(char::is_ascii_uppercase).count();
+    let valid = (2..=10).contains(&word.len())
+        && uppercase >= 2
+        && word
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+
+    valid.then_some(word)
+}
+
+/// Formats an acronym report as a table.
+///
+/// Lists each acronym with its number of occurrences and whether it is
+/// defined on first use, followed by a count of undefined acronyms.
+#[must_use]
+pub fn format_acronyms(acronyms: &[Acronym]) -> String {
+    let mut output = String::new();
+    if acronyms.is_empty() {
+        writeln!(output, "No acronyms found").unwrap();
+        return output;
+    }
+
+    let width = acronyms
+        .iter()
+        .map(|a| a.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    writeln!(
+        output,
+        "{:<width$} {:>12} {:>8}",
+        "Acronym", "Occurrences", "Defined"
+    )
+    .unwrap();
+    writeln!(output, "{}", "─".repeat(width + 22)).unwrap();
+    for acronym in acronyms {
+        let defined = if acronym.defined { "yes" } else { "no" };
+        writeln!(
+            output,
+            "{:<width$} {:>12} {:>8}",
+            acronym.name, acronym.occurrences, defined
+        )
+        .unwrap();
+    }
+
+    let undefined = acronyms.iter().filter(|a| !a.defined).count();
+    writeln!(output, "{}", "─".repeat(width + 22)).unwrap();
+    writeln!(
+        output,
+        "{undefined} of {} not defined on first use",
+        acronyms.len()
+    )
+    .unwrap();
+    output
+}
+
+/// A sentence that occurs more than once across the input files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateSentence {
+    /// The sentence, with whitespace collapsed
+    pub sentence: String,
+    /// Total number of occurrences
+    pub occurrences: usize,
+    /// Files the sentence occurs in, in input order and without repetition
+    pub files: Vec<String>,
+}
+
+/// Minimum number of words for a sentence to be checked for duplicates.
+///
+/// Shorter sentences ("See below.", "Thank you.") repeat legitimately.
+const MIN_DUPLICATE_WORDS: usize = 5;
+
+/// Splits text into sentences.
+///
+/// A sentence ends at `.`, `!`, or `?` followed by whitespace or the end of
+/// the text. Whitespace within each sentence is collapsed to single spaces.
+///
+/// # Examples
+///
+/// ```
+/// use typst_count::analysis::split_sentences;
+///
+/// let sentences = split_sentences("One two.  Three\nfour! Five");
+/// assert_eq!(sentences, ["One two.", "Three four!", "Five"]);
+/// ```
+#[must_use]
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for word in text.split_whitespace() {
+        current.push(word);
+        if word.ends_with(['.', '!', '?']) {
+            sentences.push(current.join(" "));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        sentences.push(current.join(" "));
+    }
+
+    sentences
+}
+
+/// Finds sentences that occur more than once across documents.
+///
+/// Sentences with fewer than five words are ignored. Comparison is exact
+/// after collapsing whitespace.
+///
+/// # Arguments
+///
+/// * `documents` - File names and their extracted text chunks
+///
+/// # Returns
+///
+/// The duplicated sentences in order of first occurrence.
+#[must_use]
+pub fn find_duplicate_sentences(documents: &[(String, Vec<String>)]) -> Vec<DuplicateSentence> {
+    let mut seen: Vec<DuplicateSentence> = Vec::new();
+
+    for (file, texts) in documents {
+        for sentence in texts.iter().flat_map(|text| split_sentences(text)) {
+            if sentence.split(' ').count() < MIN_DUPLICATE_WORDS {
+                continue;
+            }
+
+            if let Some(existing) = seen.iter_mut().find(|d| d.sentence == sentence) {
+                existing.occurrences += 1;
+                if !existing.files.contains(file) {
+                    existing.files.push(file.clone());
+                }
+            } else {
+                seen.push(DuplicateSentence {
+                    sentence,
+                    occurrences: 1,
+                    files: vec![file.clone()],
+                });
+            }
+        }
+    }
+
+    seen.retain(|d| d.occurrences > 1);
+    seen
+}
+
+/// Formats a list of duplicate sentences.
+///
+/// Each line shows the number of occurrences, the sentence, and the files it
+/// occurs in.
+#[must_use]
+pub fn format_duplicates(duplicates: &[DuplicateSentence]) -> String {
+    let mut output = String::new();
+    if duplicates.is_empty() {
+        return output;
+    }
+
+    writeln!(output, "Duplicate sentences:").unwrap();
+    for duplicate in duplicates {
+        writeln!(
+            output,
+            "  {}× \"{}\" ({})",
+            duplicate.occurrences,
+            duplicate.sentence,
+            duplicate.files.join(", ")
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// Formats extracted text chunks, one per line.
+///
+/// Line breaks inside a chunk are replaced by spaces. With `locations`, each
+/// line is prefixed with `file:line:column:` of the chunk's source, the format
+/// understood by most editors and terminals for jumping to a position.
+/// Chunks without a source location (e.g. text generated by the compiler)
+/// are prefixed with `-` instead.
+///
+/// # Arguments
+///
+/// * `chunks` - Text chunks in document order
+/// * `locations` - Whether to prefix each chunk with its source location
+#[must_use]
+pub fn format_chunks(chunks: &[TextChunk], locations: bool) -> String {
+    let mut output = String::new();
+    for chunk in chunks {
+        let text = chunk.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if locations {
+            match &chunk.location {
+                Some(location) => write!(output, "{location}: ").unwrap(),
+                None => output.push_str("-: "),
+            }
+        }
+        writeln!(output, "{text}").unwrap();
+    }
+    output
+}
+
+/// An occurrence of a term in the counted text, with its context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Occurrence {
+    /// Document the occurrence is in
+    pub file: String,
+    /// Heading of the section it is in, or `None` before the first heading
+    pub section: Option<String>,
+    /// Where the text containing it is written, if it comes from a source file
+    pub location: Option<SourceLocation>,
+    /// Text before the occurrence, at most the context width
+    pub before: String,
+    /// The occurrence as written
+    pub matched: String,
+    /// Text after the occurrence, at most the context width
+    pub after: String,
+}
+
+/// Collects the occurrences of a term in counted text, for a
+/// keyword-in-context listing.
+///
+/// Chunks are fed one at a time in document order, e.g. from
+/// [`stream_document`](crate::stream_document), and headings start new
+/// sections. The term matches whole words regardless of case.
+#[derive(Debug, Clone)]
+pub struct Concordance {
+    /// Pattern matching the term
+    pattern: Regex,
+    /// Characters of context kept on either side of an occurrence
+    context: usize,
+    /// Document and section of the last chunk
+    position: Option<(String, Option<String>)>,
+    /// Occurrences found so far, in document order
+    pub occurrences: Vec<Occurrence>,
+}
+
+impl Concordance {
+    /// Prepares a concordance of a term, keeping `context` characters of
+    /// text on either side of each occurrence.
+    #[must_use]
+    pub fn new(term: &str, context: usize) -> Self {
+        let boundary = |c: Option<char>| {
+            if c.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                r"\b"
+            } else {
+                ""
+            }
+        };
+        let pattern = format!(
+            "(?i){}{}{}",
+            boundary(term.chars().next()),
+            regex::escape(term),
+            boundary(term.chars().last())
+        );
+        Self {
+            pattern: Regex::new(&pattern).expect("escaped term is a valid pattern"),
+            context,
+            position: None,
+            occurrences: Vec::new(),
+        }
+    }
+
+    /// Adds the occurrences in a chunk of counted text from a document.
+    pub fn add(&mut self, file: &str, chunk: &TextChunk) {
+        let (last_file, section) = self
+            .position
+            .get_or_insert_with(|| (file.to_string(), None));
+        if last_file != file {
+            *last_file = file.to_string();
+            *section = None;
+        }
+        let text = chunk.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if chunk.element == "heading" {
+            *section = Some(text.clone());
+        }
+        for found in self.pattern.find_iter(&text) {
+            let before: Vec<char> = text[..found.start()].chars().collect();
+            let before: String = before[before.len().saturating_sub(self.context)..]
+                .iter()
+                .collect();
+            self.occurrences.push(Occurrence {
+                file: file.to_string(),
+                section: section.clone(),
+                location: chunk.location.clone(),
+                before,
+                matched: found.as_str().to_string(),
+                after: text[found.end()..].chars().take(self.context).collect(),
+            });
+        }
+    }
+}
+
+/// Formats a keyword-in-context listing and the occurrences per section.
+///
+/// Each occurrence is shown on its own line with its source location and
+/// its context, aligned on the term. The table below counts the occurrences
+/// of every section that has any.
+#[must_use]
+pub fn format_kwic(occurrences: &[Occurrence]) -> String {
+    if occurrences.is_empty() {
+        return "No occurrences found\n".to_string();
+    }
+    let labels: Vec<String> = occurrences
+        .iter()
+        .map(|occurrence| match &occurrence.location {
+            Some(location) => location.to_string(),
+            None => occurrence.file.clone(),
+        })
+        .collect();
+    let label_width = labels.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let before_width = occurrences
+        .iter()
+        .map(|o| o.before.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for (occurrence, label) in occurrences.iter().zip(&labels) {
+        writeln!(
+            output,
+            "{label:<label_width$}  {:>before_width$}{}{}",
+            occurrence.before, occurrence.matched, occurrence.after
+        )
+        .unwrap();
+    }
+
+    let several_files = occurrences.iter().any(|o| o.file != occurrences[0].file);
+    let mut sections: Vec<(String, usize)> = Vec::new();
+    for occurrence in occurrences {
+        let mut name = occurrence
+            .section
+            .clone()
+            .unwrap_or_else(|| "(before first heading)".to_string());
+        if several_files {
+            name = format!("{}: {name}", occurrence.file);
+        }
+        match sections.last_mut() {
+            Some((last, count)) if *last == name => *count += 1,
+            _ => sections.push((name, 1)),
+        }
+    }
+    let width = sections
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    writeln!(output, "\n{:<width$} {:>11}", "Section", "Occurrences").unwrap();
+    writeln!(output, "{}", "─".repeat(width + 12)).unwrap();
+    for (name, count) in &sections {
+        let padding = width - name.chars().count();
+        writeln!(output, "{name}{} {count:>11}", " ".repeat(padding)).unwrap();
+    }
+    writeln!(output, "{}", "─".repeat(width + 12)).unwrap();
+    writeln!(output, "{:<width$} {:>11}", "Total", occurrences.len()).unwrap();
+    output
+}
+
+/// A word or phrase that repeats in the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phrase {
+    /// The words of the phrase, lowercase and separated by single spaces
+    pub text: String,
+    /// Number of words in the phrase
+    pub words: usize,
+    /// Number of times it occurs
+    pub occurrences: usize,
+}
+
+/// Counts the words and phrases with a number of words in `lengths`.
+///
+/// Words are compared regardless of case and surrounding punctuation.
+/// Phrases don't extend across sentences, commas, colons, parentheses, or
+/// quotes. Phrases that occur fewer than `min_occurrences` times are left
+/// out, as are phrases that only occur as part of a longer phrase that is
+/// listed, so "as mentioned above" isn't also listed as "as mentioned".
+/// Words and phrases that `filter` ignores are left out as well.
+///
+/// # Returns
+///
+/// The phrases, most frequent first, and longer phrases before shorter ones
+/// with as many occurrences.
+///
+/// # Examples
+///
+/// ```
+/// use typst_count::analysis::count_phrases;
+/// use typst_count::wordlists::WordFilter;
+///
+/// let texts = ["As mentioned above, it works.", "As mentioned above: see below."];
+/// let phrases = count_phrases(&texts, 2..=3, 2, &WordFilter::default());
+/// assert_eq!(phrases.len(), 1);
+/// assert_eq!(phrases[0].text, "as mentioned above");
+/// assert_eq!(phrases[0].occurrences, 2);
+/// ```
+#[must_use]
+pub fn count_phrases<S: AsRef<str>>(
+    texts: &[S],
+    lengths: RangeInclusive<usize>,
+    min_occurrences: usize,
+    filter: &WordFilter,
+) -> Vec<Phrase> {
+    let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+    for sentence in texts.iter().flat_map(|text| split_sentences(text.as_ref())) {
+        for part in sentence.split([',', ';', ':', '(', ')', '[', ']', '"', '“', '”', '«', '»'])
+        {
+            let words: Vec<String> = part
+                .split_whitespace()
+                .map(|word| {
+                    word.trim_matches(|c: char| !c.is_alphanumeric())
+                        .to_lowercase()
+                })
+                .filter(|word| !word.is_empty())
+                .collect();
+            for length in lengths.clone().filter(|&length| length > 0) {
+                for window in words.windows(length) {
+                    if filter.ignores_phrase(window) {
+                        continue;
+                    }
+                    counts.entry(window.join(" ")).or_insert((length, 0)).1 += 1;
+                }
+            }
+        }
+    }
+
+    let mut phrases: Vec<Phrase> = counts
+        .into_iter()
+        .filter(|(_, (_, occurrences))| *occurrences >= min_occurrences.max(1))
+        .map(|(text, (words, occurrences))| Phrase {
+            text,
+            words,
+            occurrences,
+        })
+        .collect();
+    let contained: Vec<bool> = phrases
+        .iter()
+        .map(|phrase| {
+            let needle = format!(" {} ", phrase.text);
+            phrases.iter().any(|longer| {
+                longer.words > phrase.words
+                    && longer.occurrences == phrase.occurrences
+                    && format!(" {} ", longer.text).contains(&needle)
+            })
+        })
+        .collect();
+    let mut contained = contained.into_iter();
+    phrases.retain(|_| !contained.next().unwrap_or(false));
+    phrases.sort_by(|a, b| {
+        b.occurrences
+            .cmp(&a.occurrences)
+            .then(b.words.cmp(&a.words))
+            .then_with(|| a.text.cmp(&b.text))
+    });
+    phrases
+}
+
+/// Formats the most frequent phrases as a table.
+///
+/// Lists at most `top` phrases, followed by the number of phrases left out.
+#[must_use]
+pub fn format_phrases(phrases: &[Phrase], top: usize) -> String {
+    let mut output = String::new();
+    if phrases.is_empty() {
+        writeln!(output, "No repeated phrases found").unwrap();
+        return output;
+    }
+
+    let shown = &phrases[..top.min(phrases.len())];
+    let width = shown
+        .iter()
+        .map(|phrase| phrase.text.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+    writeln!(output, "{:<width$} {:>11}", "Phrase", "Occurrences").unwrap();
+    writeln!(output, "{}", "─".repeat(width + 12)).unwrap();
+    for phrase in shown {
+        let padding = width - phrase.text.chars().count();
+        writeln!(
+            output,
+            "{}{} {:>11}",
+            phrase.text,
+            " ".repeat(padding),
+            phrase.occurrences
+        )
+        .unwrap();
+    }
+    if phrases.len() > shown.len() {
+        writeln!(output, "{}", "─".repeat(width + 12)).unwrap();
+        writeln!(output, "{} more not shown", phrases.len() - shown.len()).unwrap();
+    }
+    output
+}
+
+/// Formats counted text for other tools, in reading order.
+///
+/// Every chunk becomes a paragraph, with its whitespace collapsed. Headings
+/// are marked with `#` in Markdown, where paragraphs that would read as
+/// Markdown syntax are escaped, and set apart by an extra blank line in
+/// EPUB text.
+#[must_use]
+pub fn format_export(chunks: &[TextChunk], format: ExportFormat) -> String {
+    let mut output = String::new();
+    for chunk in chunks {
+        let text = chunk.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.is_empty() {
+            continue;
+        }
+        if !output.is_empty() {
+            output.push('\n');
+            if format == ExportFormat::EpubText && chunk.heading_level.is_some() {
+                output.push('\n');
+            }
+        }
+        match (format, chunk.heading_level) {
+            (ExportFormat::Markdown, Some(level)) => {
+                writeln!(output, "{} {text}", "#".repeat(level.clamp(1, 6))).unwrap();
+            }
+            (ExportFormat::Markdown, None) => writeln!(output, "{}", escape_block(&text)).unwrap(),
+            (ExportFormat::EpubText, _) => writeln!(output, "{text}").unwrap(),
+        }
+    }
+    output
+}
+
+/// Escapes the start of a paragraph that Markdown would read as a heading,
+/// quote, list item, or rule.
+fn escape_block(text: &str) -> String {
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if text.starts_with(['#', '>', '-', '+', '*', '=', '|']) {
+        format!("\\{text}")
+    } else if digits > 0 && text[digits..].starts_with(['.', ')']) {
+        format!("{}\\{}", &text[..digits], &text[digits..])
+    } else {
+        text.to_string()
+    }
+}
+
+/// Formats the counting audits of several documents.
+///
+/// For each document, reports the text skipped as styling and lists the
+/// elements suspected of being counted twice, with their source location.
+///
+/// # Arguments
+///
+/// * `audits` - Document names and their audits
+#[must_use]
+pub fn format_audit(audits: &[(String, Audit)]) -> String {
+    let mut output = String::new();
+    for (name, audit) in audits {
+        writeln!(output, "{name}:").unwrap();
+        writeln!(
+            output,
+            "  Skipped {} styling elements ({} words, {} characters) already counted in their parents",
+            audit.styling_elements, audit.styling_words, audit.styling_characters
+        )
+        .unwrap();
+
+        if audit.suspects.is_empty() {
+            writeln!(output, "  No double-counted elements found").unwrap();
+            continue;
+        }
+
+        let words: usize = audit.suspects.iter().map(|s| s.words).sum();
+        writeln!(
+            output,
+            "  {} elements ({words} words) may be counted twice:",
+            audit.suspects.len()
+        )
+        .unwrap();
+        for suspect in &audit.suspects {
+            let location = suspect
+                .chunk
+                .location
+                .as_ref()
+                .map_or_else(|| "-".to_string(), ToString::to_string);
+            let text = suspect
+                .chunk
+                .text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(
+                output,
+                "    {location}: {} inside {}: {text:?}",
+                suspect.chunk.element, suspect.container
+            )
+            .unwrap();
+        }
+    }
+    output
+}
+
+/// Formats a document outline annotated with word counts.
+///
+/// Each heading is indented by its level and shown with the words of its own
+/// section, the words including subsections, and the cumulative share of the
+/// document's total.
+///
+/// # Arguments
+///
+/// * `sections` - Section counts in document order
+#[must_use]
+pub fn format_outline(sections: &[SectionCount]) -> String {
+    let mut output = String::new();
+    let total: usize = sections.iter().map(|s| s.words).sum();
+    let titles: Vec<String> = sections
+        .iter()
+        .map(|section| {
+            if section.level == 0 {
+                "(before first heading)".to_string()
+            } else {
+                format!("{}{}", "  ".repeat(section.level - 1), section.title)
+            }
+        })
+        .collect();
+    let width = titles
+        .iter()
+        .map(|t| t.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+
+    writeln!(
+        output,
+        "{:<width$} {:>10} {:>10} {:>7}",
+        "Section", "Words", "Cumulative", "Share"
+    )
+    .unwrap();
+    writeln!(output, "{}", "─".repeat(width + 30)).unwrap();
+    for (section, title) in sections.iter().zip(&titles) {
+        let padding = width - title.chars().count();
+        writeln!(
+            output,
+            "{}{} {:>10} {:>10} {:>6.1}%",
+            title,
+            " ".repeat(padding),
+            section.words,
+            section.cumulative,
+            percent(section.cumulative, total)
+        )
+        .unwrap();
+    }
+    writeln!(output, "{}", "─".repeat(width + 30)).unwrap();
+    writeln!(output, "{:<width$} {:>10}", "Total", total).unwrap();
+    output
+}
+
+/// Formats the words per author as a table.
+///
+/// Each row shows an author with their words and share of the total. Text
+/// without a declared author is listed as "(unattributed)".
+///
+/// # Arguments
+///
+/// * `authors` - Word counts per author, in the order to list them
+#[must_use]
+pub fn format_authors(authors: &[AuthorCount]) -> String {
+    if authors.is_empty() {
+        return "No text found.\n".to_string();
+    }
+
+    let mut output = String::new();
+    let total: usize = authors.iter().map(|a| a.words).sum();
+    let names: Vec<&str> = authors
+        .iter()
+        .map(|a| a.author.as_deref().unwrap_or("(unattributed)"))
+        .collect();
+    let width = names
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    writeln!(
+        output,
+        "{:<width$} {:>10} {:>7}",
+        "Author", "Words", "Share"
+    )
+    .unwrap();
+    writeln!(output, "{}", "─".repeat(width + 19)).unwrap();
+    for (author, name) in authors.iter().zip(&names) {
+        let padding = width - name.chars().count();
+        writeln!(
+            output,
+            "{}{} {:>10} {:>6.1}%",
+            name,
+            " ".repeat(padding),
+            author.words,
+            percent(author.words, total)
+        )
+        .unwrap();
+    }
+    writeln!(output, "{}", "─".repeat(width + 19)).unwrap();
+    writeln!(output, "{:<width$} {:>10}", "Total", total).unwrap();
+    output
+}
+
+/// Number of files and sections listed by [`format_contributors`].
+const TOP_CONTRIBUTORS: usize = 5;
+
+/// Formats the files and sections with the most words.
+///
+/// Lists the largest rows of the results, if there are several, and the
+/// largest sections by their own words, excluding subsections, each with
+/// its share of the total. Sections are prefixed with their file if they
+/// come from several files.
+///
+/// # Arguments
+///
+/// * `results` - Counted rows, e.g. files or groups
+/// * `sections` - Sections of the counted files, each with its file name
+#[must_use]
+pub fn format_contributors(
+    results: &[(String, Count)],
+    sections: &[(String, SectionCount)],
+) -> String {
+    let total: usize = results.iter().map(|(_, count)| count.words).sum();
+    let several_files = from_several_files(sections);
+
+    let mut rows: Vec<(String, usize)> = Vec::new();
+    if results.len() > 1 {
+        let mut files: Vec<_> = results.iter().collect();
+        files.sort_by_key(|(_, count)| std::cmp::Reverse(count.words));
+        rows.extend(
+            files
+                .into_iter()
+                .take(TOP_CONTRIBUTORS)
+                .map(|(name, count)| (name.clone(), count.words)),
+        );
+    }
+    let file_rows = rows.len();
+
+    let mut largest: Vec<_> = sections.iter().filter(|(_, s)| s.words > 0).collect();
+    largest.sort_by_key(|(_, section)| std::cmp::Reverse(section.words));
+    rows.extend(
+        largest
+            .into_iter()
+            .take(TOP_CONTRIBUTORS)
+            .map(|(file, section)| (section_name(file, section, several_files), section.words)),
+    );
+
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut output = String::new();
+    for (i, (name, words)) in rows.iter().enumerate() {
+        if i == 0 && file_rows > 0 {
+            writeln!(output, "Largest files:").unwrap();
+        }
+        if i == file_rows {
+            writeln!(output, "Largest sections:").unwrap();
+        }
+        let padding = width - name.chars().count();
+        writeln!(
+            output,
+            "  {name}{} {words:>8} words {:>5.1}%",
+            " ".repeat(padding),
+            percent(*words, total)
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// A suggested cut to a section, see [`suggest_cuts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    /// Name of the section, prefixed with its file if there are several
+    pub section: String,
+    /// Words in the section, excluding subsections
+    pub words: usize,
+    /// Number of words to cut
+    pub cut: usize,
+}
+
+/// Suggests which sections to trim to bring the word count down to `target`.
+///
+/// The longest sections are trimmed first, each down to a common length, so
+/// that no section is cut further than needed while others are left longer.
+/// This spreads the cuts over the sections furthest above average. Sections
+/// are measured by their own words, excluding subsections. Returns no cuts
+/// if the sections are already within the target.
+#[must_use]
+pub fn suggest_cuts(sections: &[(String, SectionCount)], target: usize) -> Vec<Cut> {
+    let total: usize = sections.iter().map(|(_, section)| section.words).sum();
+    let Some(excess) = total.checked_sub(target).filter(|&excess| excess > 0) else {
+        return Vec::new();
+    };
+
+    let mut sorted: Vec<_> = sections.iter().filter(|(_, s)| s.words > 0).collect();
+    sorted.sort_by_key(|(_, section)| std::cmp::Reverse(section.words));
+
+    // Find the length to trim the k longest sections to, such that the
+    // words above it add up to the excess and the next section is shorter.
+    let mut sum = 0;
+    let mut level = 0;
+    for (k, (_, section)) in sorted.iter().enumerate() {
+        sum += section.words;
+        let candidate = sum.saturating_sub(excess) / (k + 1);
+        let next = sorted.get(k + 1).map_or(0, |(_, next)| next.words);
+        if candidate >= next {
+            level = candidate;
+            break;
+        }
+    }
+
+    let several_files = from_several_files(sections);
+    sorted
+        .into_iter()
+        .filter(|(_, section)| section.words > level)
+        .map(|(file, section)| Cut {
+            section: section_name(file, section, several_files),
+            words: section.words,
+            cut: section.words - level,
+        })
+        .collect()
+}
+
+/// Formats suggested cuts as a table.
+///
+/// # Arguments
+///
+/// * `cuts` - Suggested cuts, see [`suggest_cuts`]
+/// * `total` - Current word count
+/// * `target` - Word count to reach
+#[must_use]
+pub fn format_cuts(cuts: &[Cut], total: usize, target: usize) -> String {
+    if cuts.is_empty() {
+        return format!("{total} words are within the target of {target}.\n");
+    }
+
+    let mut output = String::new();
+    writeln!(
+        output,
+        "{total} words, {} over the target of {target}.\n",
+        total.saturating_sub(target)
+    )
+    .unwrap();
+    let width = cuts
+        .iter()
+        .map(|cut| cut.section.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(7);
+    writeln!(
+        output,
+        "{:<width$} {:>8} {:>8} {:>8}",
+        "Section", "Words", "Cut", "After"
+    )
+    .unwrap();
+    writeln!(output, "{}", "─".repeat(width + 27)).unwrap();
+    for cut in cuts {
+        let padding = width - cut.section.chars().count();
+        writeln!(
+            output,
+            "{}{} {:>8} {:>8} {:>8}",
+            cut.section,
+            " ".repeat(padding),
+            cut.words,
+            format!("-{}", cut.cut),
+            cut.words - cut.cut
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// Checks whether sections come from more than one file.
+fn from_several_files(sections: &[(String, SectionCount)]) -> bool {
+    sections
+        .first()
+        .is_some_and(|(first, _)| sections.iter().any(|(file, _)| file != first))
+}
+
+/// Returns the name of a section, prefixed with its file if the sections
+/// come from several files.
+fn section_name(file: &str, section: &SectionCount, several_files: bool) -> String {
+    let title = if section.level == 0 {
+        "(before first heading)"
+    } else {
+        section.title.as_str()
+    };
+    if several_files {
+        format!("{file}: {title}")
+    } else {
+        title.to_string()
+    }
+}
+
+/// Formats the word counts of a document's history as a table.
+///
+/// Each row shows a commit with the total word count at that commit and the
+/// change since the previous counted commit. Commits at which the documents
+/// couldn't be counted are shown without a count.
+#[must_use]
+pub fn format_trend(points: &[(Commit, Option<Count>)]) -> String {
+    if points.is_empty() {
+        return "No commits found.\n".to_string();
+    }
+
+    let mut output = String::new();
+    writeln!(
+        output,
+        "{:<10}  {:<7} {:>10} {:>8}  Summary",
+        "Date", "Commit", "Words", "Change"
+    )
+    .unwrap();
+    writeln!(output, "{}", "─".repeat(48)).unwrap();
+    let mut previous = None;
+    for (commit, count) in points {
+        let sha = commit.sha.get(..7).unwrap_or(&commit.sha);
+        let (words, change) = match (count, previous) {
+            (Some(count), Some(previous)) => (
+                count.words.to_string(),
+                signed(count.words as i64 - previous as i64),
+            ),
+            (Some(count), None) => (count.words.to_string(), "-".to_string()),
+            (None, _) => ("-".to_string(), "-".to_string()),
+        };
+        writeln!(
+            output,
+            "{:<10}  {sha:<7} {words:>10} {change:>8}  {}",
+            commit.date, commit.summary
+        )
+        .unwrap();
+        previous = count.map(|count| count.words).or(previous);
+    }
+    output
+}
+
+/// Formats the word counts of a document's history as a sparkline.
+///
+/// The line has one bar per commit, scaled between the smallest and the
+/// largest count, and a gap for commits that couldn't be counted. It is
+/// framed by the dates of the first and last commit and followed by the
+/// overall change.
+#[must_use]
+pub fn format_sparkline(points: &[(Commit, Option<Count>)]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+    let words: Vec<_> = points
+        .iter()
+        .map(|(_, count)| count.map(|count| count.words))
+        .collect();
+    let counted = || words.iter().flatten().copied();
+    let (Some(min), Some(max)) = (counted().min(), counted().max()) else {
+        return "No commits found.\n".to_string();
+    };
+
+    let line: String = words
+        .iter()
+        .map(|words| match words {
+            Some(words) if max > min => BARS[(words - min) * (BARS.len() - 1) / (max - min)],
+            Some(_) => BARS[0],
+            None => ' ',
+        })
+        .collect();
+    let first = counted().next().unwrap_or_default();
+    let last = counted().last().unwrap_or_default();
+    format!(
+        "{} {line} {}\n{first} → {last} words ({})\n",
+        points[0].0.date,
+        points[points.len() - 1].0.date,
+        signed(last as i64 - first as i64)
+    )
+}
+
+/// Formats the progress towards dated word-count targets as a table.
+///
+/// Each milestone is listed with its due date, target, the share of the
+/// target reached by `words`, and its status as of `today`. For milestones
+/// that are past due, `at_deadline` holds the word count at their date, if
+/// known, to tell targets met on time from targets met late or missed.
+///
+/// # Arguments
+///
+/// * `milestones` - Milestones with their word count at the deadline
+/// * `words` - Current word count
+/// * `today` - Date to evaluate the milestones at
+#[must_use]
+pub fn format_milestones(
+    milestones: &[(Milestone, Option<usize>)],
+    words: usize,
+    today: NaiveDate,
+) -> String {
+    if milestones.is_empty() {
+        return "No milestones configured.\n".to_string();
+    }
+
+    let mut output = String::new();
+    let width = milestones
+        .iter()
+        .map(|(milestone, _)| milestone.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(9);
+
+    writeln!(output, "Current word count: {words}\n").unwrap();
+    writeln!(
+        output,
+        "{:<width$}  {:<10} {:>8} {:>9}  Status",
+        "Milestone", "Due", "Target", "Progress"
+    )
+    .unwrap();
+    writeln!(output, "{}", "─".repeat(width + 40)).unwrap();
+    for (milestone, at_deadline) in milestones {
+        let remaining = milestone.words.saturating_sub(words);
+        let days = (milestone.date - today).num_days();
+        let status = match at_deadline {
+            _ if days >= 0 && remaining == 0 => "met".to_string(),
+            _ if days > 0 => format!(
+                "{remaining} to go, {days} days left ({}/day)",
+                remaining.div_ceil(days as usize)
+            ),
+            _ if days == 0 => format!("{remaining} to go, due today"),
+            Some(count) if *count >= milestone.words => "met".to_string(),
+            Some(count) if remaining == 0 => format!("met late ({count} at deadline)"),
+            Some(count) => format!("missed ({count} at deadline), {remaining} to go"),
+            None if remaining == 0 => "met".to_string(),
+            None => format!("overdue, {remaining} to go"),
+        };
+        let padding = width - milestone.name.chars().count();
+        writeln!(
+            output,
+            "{}{}  {:<10} {:>8} {:>8.1}%  {status}",
+            milestone.name,
+            " ".repeat(padding),
+            milestone.date.format("%Y-%m-%d"),
+            milestone.words,
+            percent(words, milestone.words).min(100.0)
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// Formats the words written today against a daily goal.
+///
+/// Today's words are the difference between `words` and the count of
+/// `baseline`, the last run recorded before today.
+#[must_use]
+pub fn format_daily(baseline: &LogEntry, words: usize, goal: usize) -> String {
+    let written = words as i64 - baseline.words as i64;
+    let status = if written >= goal as i64 {
+        "goal met".to_string()
+    } else {
+        format!("{} short", goal as i64 - written)
+    };
+    format!(
+        "Words written today: {} of {goal} ({status})\nSince {}: {} → {words} words\n",
+        signed(written),
+        baseline.timestamp,
+        baseline.words
+    )
+}
+
+/// Formats the summary of a writing sprint.
+///
+/// Shows the count at the start and the end of the sprint, the words
+/// written, and the average pace over its length.
+#[must_use]
+pub fn format_sprint(start: usize, end: usize, minutes: u64) -> String {
+    let written = end as i64 - start as i64;
+    let pace = if minutes == 0 {
+        0.0
+    } else {
+        written as f64 / minutes as f64
+    };
+    format!(
+        "Sprint of {minutes} minutes\nWords: {start} → {end} ({})\nPace: {pace:.1} words per minute\n",
+        signed(written)
+    )
+}
+
+/// Formats a change with an explicit sign.
+fn signed(change: i64) -> String {
+    if change > 0 {
+        format!("+{change}")
+    } else {
+        change.to_string()
+    }
+}
+
+/// Returns `part` as a percentage of `total`, or 0 if `total` is zero.
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acronym_detection() {
+        assert_eq!(acronym("API"), Some("API"));
+        assert_eq!(acronym("APIs,"), Some("API"));
+        assert_eq!(acronym("(HTTP2)"), Some("HTTP2"));
+        assert_eq!(acronym("A"), None);
+        assert_eq!(acronym("Api"), None);
+        assert_eq!(acronym("2024"), None);
+    }
+
+    #[test]
+    fn test_find_acronyms_definitions() {
+        let texts = [
+            "We use an API (Application Programming Interface) and JSON.",
+            "The API returns JSON. Hypertext Transfer Protocol (HTTP) too.",
+        ];
+        let acronyms = find_acronyms(&texts);
+
+        assert_eq!(acronyms.len(), 3);
+        assert_eq!(acronyms[0].name, "API");
+        assert_eq!(acronyms[0].occurrences, 2);
+        assert!(acronyms[0].defined);
+        assert_eq!(acronyms[1].name, "JSON");
+        assert!(!acronyms[1].defined);
+        assert_eq!(acronyms[2].name, "HTTP");
+        assert!(acronyms[2].defined);
+    }
+
+    #[test]
+    fn test_format_acronyms() {
+        let output = format_acronyms(&[Acronym {
+            name: "API".to_string(),
+            occurrences: 3,
+            defined: false,
+        }]);
+        assert!(output.contains("API"));
+        assert!(output.contains("1 of 1 not defined on first use"));
+        assert_eq!(format_acronyms(&[]), "No acronyms found\n");
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("Is it? Yes it is.\nDone"),
+            ["Is it?", "Yes it is.", "Done"]
+        );
+        assert!(split_sentences("   ").is_empty());
+    }
+
+    #[test]
+    fn test_find_duplicate_sentences() {
+        let documents = vec![
+            (
+                "a.typ".to_string(),
+                vec!["This sentence was pasted twice. Short one.".to_string()],
+            ),
+            (
+                "b.typ".to_string(),
+                vec![
+                    "This sentence  was pasted twice. Short one.".to_string(),
+                    "A unique sentence that occurs once.".to_string(),
+                ],
+            ),
+        ];
+        let duplicates = find_duplicate_sentences(&documents);
+
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].sentence, "This sentence was pasted twice.");
+        assert_eq!(duplicates[0].occurrences, 2);
+        assert_eq!(duplicates[0].files, ["a.typ", "b.typ"]);
+    }
+
+    #[test]
+    fn test_format_duplicates() {
+        assert_eq!(format_duplicates(&[]), "");
+        let output = format_duplicates(&[DuplicateSentence {
+            sentence: "Same words in the same order.".to_string(),
+            occurrences: 2,
+            files: vec!["a.typ".to_string()],
+        }]);
+        assert!(output.contains("2× \"Same words in the same order.\" (a.typ)"));
+    }
+
+    #[test]
+    fn test_format_outline() {
+        let sections = vec![
+            SectionCount {
+                level: 1,
+                title: "Introduction".to_string(),
+                words: 30,
+                cumulative: 80,
+            },
+            SectionCount {
+                level: 2,
+                title: "Background".to_string(),
+                words: 50,
+                cumulative: 50,
+            },
+            SectionCount {
+                level: 1,
+                title: "Conclusion".to_string(),
+                words: 20,
+                cumulative: 20,
+            },
+        ];
+        let output = format_outline(&sections);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[2].starts_with("Introduction"));
+        assert!(lines[2].ends_with("80.0%"));
+        assert!(lines[3].starts_with("  Background"));
+        assert!(lines[4].ends_with("20.0%"));
+        assert!(lines[6].ends_with("100"));
+    }
+
+    #[test]
+    fn test_format_authors() {
+        let authors = vec![
+            AuthorCount {
+                author: Some("Ada".to_string()),
+                words: 75,
+            },
+            AuthorCount {
+                author: None,
+                words: 25,
+            },
+        ];
+        let output = format_authors(&authors);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[2].starts_with("Ada "));
+        assert!(lines[2].ends_with("75.0%"));
+        assert!(lines[3].starts_with("(unattributed)"));
+        assert!(lines[5].ends_with("100"));
+        assert_eq!(format_authors(&[]), "No text found.\n");
+    }
+
+    #[test]
+    fn test_concordance() {
+        let chunk = |text: &str, element| TextChunk {
+            text: text.to_string(),
+            element,
+            heading_level: None,
+            location: None,
+        };
+        let mut concordance = Concordance::new("hypothesis", 12);
+        concordance.add("a.typ", &chunk("Our hypothesis", "par"));
+        concordance.add("a.typ", &chunk("The Hypothesis", "heading"));
+        concordance.add(
+            "a.typ",
+            &chunk(
+                "The hypothesis holds,\nunlike\nhypotheses or the hypothesis-free one.",
+                "par",
+            ),
+        );
+        concordance.add("b.typ", &chunk("A hypothesis.", "par"));
+
+        let occurrences = &concordance.occurrences;
+        let found: Vec<(&str, Option<&str>)> = occurrences
+            .iter()
+            .map(|o| (o.matched.as_str(), o.section.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("hypothesis", None),
+                ("Hypothesis", Some("The Hypothesis")),
+                ("hypothesis", Some("The Hypothesis")),
+                ("hypothesis", Some("The Hypothesis")),
+                ("hypothesis", None),
+            ]
+        );
+        assert_eq!(occurrences[3].before, "eses or the ");
+        assert_eq!(occurrences[3].after, "-free one.");
+
+        assert_eq!(
+            format_kwic(occurrences),
+            concat!(
+                "a.typ          Our hypothesis\n",
+                "a.typ          The Hypothesis\n",
+                "a.typ          The hypothesis holds, unli\n",
+                "a.typ  eses or the hypothesis-free one.\n",
+                "b.typ            A hypothesis.\n",
+                "\n",
+                "Section                       Occurrences\n",
+                "─────────────────────────────────────────\n",
+                "a.typ: (before first heading)           1\n",
+                "a.typ: The Hypothesis                   3\n",
+                "b.typ: (before first heading)           1\n",
+                "─────────────────────────────────────────\n",
+                "Total                                   5\n",
+            )
+        );
+        assert_eq!(format_kwic(&[]), "No occurrences found\n");
+    }
+
+    #[test]
+    fn test_count_phrases() {
+        let texts = [
+            "As mentioned above, the results hold. As Mentioned Above the results hold.",
+            "It is worth noting (as mentioned above) that it is worth noting.",
+        ];
+        let phrases = count_phrases(&texts, 2..=3, 2, &WordFilter::default());
+        let listed: Vec<(&str, usize)> = phrases
+            .iter()
+            .map(|phrase| (phrase.text.as_str(), phrase.occurrences))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                ("as mentioned above", 3),
+                ("is worth noting", 2),
+                ("it is worth", 2),
+                ("the results hold", 2),
+            ]
+        );
+
+        let words = count_phrases(&texts, 1..=1, 3, &WordFilter::default());
+        let listed: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+        assert_eq!(listed, ["above", "as", "mentioned"]);
+
+        let filter = WordFilter {
+            stopwords: ["as", "above", "is", "it", "the"].map(String::from).into(),
+            exclude: ["noting".to_string()].into(),
+            ..WordFilter::default()
+        };
+        let words = count_phrases(&texts, 1..=1, 2, &filter);
+        let listed: Vec<&str> = words.iter().map(|word| word.text.as_str()).collect();
+        assert_eq!(listed, ["mentioned", "hold", "results", "worth"]);
+        let filtered = count_phrases(&texts, 2..=2, 2, &filter);
+        let listed: Vec<&str> = filtered.iter().map(|phrase| phrase.text.as_str()).collect();
+        assert_eq!(
+            listed,
+            [
+                "as mentioned",
+                "mentioned above",
+                "is worth",
+                "results hold",
+                "the results"
+            ]
+        );
+
+        let report = format_phrases(&phrases, 2);
+        assert_eq!(
+            report,
+            "Phrase             Occurrences\n\
+             ──────────────────────────────\n\
+             as mentioned above           3\n\
+             is worth noting              2\n\
+             ──────────────────────────────\n\
+             2 more not shown\n"
+        );
+        assert_eq!(format_phrases(&[], 10), "No repeated phrases found\n");
+    }
+
+    #[test]
+    fn test_format_chunks_with_locations() {
+        use crate::counter::SourceLocation;
+
+        let chunks = [
+            TextChunk {
+                text: "A first\nparagraph.".to_string(),
+                element: "par",
+                heading_level: None,
+                location: Some(SourceLocation {
+                    file: "chapters/intro.typ".to_string(),
+                    line: 3,
+                    column: 1,
+                }),
+            },
+            TextChunk {
+                text: "Figure 1".to_string(),
+                element: "text",
+                heading_level: None,
+                location: None,
+            },
+        ];
+
+        assert_eq!(
+            format_chunks(&chunks, true),
+            "chapters/intro.typ:3:1: A first paragraph.\n-: Figure 1\n"
+        );
+        assert_eq!(
+            format_chunks(&chunks, false),
+            "A first paragraph.\nFigure 1\n"
+        );
+    }
+
+    #[test]
+    fn test_format_export() {
+        let chunk = |text: &str, heading_level: Option<usize>| TextChunk {
+            text: text.to_string(),
+            element: if heading_level.is_some() {
+                "heading"
+            } else {
+                "par"
+            },
+            heading_level,
+            location: None,
+        };
+        let chunks = [
+            chunk("Preface", Some(1)),
+            chunk("A first\nparagraph.", None),
+            chunk("Methods", Some(2)),
+            chunk("- not a list", None),
+            chunk("2024. A year.", None),
+        ];
+
+        assert_eq!(
+            format_export(&chunks, ExportFormat::Markdown),
+            "# Preface\n\nA first paragraph.\n\n## Methods\n\n\\- not a list\n\n\
+             2024\\. A year.\n"
+        );
+        assert_eq!(
+            format_export(&chunks, ExportFormat::EpubText),
+            "Preface\n\nA first paragraph.\n\n\nMethods\n\n- not a list\n\n2024. A year.\n"
+        );
+    }
+
+    #[test]
+    fn test_format_audit() {
+        use crate::counter::{SourceLocation, Suspect};
+
+        let audit = Audit {
+            styling_elements: 2,
+            styling_words: 3,
+            styling_characters: 15,
+            suspects: vec![Suspect {
+                chunk: TextChunk {
+                    text: "small caps".to_string(),
+                    element: "smallcaps",
+                    heading_level: None,
+                    location: Some(SourceLocation {
+                        file: "main.typ".to_string(),
+                        line: 4,
+                        column: 7,
+                    }),
+                },
+                container: "par",
+                words: 2,
+            }],
+        };
+
+        assert_eq!(
+            format_audit(&[("main.typ".to_string(), audit)]),
+            concat!(
+                "main.typ:\n",
+                "  Skipped 2 styling elements (3 words, 15 characters) already counted in their parents\n",
+                "  1 elements (2 words) may be counted twice:\n",
+                "    main.typ:4:7: smallcaps inside par: \"small caps\"\n",
+            )
+        );
+    }
+
+    /// Builds a history point for the trend tests.
+    fn point(date: &str, words: Option<usize>) -> (Commit, Option<Count>) {
+        let commit = Commit {
+            sha: format!("{:0<40}", date.replace('-', "")),
+            date: date.to_string(),
+            summary: format!("Work on {date}"),
+        };
+        let count = words.map(|words| Count {
+            words,
+            ..Count::default()
+        });
+        (commit, count)
+    }
+
+    #[test]
+    fn test_format_trend() {
+        let points = [
+            point("2026-01-05", Some(1200)),
+            point("2026-01-09", None),
+            point("2026-01-12", Some(1000)),
+            point("2026-02-01", Some(1500)),
+        ];
+        let output = format_trend(&points);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines.len(), 6);
+        assert!(lines[2].starts_with("2026-01-05  2026010"));
+        assert!(lines[2].contains("1200        -  Work on"));
+        assert!(lines[3].contains("   -        -  Work on"));
+        assert!(lines[4].contains("1000     -200  Work on"));
+        assert!(lines[5].contains("1500     +500  Work on"));
+    }
+
+    #[test]
+    fn test_format_milestones() {
+        let milestone = |name: &str, date: &str, words| Milestone {
+            name: name.to_string(),
+            date: date.parse().unwrap(),
+            words,
+        };
+        let milestones = [
+            (milestone("outline", "2026-01-15", 2000), Some(2500)),
+            (milestone("draft", "2026-02-01", 20000), Some(12000)),
+            (milestone("review", "2026-02-20", 40000), None),
+            (milestone("full", "2026-04-01", 80000), None),
+        ];
+        let output = format_milestones(&milestones, 30000, "2026-03-02".parse().unwrap());
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "Current word count: 30000");
+        assert_eq!(lines.len(), 8);
+        assert!(lines[4].ends_with("100.0%  met"));
+        assert!(lines[5].ends_with("met late (12000 at deadline)"));
+        assert!(lines[6].ends_with("75.0%  overdue, 10000 to go"));
+        assert!(lines[7].starts_with("full       2026-04-01    80000"));
+        assert!(lines[7].ends_with("50000 to go, 30 days left (1667/day)"));
+        assert_eq!(
+            format_milestones(&[], 0, "2026-03-02".parse().unwrap()),
+            "No milestones configured.\n"
+        );
+    }
+
+    #[test]
+    fn test_format_daily() {
+        let baseline = LogEntry {
+            timestamp: "2026-03-02 21:00:00".to_string(),
+            words: 1900,
+        };
+        assert_eq!(
+            format_daily(&baseline, 2500, 500),
+            "Words written today: +600 of 500 (goal met)\n\
+             Since 2026-03-02 21:00:00: 1900 → 2500 words\n"
+        );
+        assert!(
+            format_daily(&baseline, 2200, 500)
+                .starts_with("Words written today: +300 of 500 (200 short)")
+        );
+        assert!(format_daily(&baseline, 1850, 500).contains("-50 of 500 (550 short)"));
+    }
+
+    #[test]
+    fn test_format_sprint() {
+        assert_eq!(
+            format_sprint(1200, 1650, 25),
+            "Sprint of 25 minutes\nWords: 1200 → 1650 (+450)\nPace: 18.0 words per minute\n"
+        );
+        assert!(format_sprint(1200, 1150, 0).contains("(-50)\nPace: 0.0"));
+    }
+
+    #[test]
+    fn test_format_contributors() {
+        let count = |words| Count {
+            words,
+            ..Count::default()
+        };
+        let section = |level, title: &str, words| SectionCount {
+            level,
+            title: title.to_string(),
+            words,
+            cumulative: words,
+        };
+        let results = [
+            ("intro.typ".to_string(), count(200)),
+            ("results.typ".to_string(), count(800)),
+        ];
+        let sections = [
+            ("intro.typ".to_string(), section(1, "Introduction", 200)),
+            ("results.typ".to_string(), section(1, "Results", 50)),
+            ("results.typ".to_string(), section(2, "Ablations", 750)),
+        ];
+
+        assert_eq!(
+            format_contributors(&results, &sections),
+            concat!(
+                "Largest files:\n",
+                "  results.typ                  800 words  80.0%\n",
+                "  intro.typ                    200 words  20.0%\n",
+                "Largest sections:\n",
+                "  results.typ: Ablations       750 words  75.0%\n",
+                "  intro.typ: Introduction      200 words  20.0%\n",
+                "  results.typ: Results          50 words   5.0%\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_suggest_cuts() {
+        let section = |title: &str, words| {
+            (
+                "thesis.typ".to_string(),
+                SectionCount {
+                    level: 1,
+                    title: title.to_string(),
+                    words,
+                    cumulative: words,
+                },
+            )
+        };
+        let sections = [
+            section("Introduction", 1000),
+            section("Methods", 3000),
+            section("Results", 2600),
+            section("Discussion", 1400),
+        ];
+
+        let cuts = suggest_cuts(&sections, 7000);
+        assert_eq!(
+            cuts,
+            [
+                Cut {
+                    section: "Methods".to_string(),
+                    words: 3000,
+                    cut: 700,
+                },
+                Cut {
+                    section: "Results".to_string(),
+                    words: 2600,
+                    cut: 300,
+                },
+            ]
+        );
+        assert!(suggest_cuts(&sections, 8000).is_empty());
+
+        let output = format_cuts(&cuts, 8000, 7000);
+        assert!(output.starts_with("8000 words, 1000 over the target of 7000.\n"));
+        assert!(output.contains("Methods     3000     -700     2300\n"));
+    }
+
+    #[test]
+    fn test_format_sparkline() {
+        let points = [
+            point("2026-01-05", Some(1000)),
+            point("2026-01-09", None),
+            point("2026-01-12", Some(1700)),
+            point("2026-02-01", Some(2000)),
+        ];
+        assert_eq!(
+            format_sparkline(&points),
+            "2026-01-05 ▁ ▅█ 2026-02-01\n1000 → 2000 words (+1000)\n"
+        );
+        assert_eq!(format_sparkline(&[]), "No commits found.\n");
+    }
+}
@@ -0,0 +1,462 @@
+//! Signed word count certificates.
+//!
+//! Submissions with a declared word count can come with a certificate that
+//! makes the count verifiable: `typst-count certify` states the count of
+//! every document, the SHA-256 digests of the files it was compiled from,
+//! the counting options, and the versions of typst-count and Typst, and
+//! signs the statement with a minisign (Ed25519) key. The certificate is a
+//! JSON object holding the statement's fields and a `signature` field with
+//! the minisign signature of the statement, serialized compactly without
+//! the signature.
+//!
+//! `typst-count verify-cert` checks the signature and the digests of the
+//! sources, and recounts the documents with the certified options, so a
+//! third party can confirm the count still holds.
+
+use crate::counter::{Count, CountOptions, Normalization};
+use crate::{compiler_version, count_plain, count_with_dependencies, discovery};
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Counts documents and states their counts, sources, and counting options.
+///
+/// Source paths are relative to the working directory where possible, so a
+/// certificate can be checked in another copy of the project.
+///
+/// # Errors
+///
+/// Returns an error if a document fails to compile or a source cannot be
+/// read.
+pub fn statement(inputs: &[PathBuf], options: &CountOptions) -> Result<Value> {
+    let cwd = env::current_dir()
+        .and_then(|dir| dir.canonicalize())
+        .unwrap_or_default();
+    let mut files = Vec::new();
+    let mut total = Count::default();
+    for path in inputs {
+        let (count, sources) = count_sources(path, options)?;
+        let mut hashed = Vec::new();
+        for source in sources {
+            let bytes = fs::read(&source)
+                .with_context(|| format!("Failed to read file: {}", source.display()))?;
+            let source = source.strip_prefix(&cwd).unwrap_or(&source);
+            hashed.push(json!({
+                "path": source.display().to_string(),
+                "sha256": format!("{:x}", Sha256::digest(&bytes)),
+            }));
+        }
+        files.push(json!({
+            "file": path.display().to_string(),
+            "words": count.words,
+            "characters": count.characters,
+            "sources": hashed,
+        }));
+        total += count;
+    }
+    Ok(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "typst_version": compiler_version(),
+        "created": chrono::Local::now().to_rfc3339(),
+        "options": options_json(options),
+        "files": files,
+        "words": total.words,
+        "characters": total.characters,
+    }))
+}
+
+/// Signs a statement with a minisign secret key and returns the certificate.
+///
+/// Without a key, minisign's default key is used. minisign asks for the
+/// key's password on the terminal if it has one.
+///
+/// # Errors
+///
+/// Returns an error if `minisign` is not installed or fails to sign.
+pub fn sign(mut statement: Value, secret_key: Option<&Path>) -> Result<String> {
+    // A private directory, so no one else can swap the files minisign reads
+    // and writes.
+    let dir = tempfile::tempdir().context("Failed to create a temporary directory")?;
+    let base = dir.path().join("certificate.json");
+    let signature = dir.path().join("certificate.json.minisig");
+    fs::write(&base, canonical(&statement)).context("Failed to write the statement to sign")?;
+
+    let comment = format!("typst-count certificate, {} words", statement["words"]);
+    let mut command = Command::new("minisign");
+    command
+        .arg("-S")
+        .arg("-m")
+        .arg(&base)
+        .arg("-x")
+        .arg(&signature);
+    command.arg("-t").arg(comment);
+    if let Some(key) = secret_key {
+        command.arg("-s").arg(key);
+    }
+    let status = command
+        .status()
+        .context("Failed to run minisign; install minisign to certify counts")?;
+    if !status.success() {
+        bail!("Failed to sign the certificate");
+    }
+
+    let text = fs::read_to_string(&signature).context("Failed to read the signature")?;
+    statement["signature"] = Value::String(text);
+    Ok(serde_json::to_string_pretty(&statement)? + "\n")
+}
+
+/// The outcome of checking one document of a certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCheck {
+    /// Document as named in the certificate
+    pub file: String,
+    /// Certified word and character counts
+    pub certified: (usize, usize),
+    /// Word and character counts of the document now, unless only the
+    /// digests of its sources were checked
+    pub counted: Option<(usize, usize)>,
+    /// Sources that changed or are missing since the certificate was made
+    pub changed: Vec<String>,
+}
+
+impl FileCheck {
+    /// Checks whether the certified count still holds: the document counts
+    /// the same, or, if it wasn't recounted, none of its sources changed.
+    #[must_use]
+    pub fn holds(&self) -> bool {
+        match self.counted {
+            Some(counted) => counted == self.certified,
+            None => self.changed.is_empty(),
+        }
+    }
+}
+
+/// The outcome of checking a certificate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    /// Time the certificate was made, as RFC 3339
+    pub created: String,
+    /// Version of Typst the documents were certified with
+    pub typst_version: String,
+    /// Checks of the certified documents
+    pub files: Vec<FileCheck>,
+}
+
+impl Verification {
+    /// Checks whether the counts of all certified documents still hold.
+    #[must_use]
+    pub fn holds(&self) -> bool {
+        self.files.iter().all(FileCheck::holds)
+    }
+}
+
+/// Verifies a certificate's signature with a minisign public key and checks
+/// its documents.
+///
+/// Without a key, minisign's default public key is used. The documents are
+/// recounted with the certified options, unless `recount` is false, in which
+/// case only the digests of their sources are compared. Source paths are
+/// resolved against the working directory.
+///
+/// # Errors
+///
+/// Returns an error if the certificate is malformed, its signature is
+/// invalid, `minisign` is not installed, or a document fails to compile.
+pub fn verify(certificate: &str, public_key: Option<&Path>, recount: bool) -> Result<Verification> {
+    let statement: Value = serde_json::from_str(certificate).context("Invalid certificate")?;
+    let signature = statement["signature"]
+        .as_str()
+        .context("The certificate is not signed")?;
+    check_signature(&statement, signature, public_key)?;
+    check(&statement, recount)
+}
+
+/// Formats the outcome of checking a certificate, a line per document.
+#[must_use]
+pub fn format_verification(verification: &Verification) -> String {
+    let counts =
+        |(words, characters): (usize, usize)| format!("{words} words, {characters} characters");
+    let mut output = format!(
+        "Signature valid, certified {} with Typst {}\n",
+        verification.created, verification.typst_version
+    );
+    for check in &verification.files {
+        let status = match check.counted {
+            _ if check.holds() => format!("holds ({})", counts(check.certified)),
+            Some(counted) => format!(
+                "certified {}, counted {}",
+                counts(check.certified),
+                counts(counted)
+            ),
+            None => "no longer holds".to_string(),
+        };
+        output += &format!("{}: {status}", check.file);
+        if !check.changed.is_empty() {
+            output += &format!("; changed: {}", check.changed.join(", "));
+        }
+        output.push('\n');
+    }
+    output += if verification.holds() {
+        "The certified count holds\n"
+    } else {
+        "The certified count no longer holds\n"
+    };
+    output
+}
+
+/// Counts a document and returns the files it was compiled from.
+fn count_sources(path: &Path, options: &CountOptions) -> Result<(Count, Vec<PathBuf>)> {
+    if discovery::is_plain(path) {
+        Ok((count_plain(path, options)?, vec![path.to_path_buf()]))
+    } else {
+        count_with_dependencies(path, options)
+    }
+}
+
+/// Verifies the minisign signature of a statement.
+fn check_signature(statement: &Value, signature: &str, public_key: Option<&Path>) -> Result<()> {
+    // minisign checks the files, not the statement in memory, so they are
+    // kept where no one else can replace them.
+    let dir = tempfile::tempdir().context("Failed to create a temporary directory")?;
+    let base = dir.path().join("certificate.json");
+    let signature_path = dir.path().join("certificate.json.minisig");
+    fs::write(&base, canonical(statement))
+        .and_then(|()| fs::write(&signature_path, signature))
+        .context("Failed to write the statement to verify")?;
+
+    let mut command = Command::new("minisign");
+    command.arg("-V").arg("-q").arg("-m").arg(&base);
+    command.arg("-x").arg(&signature_path);
+    if let Some(key) = public_key {
+        command.arg("-p").arg(key);
+    }
+    let output = command
+        .output()
+        .context("Failed to run minisign; install minisign to verify certificates")?;
+    if !output.status.success() {
+        bail!(
+            "The certificate's signature is invalid: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Checks the documents of a statement against the files on disk.
+fn check(statement: &Value, recount: bool) -> Result<Verification> {
+    let field = |value: &Value, name: &str| -> Result<Value> {
+        value
+            .get(name)
+            .cloned()
+            .with_context(|| format!("Invalid certificate: missing field {name}"))
+    };
+    let number = |value: &Value, name: &str| -> Result<usize> {
+        let number = field(value, name)?.as_u64();
+        number
+            .and_then(|number| usize::try_from(number).ok())
+            .with_context(|| format!("Invalid certificate: {name} is not a count"))
+    };
+    let text = |value: &Value, name: &str| -> Result<String> {
+        let text = field(value, name)?;
+        text.as_str()
+            .map(str::to_string)
+            .with_context(|| format!("Invalid certificate: {name} is not a string"))
+    };
+
+    let options = options_from_json(&field(statement, "options")?)?;
+    let mut files = Vec::new();
+    for file in field(statement, "files")?.as_array().into_iter().flatten() {
+        let path = text(file, "file")?;
+        let mut changed = Vec::new();
+        for source in field(file, "sources")?.as_array().into_iter().flatten() {
+            let source_path = text(source, "path")?;
+            let digest =
+                fs::read(&source_path).map(|bytes| format!("{:x}", Sha256::digest(&bytes)));
+            if digest.ok() != Some(text(source, "sha256")?) {
+                changed.push(source_path);
+            }
+        }
+        let counted = if recount {
+            let (count, _) = count_sources(Path::new(&path), &options)?;
+            Some((count.words, count.characters))
+        } else {
+            None
+        };
+        files.push(FileCheck {
+            certified: (number(file, "words")?, number(file, "characters")?),
+            file: path,
+            counted,
+            changed,
+        });
+    }
+    Ok(Verification {
+        created: text(statement, "created")?,
+        typst_version: text(statement, "typst_version")?,
+        files,
+    })
+}
+
+/// Returns the bytes of a statement that are signed.
+fn canonical(statement: &Value) -> String {
+    let mut statement = statement.clone();
+    if let Some(fields) = statement.as_object_mut() {
+        fields.remove("signature");
+    }
+    statement.to_string()
+}
+
+/// Returns the counting options a certificate records: every option that
+/// decides which content is counted, with the exclusions of a preset
+/// spelled out.
+fn options_json(options: &CountOptions) -> Value {
+    let normalize: Vec<String> = options
+        .normalize
+        .iter()
+        .filter_map(ValueEnum::to_possible_value)
+        .map(|value| value.get_name().to_string())
+        .collect();
+    json!({
+        "exclude_imports": options.exclude_imports,
+        "exclude_captions": options.exclude_captions,
+        "exclude_footnotes": options.exclude_footnotes,
+        "exclude_bibliography": options.exclude_bibliography,
+        "exclude_floats": options.exclude_floats,
+        "exclude_urls": options.exclude_urls,
+        "exclude_numbers": options.exclude_numbers,
+        "exclude_packages": options.exclude_packages,
+        "exclude_generated": options.exclude_generated,
+        "include_chrome": options.include_chrome,
+        "include_outline": options.include_outline,
+        "exclude_numbering": options.exclude_numbering,
+        "exclude_marked": options.exclude_marked,
+        "styling_elements": options.styling_elements,
+        "normalize": normalize,
+        "word_pattern": options.word_pattern.as_ref().map(|pattern| pattern.as_str()),
+    })
+}
+
+/// Returns the counting options recorded by [`options_json`].
+///
+/// # Errors
+///
+/// Returns an error if a normalization pass or the word pattern is invalid.
+fn options_from_json(options: &Value) -> Result<CountOptions> {
+    let flag = |name: &str| options[name].as_bool().unwrap_or(false);
+    let list = |name: &str| -> Vec<String> {
+        options[name]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|value| value.as_str().map(str::to_string))
+            .collect()
+    };
+    let normalize = list("normalize")
+        .iter()
+        .map(|name| {
+            Normalization::from_str(name, false)
+                .map_err(|_| anyhow::anyhow!("Invalid certificate: unknown normalization `{name}`"))
+        })
+        .collect::<Result<_>>()?;
+    let word_pattern = options["word_pattern"]
+        .as_str()
+        .map(|pattern| {
+            pattern
+                .parse()
+                .context("Invalid certificate: invalid word pattern")
+        })
+        .transpose()?;
+    Ok(CountOptions {
+        exclude_imports: flag("exclude_imports"),
+        exclude_captions: flag("exclude_captions"),
+        exclude_footnotes: flag("exclude_footnotes"),
+        exclude_bibliography: flag("exclude_bibliography"),
+        exclude_floats: flag("exclude_floats"),
+        exclude_urls: flag("exclude_urls"),
+        exclude_numbers: flag("exclude_numbers"),
+        exclude_packages: flag("exclude_packages"),
+        exclude_generated: flag("exclude_generated"),
+        include_chrome: flag("include_chrome"),
+        include_outline: flag("include_outline"),
+        exclude_numbering: flag("exclude_numbering"),
+        exclude_marked: list("exclude_marked"),
+        styling_elements: list("styling_elements"),
+        normalize,
+        word_pattern,
+        ..CountOptions::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statement() {
+        let dir = tempfile::tempdir().unwrap();
+        let inputs = [dir.path().join("notes.md")];
+        fs::write(&inputs[0], "Three words here").unwrap();
+        let options = CountOptions {
+            exclude_footnotes: true,
+            ..CountOptions::default()
+        };
+
+        let statement = statement(&inputs, &options).unwrap();
+        assert_eq!(statement["words"], 3);
+        assert_eq!(statement["options"]["exclude_footnotes"], true);
+        let file = &statement["files"][0];
+        assert_eq!(file["file"], inputs[0].display().to_string());
+        assert_eq!(
+            file["sources"][0]["sha256"],
+            format!("{:x}", Sha256::digest(b"Three words here"))
+        );
+
+        // The signature is not part of what is signed.
+        let mut signed = statement.clone();
+        signed["signature"] = Value::String("untrusted comment: ...".to_string());
+        assert_eq!(canonical(&signed), canonical(&statement));
+        assert!(!canonical(&statement).contains('\n'));
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let inputs = [dir.path().join("notes.md")];
+        fs::write(&inputs[0], "Three words here").unwrap();
+        let options = CountOptions {
+            exclude_marked: vec!["<todo>".to_string()],
+            include_chrome: true,
+            normalize: vec![Normalization::SoftHyphens],
+            word_pattern: Some(r"[\w']+".parse().unwrap()),
+            ..CountOptions::default()
+        };
+        let statement = statement(&inputs, &options).unwrap();
+        assert_eq!(statement["options"]["normalize"][0], "soft-hyphens");
+        assert_eq!(options_from_json(&statement["options"]).unwrap(), options);
+
+        let verification = check(&statement, true).unwrap();
+        assert!(verification.holds());
+        assert_eq!(verification.files[0].counted, Some((3, 16)));
+        assert!(format_verification(&verification).ends_with("The certified count holds\n"));
+
+        // Changing the source without changing the count keeps the count.
+        fs::write(&inputs[0], "Three words HERE").unwrap();
+        let verification = check(&statement, true).unwrap();
+        assert!(verification.holds());
+        assert_eq!(verification.files[0].changed.len(), 1);
+        assert!(!check(&statement, false).unwrap().holds());
+
+        fs::write(&inputs[0], "Now four words here").unwrap();
+        let verification = check(&statement, true).unwrap();
+        assert!(!verification.holds());
+        let output = format_verification(&verification);
+        assert!(
+            output.contains("certified 3 words, 16 characters, counted 4 words, 19 characters")
+        );
+        assert!(output.ends_with("The certified count no longer holds\n"));
+    }
+}
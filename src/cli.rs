@@ -3,8 +3,18 @@
 //! This module defines the CLI structure using `clap`, including all command-line
 //! arguments, options, and their associated enums for output formats and counting modes.
 
-use clap::{Parser, ValueEnum};
-use std::path::PathBuf;
+use crate::counter::{BillingRegion, Count, CountOptions, Metric, Normalization, WordPattern};
+use crate::discovery::DiscoveryOptions;
+use crate::output::{FormatOptions, OutputFormatter, Rate};
+use crate::preset::{Preset, Profile};
+use crate::report::Limits;
+use crate::terms::{KeywordTarget, Terms};
+use chrono::NaiveDate;
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
 
 /// Command-line arguments for the typst-count tool.
 ///
@@ -13,24 +23,110 @@ use std::path::PathBuf;
 /// and various filtering and limiting options.
 #[derive(Parser)]
 #[command(name = "typst-count")]
-#[command(version, about = "Count words and characters in Typst documents")]
+#[command(version = version(), about = "Count words and characters in Typst documents")]
 #[command(long_about = "Count words and characters in Typst documents.\n\n\
                   Counts are based on the compiled document, meaning only rendered \
                   text is counted. Code, markup, headers, and footers are excluded.")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+#[command(group = ArgGroup::new("baseline_source").args(["baseline", "baseline_report"]))]
 pub struct Cli {
+    /// Report to produce instead of counting.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Path(s) to Typst document(s) to count.
     ///
     /// Multiple files can be specified to get counts for each file plus totals.
-    /// Path(s) to Typst document(s)
-    #[arg(required = true, value_name = "FILE")]
+    /// Directories are searched for `.typ`, `.md`, and `.txt` files, which
+    /// are then subtotalled by file type. If omitted, the groups defined in
+    /// the configuration file are counted.
+    #[arg(value_name = "FILE")]
     pub input: Vec<PathBuf>,
 
+    /// Print the JSON Schema of the `--format json` output and exit.
+    ///
+    /// The schema is versioned, so consumers can validate reports and pin
+    /// against a specific version of the format.
+    #[arg(long, exclusive = true)]
+    pub schema: bool,
+
+    /// Configuration file defining named groups of inputs.
+    ///
+    /// Defaults to `typst-count.toml` in the working directory. Groups are
+    /// only counted when no input files are given.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Count the input files as of a git revision instead of the working tree.
+    ///
+    /// Accepts a branch, tag, or commit SHA. The files are read from the
+    /// repository without checking anything out.
+    #[arg(
+        long,
+        value_name = "REV",
+        requires = "input",
+        conflicts_with_all = ["audit", "find_duplicates"]
+    )]
+    pub rev: Option<String>,
+
+    /// Count the text of PDFs exported from Typst, in addition to the inputs.
+    ///
+    /// For when only the artifact is at hand, e.g. to verify that a submitted
+    /// file matches its declared count. The text is extracted with
+    /// `pdftotext` from Poppler, which must be installed. Headers, footers,
+    /// and page numbers on the pages are counted as well.
+    #[arg(
+        long,
+        value_name = "PDF",
+        conflicts_with_all = ["rev", "audit", "find_duplicates"]
+    )]
+    pub from_pdf: Vec<PathBuf>,
+
+    /// Count plain text and Markdown files, in addition to the inputs.
+    ///
+    /// Notes and other companion files are counted with the same word
+    /// rules and listed with the documents. Markdown syntax is removed from
+    /// `.md` files before counting.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "FILE",
+        conflicts_with_all = ["rev", "audit", "find_duplicates"]
+    )]
+    pub include_plain: Vec<PathBuf>,
+
+    /// Skip files and directories matching a pattern when searching input directories.
+    ///
+    /// Patterns are relative to the searched directory, e.g. `drafts/**`.
+    /// Hidden directories such as `.git`, `target`, and vendored Typst
+    /// packages are always skipped. Adds to `exclude` in the configuration
+    /// file.
+    #[arg(long, value_delimiter = ',', value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+
+    /// Count only the Typst files matching a pattern when searching input directories.
+    ///
+    /// By default, the Typst files that no other file includes or imports,
+    /// and those with `#set document(..)`, are counted, so chapters aren't
+    /// counted twice. Adds to `entry-points` in the configuration file.
+    #[arg(long = "entry-point", value_delimiter = ',', value_name = "PATTERN")]
+    pub entry_points: Vec<String>,
+
+    /// Count files included by several inputs only once.
+    ///
+    /// A shared file such as a glossary is counted in the first input that
+    /// includes it and left out of the others, so the total isn't inflated.
+    /// The table flags the inputs whose count leaves out shared words.
+    #[arg(long, conflicts_with = "rev")]
+    pub dedupe_shared: bool,
+
     /// Output format for results.
     ///
     /// Available formats:
     /// - `human`: Human-readable table format (default)
     /// - `json`: JSON format for machine processing
     /// - `csv`: CSV format for spreadsheet import
+    /// - `gitlab-metrics`: GitLab metrics report (`metrics.txt`)
     #[arg(short = 'f', long, value_enum, default_value_t = OutputFormat::Human)]
     pub format: OutputFormat,
 
@@ -45,10 +141,59 @@ pub struct Cli {
     /// Write output to a file instead of stdout.
     ///
     /// If not specified, output is written to stdout. The file format is
-    /// determined by the `--format` option.
+    /// determined by the `--format` option. An `https://` URL uploads the
+    /// report with a PUT request, and `s3://BUCKET/KEY` uploads it to S3
+    /// with the AWS credentials of the environment.
     #[arg(short = 'o', long = "output", value_name = "FILE")]
     pub output: Option<PathBuf>,
 
+    /// Append timestamped rows to the output file instead of overwriting it.
+    ///
+    /// Turns the CSV output into a progress log: the header is only written
+    /// when the file is new, and every row starts with the time of the run.
+    /// Requires `--output` and `--format csv`.
+    #[arg(long, requires = "output")]
+    pub append: bool,
+
+    /// Report errors as a JSON error object in place of the JSON report.
+    ///
+    /// Diagnostics always go to stderr, so with `--format json` the output
+    /// is valid JSON even when the run fails. Requires `--format json`.
+    #[arg(long)]
+    pub json_errors: bool,
+
+    /// Count in this process even if a daemon is running.
+    ///
+    /// By default, the counting is delegated to a running `typst-count
+    /// daemon`, which keeps fonts and caches warm between runs.
+    #[arg(long)]
+    pub no_daemon: bool,
+
+    /// Keep counting, recounting input files as they or their includes change.
+    ///
+    /// The table is redrawn in place; other formats write a report per
+    /// count, with the watching status on stderr. Quoted glob patterns among
+    /// the inputs, e.g. `"chapters/*.typ"`, pick up files created while
+    /// watching.
+    #[arg(long, conflicts_with_all = ["rev", "append", "output"])]
+    pub watch: bool,
+
+    /// Run a shell command after each count, e.g. to send a notification.
+    ///
+    /// `{json}` is replaced by the JSON report, quoted for the shell, and
+    /// `{words}` by the total word count. In watch mode, the command runs
+    /// after every recount.
+    #[arg(long, value_name = "COMMAND")]
+    pub exec: Option<String>,
+
+    /// Post the JSON report to this URL after each count, e.g. a chat bot.
+    ///
+    /// If `TYPST_COUNT_WEBHOOK_SECRET` is set, the report is signed with it
+    /// in the `X-Typst-Count-Signature-256` header. In watch mode, the
+    /// report is posted after every recount.
+    #[arg(long, value_name = "URL")]
+    pub webhook: Option<String>,
+
     /// Display mode when processing multiple files.
     ///
     /// - `auto`: Detailed for multiple files, simple for single file (default)
@@ -58,12 +203,243 @@ pub struct Cli {
     #[arg(short = 'd', long = "display", value_enum, default_value_t = DisplayMode::Auto)]
     pub display: DisplayMode,
 
-    /// Exclude content from imported/included files.
+    /// When to color table rows by the status of their limits.
     ///
-    /// By default, text from all imported and included files is counted.
-    /// Use this flag to count only the main file(s) specified on the command line.
-    #[arg(short = 'e', long = "exclude-imports")]
-    pub exclude_imports: bool,
+    /// Rows within their limits are green, rows above 90% of a maximum
+    /// yellow, and rows violating a limit red. The total is checked against
+    /// the limits given on the command line, and configured groups against
+    /// their own. `auto` colors the table when writing to a terminal, unless
+    /// `NO_COLOR` is set.
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto, value_name = "WHEN")]
+    pub color: ColorChoice,
+
+    /// Width of the table in columns (default: the terminal's width).
+    ///
+    /// Tables wider than this show only the file and word columns, so they
+    /// don't wrap in narrow terminals and CI log panes. When not writing to
+    /// a terminal, the full table is shown unless a width is given.
+    #[arg(long, value_name = "N")]
+    pub width: Option<usize>,
+
+    /// How file paths are shown in the table.
+    ///
+    /// - `full`: Paths as given on the command line
+    /// - `relative`: Paths relative to the working directory (default)
+    /// - `filename`: File names only
+    /// - `truncate`: Relative paths, shortened from the start to fit the
+    ///   width of the table
+    #[arg(long, value_enum, default_value_t = PathStyle::Relative, value_name = "STYLE")]
+    pub path_style: PathStyle,
+
+    /// End the output with a one-line verdict of the run.
+    ///
+    /// Human output gets a final line such as `2 files, 18,342 words, 1
+    /// limit violation, 0 errors`; JSON objects get a `summary` field with
+    /// the same numbers.
+    #[arg(long)]
+    pub summary: bool,
+
+    /// Show each file's share of the total word count.
+    ///
+    /// Adds a percentage column to the multi-file table and CSV output.
+    #[arg(long)]
+    pub percent: bool,
+
+    /// Estimate the cost of the text at this price per word or character.
+    ///
+    /// Adds a cost column to the table, JSON, and CSV output, e.g. for
+    /// translation or editing quotes. The unit is set with `--rate-unit`.
+    #[arg(long, value_name = "AMOUNT")]
+    pub rate: Option<f64>,
+
+    /// Unit the `--rate` is quoted per.
+    #[arg(long, value_enum, default_value_t = RateUnit::Word, requires = "rate")]
+    pub rate_unit: RateUnit,
+
+    /// Hide files with fewer than N words from the table and CSV rows.
+    ///
+    /// Hidden files are still included in the total.
+    #[arg(long, value_name = "N")]
+    pub min_row_words: Option<usize>,
+
+    /// Show only the N files with the most words in the table and CSV rows.
+    ///
+    /// Rows are sorted by word count. Hidden files are still included in
+    /// the total.
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// Aggregate files by directory with subtotals.
+    ///
+    /// Applies to the multi-file table and JSON output, e.g. to see the
+    /// totals of `chapters/` and `appendices/` separately.
+    #[arg(long)]
+    pub group_by_dir: bool,
+
+    /// Aggregate files by file type with subtotals.
+    ///
+    /// Applies to the multi-file table and JSON output, e.g. to see the
+    /// totals of the Typst and Markdown files of a project separately. On by
+    /// default when counting a directory.
+    #[arg(long, conflicts_with = "group_by_dir")]
+    pub group_by_type: bool,
+
+    /// Add run metadata to JSON and CSV output.
+    ///
+    /// Adds the time of the run, the git commit and branch, and the versions
+    /// of typst-count and the Typst compiler, so archived reports are
+    /// self-describing.
+    #[arg(long)]
+    pub with_metadata: bool,
+
+    /// Produce byte-stable output for committing reports to git.
+    ///
+    /// Files are sorted by name and timestamps are left out, so repeated
+    /// runs over unchanged documents give identical output.
+    #[arg(long, conflicts_with = "append")]
+    pub deterministic: bool,
+
+    /// Add the title and authors the documents declare to the output.
+    ///
+    /// Reads `#set document(title: .., author: ..)` from each input file.
+    /// The table labels files by their title, and JSON and CSV output gain
+    /// `title` and `authors` fields.
+    #[arg(long, conflicts_with = "rev")]
+    pub with_doc_metadata: bool,
+
+    /// Never write text extracted from the documents, only numbers.
+    ///
+    /// For compliance-sensitive manuscripts: options that list document
+    /// text, such as `--find-duplicates`, `--audit`, and `--spellcheck`, are
+    /// rejected, and `--bundle` leaves out the text of the files.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "find_duplicates",
+            "audit",
+            "explain",
+            "with_doc_metadata",
+            "spellcheck",
+        ]
+    )]
+    pub no_text: bool,
+
+    /// Content of the documents to leave out of the count.
+    #[command(flatten)]
+    pub exclusions: ExclusionArgs,
+
+    /// Count content with this label or element as notes, e.g. `<endnote>`.
+    ///
+    /// For the `note-words` and `body-words` metrics, which cover footnotes
+    /// by default. Use it for the endnotes or margin notes of templates and
+    /// packages. Marked like `--exclude-marked`.
+    #[arg(long, value_delimiter = ',', value_name = "MARKER")]
+    pub note_marker: Vec<String>,
+
+    /// Count discouraged variants of preferred terms from a TOML file.
+    ///
+    /// Each key is a preferred term and maps to a list of its variants, e.g.
+    /// `email = ["e-mail"]`. Enables the `discouraged-terms` metric, and
+    /// lists the variants found in each file on stderr. Lowercase variants
+    /// match regardless of case.
+    #[arg(long, value_name = "FILE", value_parser = parse_terms)]
+    pub terms: Option<Terms>,
+
+    /// Count documents whose fonts are not installed with a fallback font.
+    ///
+    /// Text in missing font families is laid out with Typst's default font,
+    /// or the first font found, and a warning names the families. Counts rarely
+    /// depend on the exact face, but page-based metrics may shift.
+    #[arg(long)]
+    pub font_fallback: bool,
+
+    /// Count documents whose images or data files are missing.
+    ///
+    /// Missing images are replaced by empty ones and missing data files by
+    /// empty data, and a warning names the files, e.g. when Git LFS assets
+    /// were not pulled. Text generated from the data is not counted.
+    #[arg(long)]
+    pub allow_missing_assets: bool,
+
+    /// Count the main file even if files it imports fail to compile.
+    ///
+    /// Imported and included files with errors are skipped as if they were
+    /// empty, and a warning names them and their errors, e.g. while a
+    /// shared style file is broken mid-refactor. Errors in the main file,
+    /// including those caused by skipping an import, still fail the count.
+    #[arg(long)]
+    pub lenient_imports: bool,
+
+    /// Count documents that fail to compile from their source text.
+    ///
+    /// The text of their markup is counted instead, following includes, and
+    /// the count is marked as approximate in the report, so one broken file
+    /// doesn't fail a whole multi-file report. The summary counts these
+    /// files as errors.
+    #[arg(long)]
+    pub approximate_failed: bool,
+
+    /// List sentences that occur more than once across the input files.
+    ///
+    /// The list is written to stderr so it doesn't interfere with the
+    /// formatted counts. Sentences shorter than five words are ignored.
+    #[arg(long)]
+    pub find_duplicates: bool,
+
+    /// Report how the count was derived, to check it for double-counting.
+    ///
+    /// Lists the text skipped as styling, and elements whose text is also
+    /// counted as part of an enclosing element. The report is written to
+    /// stderr so it doesn't interfere with the formatted counts.
+    #[arg(long)]
+    pub audit: bool,
+
+    /// When a limit is violated, list the largest files and sections.
+    ///
+    /// Shows where the words are, so it's clear where to cut. The list is
+    /// written to stderr after the violations.
+    #[arg(long, conflicts_with = "rev")]
+    pub explain: bool,
+
+    /// Report additional metrics alongside words and characters.
+    ///
+    /// Can be repeated or given as a comma-separated list.
+    #[arg(
+        long = "metric",
+        value_enum,
+        value_delimiter = ',',
+        value_name = "METRIC"
+    )]
+    pub metrics: Vec<Metric>,
+
+    /// Words per page for the `manuscript-pages` metric (default: 250).
+    ///
+    /// Enables the metric. Publishers and agents estimate the length of a
+    /// standard manuscript page differently, commonly 250 to 300 words.
+    #[arg(long, value_name = "N")]
+    pub words_per_page: Option<usize>,
+
+    /// Count billable units the way translation agencies of a region do.
+    ///
+    /// Enables the `billable-units` metric, in which Chinese, Japanese, and
+    /// Korean characters count one unit each. Other text counts one unit per
+    /// word for `zh` and per character for `ja` and `ko`.
+    #[arg(long, value_enum, value_name = "REGION")]
+    pub billing_region: Option<BillingRegion>,
+
+    /// Bill this many units per word of non-CJK text, whatever the region.
+    ///
+    /// Enables the `billable-units` metric.
+    #[arg(long, value_name = "N")]
+    pub latin_word_units: Option<usize>,
+
+    /// Label marking the abstract (default: `abstract`).
+    ///
+    /// The abstract is the content carrying this label. If the label is on a
+    /// heading, or no label is used and a heading reads "Abstract", the
+    /// section below that heading is the abstract.
+    #[arg(long, value_name = "LABEL")]
+    pub abstract_label: Option<String>,
 
     /// Exit with error if word count exceeds this limit.
     ///
@@ -92,12 +468,998 @@ pub struct Cli {
     /// Exit code will be 1 if the count is below the limit.
     #[arg(long, value_name = "N")]
     pub min_characters: Option<usize>,
+
+    /// Exit with error if the abstract's word count exceeds this limit.
+    ///
+    /// Enables the `abstract-words` metric. The abstract is located as
+    /// described for `--abstract-label`.
+    #[arg(long, value_name = "N")]
+    pub max_abstract_words: Option<usize>,
+
+    /// Exit with error if more than this percentage of words is quoted.
+    ///
+    /// Enables the `quoted-words` metric. Some institutions use the share of
+    /// quoted material as a guideline alongside plagiarism checks.
+    #[arg(long, value_name = "PERCENT")]
+    pub max_quote_percent: Option<f64>,
+
+    /// Exit with error if the reference list has fewer entries than this.
+    ///
+    /// Enables the `references` metric, e.g. for coursework rubrics that
+    /// require a minimum number of sources.
+    #[arg(long, value_name = "N")]
+    pub min_references: Option<usize>,
+
+    /// Exit with error if the document has more figures than this.
+    ///
+    /// Enables the `figures` metric. Tables and listings in a `figure`
+    /// count as figures.
+    #[arg(long, value_name = "N")]
+    pub max_figures: Option<usize>,
+
+    /// Exit with error if there are more figures per 1000 words than this.
+    ///
+    /// Enables the `figures` metric, for venues that cap the number of
+    /// floats relative to the length of the text.
+    #[arg(long, value_name = "N")]
+    pub max_figure_density: Option<f64>,
+
+    /// Exit with error if discouraged terms occur more often than this.
+    ///
+    /// Counts the variants of the terms given with `--terms`; `0` rejects
+    /// any of them.
+    #[arg(long, value_name = "N", requires = "terms")]
+    pub max_discouraged_terms: Option<usize>,
+
+    /// Exit with error if a keyword's density is outside a range.
+    ///
+    /// Given as `KEYWORD=MIN..MAX%`, e.g. `"machine learning=1..3%"`, in
+    /// occurrences per 100 words; either bound may be left out. Keywords
+    /// match as whole words, and regardless of case if written in
+    /// lowercase. Can be repeated.
+    #[arg(long, value_name = "KEYWORD=MIN..MAX%")]
+    pub keyword: Vec<KeywordTarget>,
+
+    /// Use only the word lists of this language from the config file.
+    ///
+    /// Stopwords and excluded words don't count towards the words a
+    /// keyword density is computed from. Defaults to the lists of all
+    /// languages.
+    #[arg(long, value_name = "LANG", requires = "keyword")]
+    pub word_lists: Option<String>,
+
+    /// Spell-check the counted text with this Hunspell dictionary.
+    ///
+    /// A dictionary name such as `en_US`, or the path of a `.dic` file
+    /// without its extension. Lists the misspelled words with their source
+    /// locations on stderr. Needs `hunspell` to be installed.
+    #[arg(long, value_name = "DICT")]
+    pub spellcheck: Option<String>,
+
+    /// Exit with error if the spell-check finds more typos than this.
+    #[arg(long, value_name = "N", requires = "spellcheck")]
+    pub max_typos: Option<usize>,
+
+    /// Git revision to compare against for the delta limits.
+    ///
+    /// The input files are counted as of this revision, e.g. the target
+    /// branch of a pull request. Files that don't exist at the revision
+    /// count as empty.
+    #[arg(long, value_name = "REV", requires = "input")]
+    pub baseline: Option<String>,
+
+    /// JSON report to compare against for the delta limits.
+    ///
+    /// For example the report of the last accepted version, generated with
+    /// `--format json`.
+    #[arg(long, value_name = "REPORT")]
+    pub baseline_report: Option<PathBuf>,
+
+    /// Exit with error if the word count grew by less than this since the baseline.
+    ///
+    /// Enforces progress, e.g. that every pull request adds at least 200
+    /// words. A negative value allows the count to shrink by up to that many
+    /// words. Requires `--baseline` or `--baseline-report`.
+    #[arg(
+        long,
+        value_name = "N",
+        allow_negative_numbers = true,
+        requires = "baseline_source"
+    )]
+    pub min_delta_words: Option<i64>,
+
+    /// Exit with error if the word count grew by more than this since the baseline.
+    ///
+    /// Use 0 to forbid growth. Requires `--baseline` or `--baseline-report`.
+    #[arg(
+        long,
+        value_name = "N",
+        allow_negative_numbers = true,
+        requires = "baseline_source"
+    )]
+    pub max_delta_words: Option<i64>,
+
+    /// Exit with error if the abstract grew by more than this since the baseline.
+    ///
+    /// Enables the `abstract-words` metric; a baseline report must include
+    /// it as well. Requires `--baseline` or `--baseline-report`.
+    #[arg(
+        long,
+        value_name = "N",
+        allow_negative_numbers = true,
+        requires = "baseline_source"
+    )]
+    pub max_delta_abstract_words: Option<i64>,
+
+    /// Check the limits declared inside the documents.
+    ///
+    /// Limits are declared next to the part they govern with a labelled
+    /// metadata element, e.g. `#metadata((max-words: 300)) <limit-abstract>`
+    /// for the part labelled `abstract`, or the section below a heading
+    /// reading "Abstract".
+    #[arg(long, conflicts_with = "rev")]
+    pub document_limits: bool,
+
+    /// Write limit violations to a SARIF file.
+    ///
+    /// Code scanning tools such as GitHub's show the violations inline at
+    /// the offending files. The file is written even if all limits are met,
+    /// so stale results are cleared.
+    #[arg(long, value_name = "FILE")]
+    pub sarif: Option<PathBuf>,
+
+    /// Write a bundle of the run's artifacts into a directory.
+    ///
+    /// The bundle holds the JSON report (`report.json`), the counted text of
+    /// every file (below `text/`), and the diagnostics of the run
+    /// (`diagnostics.log`), so a failed CI job can be investigated from its
+    /// artifacts without running it again.
+    #[arg(long, value_name = "DIR")]
+    pub bundle: Option<PathBuf>,
+
+    /// Record the count of every file with the current Typst compiler.
+    ///
+    /// Writes a JSON report with the compiler version, to check the counts
+    /// with `--verify-report` after upgrading Typst.
+    #[arg(long, value_name = "FILE", conflicts_with = "verify_report")]
+    pub pin_report: Option<PathBuf>,
+
+    /// Exit with error if the count of a file shifted since `--pin-report`.
+    ///
+    /// Flags the documents whose word or character count changed, e.g. since
+    /// the output of a different Typst version is counted differently.
+    #[arg(long, value_name = "FILE")]
+    pub verify_report: Option<PathBuf>,
+
+    /// Percentage by which counts may shift for `--verify-report`.
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 0.0,
+        requires = "verify_report"
+    )]
+    pub pin_tolerance: f64,
+}
+
+impl Cli {
+    /// Builds the options for searching input directories.
+    #[must_use]
+    pub fn discovery_options(&self) -> DiscoveryOptions {
+        DiscoveryOptions {
+            exclude: self.exclude.clone(),
+            entry_points: self.entry_points.clone(),
+        }
+    }
+
+    /// Checks whether any files to count were given, as opposed to counting
+    /// the configured groups.
+    #[must_use]
+    pub fn has_inputs(&self) -> bool {
+        !self.input.is_empty() || !self.from_pdf.is_empty() || !self.include_plain.is_empty()
+    }
+
+    /// Applies the selected preset's exclusions and default limits.
+    ///
+    /// Exclusion flags are only ever turned on, and limits are only filled in
+    /// where none was given on the command line.
+    pub fn apply_preset(&mut self) {
+        let Some(preset) = self.exclusions.preset else {
+            return;
+        };
+        let profile = preset.profile();
+
+        let exclusions = &mut self.exclusions;
+        exclusions.exclude_captions |= profile.exclude_captions;
+        exclusions.exclude_footnotes |= profile.exclude_footnotes;
+        exclusions.exclude_bibliography |= profile.exclude_bibliography;
+        self.max_words = self.max_words.or(profile.max_words);
+        self.min_words = self.min_words.or(profile.min_words);
+    }
+
+    /// Returns the counting options selected by these arguments.
+    #[must_use]
+    pub fn count_options(&self) -> CountOptions {
+        let mut metrics = self.metrics.clone();
+        if (self.max_abstract_words.is_some()
+            || self.max_delta_abstract_words.is_some()
+            || self.abstract_label.is_some())
+            && !metrics.contains(&Metric::AbstractWords)
+        {
+            metrics.push(Metric::AbstractWords);
+        }
+        if self.max_quote_percent.is_some() && !metrics.contains(&Metric::QuotedWords) {
+            metrics.push(Metric::QuotedWords);
+        }
+        if self.words_per_page.is_some() && !metrics.contains(&Metric::ManuscriptPages) {
+            metrics.push(Metric::ManuscriptPages);
+        }
+        if (self.billing_region.is_some()
+            || self.latin_word_units.is_some()
+            || (self.rate.is_some() && self.rate_unit == RateUnit::Unit))
+            && !metrics.contains(&Metric::BillableUnits)
+        {
+            metrics.push(Metric::BillableUnits);
+        }
+        if self.min_references.is_some() && !metrics.contains(&Metric::References) {
+            metrics.push(Metric::References);
+        }
+        if (self.max_figures.is_some() || self.max_figure_density.is_some())
+            && !metrics.contains(&Metric::Figures)
+        {
+            metrics.push(Metric::Figures);
+        }
+        if self.terms.is_some() && !metrics.contains(&Metric::DiscouragedTerms) {
+            metrics.push(Metric::DiscouragedTerms);
+        }
+        // Densities are computed from the occurrences they are based on.
+        for base in metrics.clone().iter().filter_map(|m| m.density_of()) {
+            if !metrics.contains(&base) {
+                metrics.push(base);
+            }
+        }
+
+        CountOptions {
+            note_markers: self.note_marker.clone(),
+            abstract_label: self.abstract_label.clone(),
+            metrics,
+            words_per_page: self.words_per_page,
+            billing_region: self.billing_region.unwrap_or_default(),
+            latin_word_units: self.latin_word_units,
+            font_fallback: self.font_fallback,
+            allow_missing_assets: self.allow_missing_assets,
+            lenient_imports: self.lenient_imports,
+            approximate_failed: self.approximate_failed,
+            terms: self.terms.clone().unwrap_or_default(),
+            ..self.exclusions.count_options()
+        }
+    }
+
+    /// Returns the output options selected by these arguments.
+    #[must_use]
+    pub fn format_options(&self) -> FormatOptions {
+        FormatOptions {
+            percent: self.percent,
+            rate: self.rate.map(|amount| Rate {
+                amount,
+                unit: self.rate_unit,
+            }),
+            min_row_words: self.min_row_words,
+            top: self.top,
+            group_by_dir: self.group_by_dir,
+            group_by_type: self.group_by_type
+                || (!self.group_by_dir && self.input.iter().any(|path| path.is_dir())),
+            timestamp: None,
+            metadata: None,
+            documents: HashMap::new(),
+            shared_words: HashMap::new(),
+            color: match self.color {
+                ColorChoice::Always => true,
+                ColorChoice::Never => false,
+                ColorChoice::Auto => {
+                    self.output.is_none()
+                        && std::env::var_os("NO_COLOR").is_none()
+                        && std::io::stdout().is_terminal()
+                }
+            },
+            row_limits: HashMap::new(),
+            total_limits: self.limits(),
+            path_style: self.path_style,
+            width: self.width.or_else(|| {
+                if self.output.is_none() && std::io::stdout().is_terminal() {
+                    terminal_width()
+                } else {
+                    None
+                }
+            }),
+            summary: None,
+        }
+    }
+
+    /// Returns the report `--format json` would write for the results, as
+    /// handed to `--exec` and `--webhook`.
+    #[must_use]
+    pub fn json_report(&self, results: &[(String, Count)]) -> String {
+        OutputFormatter::new(OutputFormat::Json, self.mode)
+            .with_options(self.format_options())
+            .format_output(results, self.display)
+    }
+
+    /// Returns the word and character limits given on the command line.
+    #[must_use]
+    pub const fn limits(&self) -> Limits {
+        Limits {
+            max_words: self.max_words,
+            min_words: self.min_words,
+            max_characters: self.max_characters,
+            min_characters: self.min_characters,
+        }
+    }
+}
+
+/// Returns the width of the terminal attached to stdout, or the `COLUMNS`
+/// environment variable if the terminal doesn't report it.
+fn terminal_width() -> Option<usize> {
+    #[cfg(unix)]
+    if let Ok(size) = rustix::termios::tcgetwinsize(std::io::stdout())
+        && size.ws_col > 0
+    {
+        return Some(usize::from(size.ws_col));
+    }
+    std::env::var("COLUMNS").ok()?.parse().ok()
+}
+
+/// Reads the terms file given with `--terms`.
+fn parse_terms(path: &str) -> Result<Terms, String> {
+    Terms::load(Path::new(path)).map_err(|e| format!("{e:#}"))
+}
+
+/// Parses the phrase lengths given with `--ngrams`, e.g. `2` or `2..3`.
+fn parse_ngrams(value: &str) -> Result<RangeInclusive<usize>, String> {
+    let (min, max) = value.split_once("..").unwrap_or((value, value));
+    let parse = |n: &str| match n.trim().parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("expected a positive number of words, found `{n}`")),
+    };
+    let (min, max) = (parse(min)?, parse(max)?);
+    if min > max {
+        return Err(format!("empty range `{value}`"));
+    }
+    Ok(min..=max)
+}
+
+/// Returns the version shown by `--version`, including the version of the
+/// Typst compiler, since counts can differ between compiler versions.
+fn version() -> String {
+    format!(
+        "{} (typst {})",
+        env!("CARGO_PKG_VERSION"),
+        crate::compiler_version()
+    )
+}
+
+/// Reports that analyze documents instead of counting them.
+#[derive(Subcommand)]
+pub enum Command {
+    /// List all-caps acronyms with their number of occurrences.
+    ///
+    /// Also reports whether each acronym appears to be defined on first use,
+    /// e.g. "Application Programming Interface (API)".
+    Acronyms(DocumentArgs),
+
+    /// Print the heading outline with per-section and cumulative word counts.
+    ///
+    /// Shows how the words are distributed across the document's structure,
+    /// including each section's share of the total.
+    Outline(DocumentArgs),
+
+    /// Suggest which sections to trim to reach a target word count.
+    ///
+    /// Trims the longest sections first, down to a common length, as an
+    /// editorial aid for getting under a word limit.
+    Suggest(SuggestArgs),
+
+    /// Show the words written by each author declared in the documents.
+    ///
+    /// Sections are attributed with `#metadata("Name") <author>`, as done by
+    /// collaborative templates. Unlike git blame, this follows the declared
+    /// authorship of the text rather than who last edited it.
+    Authors(DocumentArgs),
+
+    /// Print the counted text, one element per line.
+    ///
+    /// Shows exactly what contributes to the count, e.g. to check the effect
+    /// of exclusions or to find text that is counted twice.
+    Extract(ExtractArgs),
+
+    /// Print every occurrence of a term in its context, and the occurrences
+    /// per section.
+    ///
+    /// Searches the counted text for the term as a whole word, regardless
+    /// of case, e.g. to check that a term is used consistently.
+    Kwic(KwicArgs),
+
+    /// List the most frequent words, or with `--ngrams` phrases.
+    ///
+    /// Repeated phrases such as "as mentioned above" point to crutch
+    /// phrases. Words are compared regardless of case, and phrases end at
+    /// sentence boundaries and punctuation such as commas.
+    Frequency(FrequencyArgs),
+
+    /// Write the counted text in reading order, section by section.
+    ///
+    /// Hands other tools, such as text-to-speech, grammar, or plagiarism
+    /// checkers, the same prose that is counted, without Typst markup.
+    Export(ExportArgs),
+
+    /// Check grammar and style with an external checker.
+    ///
+    /// Sends the counted text to the checker and lists its findings at the
+    /// place in the sources they come from. Exits with 1 if there are any.
+    Lint(LintArgs),
+
+    /// Work with previously generated JSON reports.
+    Report(ReportArgs),
+
+    /// Show how the word count developed over the git history.
+    ///
+    /// Counts the documents at every commit that changed their directory,
+    /// reading the files from the repository without checking anything out.
+    Trend(TrendArgs),
+
+    /// Show the progress towards the milestones of the configuration file.
+    ///
+    /// Each milestone is a word-count target with a due date. Milestones
+    /// that are past due are checked against the count at their date in the
+    /// git history.
+    Milestones(MilestonesArgs),
+
+    /// Check whether today's writing met a daily word goal.
+    ///
+    /// Compares the current count with the last run recorded before today in
+    /// a progress log written with `--format csv --append`. Exits with code 1
+    /// if the goal was missed, e.g. to notify from a cron job.
+    Daily(DailyArgs),
+
+    /// Run a timed writing sprint and summarize the words written.
+    ///
+    /// Recounts the documents whenever one of the files they were compiled
+    /// from changes and prints the progress to stderr. When the time is up, prints the
+    /// words written during the sprint and the pace.
+    Sprint(SprintArgs),
+
+    /// Keep fonts and compiler caches warm for repeated runs.
+    ///
+    /// Serves counting requests over a Unix socket until stopped. While the
+    /// daemon is listening, typst-count delegates the counting to it, so
+    /// runs from build systems finish almost instantly for unchanged
+    /// documents. Only available on Unix.
+    Daemon(DaemonArgs),
+
+    /// Write a signed certificate of the documents' word counts.
+    ///
+    /// States the count of each document, the SHA-256 digests of the files
+    /// it was compiled from, the counting options, and the versions of
+    /// typst-count and Typst, signed with a minisign key, e.g. to attach to
+    /// a submission with a declared word count. Needs `minisign`.
+    Certify(CertifyArgs),
+
+    /// Check that the word counts of a certificate still hold.
+    ///
+    /// Verifies the certificate's signature, compares the digests of the
+    /// files the documents were compiled from, and recounts the documents
+    /// with the certified options. Run it from the directory the
+    /// certificate was made in. Exits with code 1 if a count changed. Needs
+    /// `minisign`.
+    VerifyCert(VerifyCertArgs),
+}
+
+/// Arguments of the `extract` subcommand.
+#[derive(Args)]
+pub struct ExtractArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Prefix each line with the source file, line, and column of its text.
+    #[arg(long)]
+    pub locations: bool,
+}
+
+/// Arguments of the `kwic` subcommand.
+#[derive(Args)]
+pub struct KwicArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Term to search for, e.g. `hypothesis`.
+    #[arg(short = 't', long, value_name = "TERM")]
+    pub term: String,
+
+    /// Characters of context shown on either side of each occurrence.
+    #[arg(long, default_value_t = 40, value_name = "N")]
+    pub context: usize,
+}
+
+/// Arguments of the `frequency` subcommand.
+#[derive(Args)]
+pub struct FrequencyArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Number of words per phrase, or a range such as `2..3`.
+    #[arg(long, default_value = "1", value_name = "N[..M]", value_parser = parse_ngrams)]
+    pub ngrams: RangeInclusive<usize>,
+
+    /// Leave out words and phrases that occur fewer times than this.
+    #[arg(long, default_value_t = 2, value_name = "N")]
+    pub min_occurrences: usize,
+
+    /// Number of words and phrases to list, most frequent first.
+    #[arg(long, default_value_t = 20, value_name = "N")]
+    pub top: usize,
+
+    /// Read the word lists from this file instead of typst-count.toml.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Use only the word lists of this language from the config file.
+    ///
+    /// Stopwords, and words on exclude lists, are left out of the list.
+    /// Defaults to the lists of all languages.
+    #[arg(long, value_name = "LANG")]
+    pub word_lists: Option<String>,
+}
+
+/// Arguments of the `export` subcommand.
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Format of the exported text.
+    #[arg(short = 'f', long, value_enum, default_value_t = ExportFormat::Markdown)]
+    pub format: ExportFormat,
+}
+
+/// Arguments of the `lint` subcommand.
+#[derive(Args)]
+pub struct LintArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Checker to use and its URL, e.g.
+    /// `languagetool-server http://localhost:8081`.
+    ///
+    /// `languagetool-server` is any server with the LanguageTool HTTP API,
+    /// such as a local LanguageTool or `https://api.languagetool.org`.
+    #[arg(long, required = true, num_args = 2, value_names = ["CHECKER", "URL"])]
+    pub via: Vec<String>,
+
+    /// Language of the text, e.g. `en-US`, or `auto` to detect it.
+    #[arg(short = 'l', long, default_value = "auto", value_name = "CODE")]
+    pub language: String,
+}
+
+/// Arguments of the `suggest` subcommand.
+#[derive(Args)]
+pub struct SuggestArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Word count to reach.
+    #[arg(long, value_name = "N")]
+    pub target: usize,
+}
+
+/// Arguments of the `trend` subcommand.
+#[derive(Args)]
+pub struct TrendArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Only include commits after this date, e.g. "3 months ago" or "2026-01-01".
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+
+    /// Print a one-line sparkline instead of a table.
+    #[arg(long)]
+    pub sparkline: bool,
+}
+
+/// Arguments of the `milestones` subcommand.
+#[derive(Args)]
+pub struct MilestonesArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Read the milestones from this file instead of typst-count.toml.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Show the progress as of this date instead of today, as YYYY-MM-DD.
+    #[arg(long, value_name = "DATE")]
+    pub today: Option<NaiveDate>,
+}
+
+/// Arguments of the `daily` subcommand.
+#[derive(Args)]
+pub struct DailyArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Number of words to write per day.
+    #[arg(long, value_name = "N")]
+    pub goal: usize,
+
+    /// Progress log to compare with, as written by `--append`.
+    #[arg(long, value_name = "FILE")]
+    pub log: PathBuf,
+}
+
+/// Arguments of the `daemon` subcommand.
+#[derive(Args)]
+pub struct DaemonArgs {
+    /// Socket to listen on (default: `$TYPST_COUNT_SOCKET`, or
+    /// `typst-count.sock` in `$XDG_RUNTIME_DIR`).
+    ///
+    /// Clients find a daemon on another socket with `TYPST_COUNT_SOCKET`.
+    #[arg(long, value_name = "PATH")]
+    pub socket: Option<PathBuf>,
+}
+
+/// Arguments of the `sprint` subcommand.
+#[derive(Args)]
+pub struct SprintArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Length of the sprint in minutes.
+    #[arg(long, default_value_t = 25, value_name = "N")]
+    pub minutes: u64,
+
+    /// Seconds between checks for changed files.
+    #[arg(long, default_value_t = 5, value_name = "SECONDS")]
+    pub interval: u64,
+}
+
+/// Arguments of the `certify` subcommand.
+#[derive(Args)]
+pub struct CertifyArgs {
+    /// Documents and exclusions.
+    #[command(flatten)]
+    pub document: DocumentArgs,
+
+    /// Minisign secret key to sign with (default: minisign's own default).
+    #[arg(short = 's', long, value_name = "FILE")]
+    pub secret_key: Option<PathBuf>,
+}
+
+/// Arguments of the `verify-cert` subcommand.
+#[derive(Args)]
+pub struct VerifyCertArgs {
+    /// Certificate written by `certify`
+    #[arg(value_name = "CERTIFICATE")]
+    pub certificate: PathBuf,
+
+    /// Minisign public key to verify with (default: minisign's own default).
+    #[arg(short = 'p', long, value_name = "FILE")]
+    pub public_key: Option<PathBuf>,
+
+    /// Only compare the digests of the sources instead of recounting.
+    ///
+    /// Faster and needs no fonts, but fails on any change to the sources,
+    /// even one that leaves the count as it was.
+    #[arg(long)]
+    pub hashes_only: bool,
+
+    /// Write output to a file instead of stdout.
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+/// Arguments of the `report` subcommand.
+#[derive(Args)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct ReportArgs {
+    /// What to do with the reports.
+    #[command(subcommand)]
+    pub command: Option<ReportCommand>,
+
+    /// Summary of a change, produced when no operation is given.
+    #[command(flatten)]
+    pub summary: SummaryArgs,
+}
+
+/// Arguments for summarizing the change between two JSON reports.
+#[derive(Args)]
+pub struct SummaryArgs {
+    /// Format of the summary.
+    #[arg(short = 'f', long, value_enum, required = true)]
+    pub format: Option<ReportFormat>,
+
+    /// JSON report of the changed documents
+    #[arg(required = true, value_name = "REPORT")]
+    pub report: Option<PathBuf>,
+
+    /// JSON report to compare against, e.g. of the target branch.
+    #[arg(long, value_name = "REPORT")]
+    pub baseline: Option<PathBuf>,
+
+    /// Maximum word count, shown with its status.
+    #[arg(long, value_name = "N")]
+    pub max_words: Option<usize>,
+
+    /// Minimum word count, shown with its status.
+    #[arg(long, value_name = "N")]
+    pub min_words: Option<usize>,
+
+    /// Maximum character count, shown with its status.
+    #[arg(long, value_name = "N")]
+    pub max_characters: Option<usize>,
+
+    /// Minimum character count, shown with its status.
+    #[arg(long, value_name = "N")]
+    pub min_characters: Option<usize>,
+
+    /// Write output to a file instead of stdout.
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+impl SummaryArgs {
+    /// Returns the limits whose status is shown in the summary.
+    #[must_use]
+    pub const fn limits(&self) -> Limits {
+        Limits {
+            max_words: self.max_words,
+            min_words: self.min_words,
+            max_characters: self.max_characters,
+            min_characters: self.min_characters,
+        }
+    }
+}
+
+/// Format of a report summary.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug)]
+pub enum ReportFormat {
+    /// Markdown comment for a pull request, to be posted by a bot.
+    ///
+    /// Shows the counts before and after the change, the differences, and
+    /// the status of the given limits.
+    PrComment,
+}
+
+/// Operations on JSON reports.
+#[derive(Subcommand)]
+pub enum ReportCommand {
+    /// Combine several JSON reports into one aggregated report.
+    ///
+    /// Each input report becomes one row of the output, e.g. one per CI
+    /// shard or repository, followed by the combined total.
+    Merge(MergeArgs),
+}
+
+/// Arguments of the `report merge` subcommand.
+#[derive(Args)]
+pub struct MergeArgs {
+    /// JSON reports generated with `--format json`
+    #[arg(required = true, value_name = "REPORT")]
+    pub reports: Vec<PathBuf>,
+
+    /// Output format.
+    #[arg(short = 'f', long, value_enum, default_value_t = OutputFormat::Human)]
+    pub format: OutputFormat,
+
+    /// What to show in the output.
+    #[arg(short = 'm', long = "mode", value_enum, default_value_t = CountMode::Both)]
+    pub mode: CountMode,
+
+    /// Show the per-report breakdown or only the total.
+    #[arg(short = 'd', long = "display", value_enum, default_value_t = DisplayMode::Auto)]
+    pub display: DisplayMode,
+
+    /// Write output to a file instead of stdout.
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+/// Documents to analyze and which of their content to include.
+///
+/// Shared by the subcommands, which see the same text that is counted.
+#[derive(Args)]
+pub struct DocumentArgs {
+    /// Path(s) to Typst document(s)
+    #[arg(required = true, value_name = "FILE")]
+    pub input: Vec<PathBuf>,
+
+    /// Content of the documents to leave out.
+    #[command(flatten)]
+    pub exclusions: ExclusionArgs,
+
+    /// Write output to a file instead of stdout.
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+impl DocumentArgs {
+    /// Returns the counting options selected by these arguments.
+    #[must_use]
+    pub fn count_options(&self) -> CountOptions {
+        self.exclusions.count_options()
+    }
+}
+
+/// Which content of the documents is counted.
+///
+/// Shared by counting and the subcommands, so that reports on the text
+/// explain the same count.
+#[derive(Args, Clone, Debug, Default)]
+pub struct ExclusionArgs {
+    /// Apply the counting rules and default limits of a journal or programme.
+    ///
+    /// Presets enable the exclusions a venue expects (e.g. references and
+    /// figure legends) and, when counting, supply default limits. Limits
+    /// given explicitly on the command line take precedence over the
+    /// preset's defaults.
+    #[arg(short = 'p', long, value_enum, value_name = "PRESET")]
+    pub preset: Option<Preset>,
+
+    /// Exclude content from imported/included files.
+    ///
+    /// By default, text from all imported and included files is counted.
+    /// Use this flag to count only the main file(s) specified on the command line.
+    #[arg(short = 'e', long = "exclude-imports")]
+    pub exclude_imports: bool,
+
+    /// Exclude figure and table captions from the count.
+    #[arg(long)]
+    pub exclude_captions: bool,
+
+    /// Exclude footnotes from the count.
+    #[arg(long)]
+    pub exclude_footnotes: bool,
+
+    /// Exclude the bibliography from the count.
+    #[arg(long)]
+    pub exclude_bibliography: bool,
+
+    /// Exclude placed content, such as floating figures and callout boxes.
+    ///
+    /// Skips everything positioned with `place`, which page budgets often
+    /// treat separately from the body text. The `float-words` metric reports
+    /// it instead.
+    #[arg(long)]
+    pub exclude_floats: bool,
+
+    /// Don't count raw URLs as words.
+    ///
+    /// Words starting with `http://`, `https://`, `www.`, etc. are skipped,
+    /// since a long URL shouldn't count as one giant word.
+    #[arg(long)]
+    pub exclude_urls: bool,
+
+    /// Don't count numbers and dates as words.
+    ///
+    /// Purely numeric words such as `42`, `3.14`, or `50%` and dates such
+    /// as `2026-03-01` are skipped, so that the count of a data-heavy report
+    /// means prose. The `numeric-words` and `date-words` metrics report them.
+    #[arg(long)]
+    pub exclude_numbers: bool,
+
+    /// Exclude text from the files of packages.
+    ///
+    /// Templates imported from `@preview` may add text of their own, such as
+    /// a title page or a declaration. Use the `package-words` metric to see
+    /// how much of the count comes from packages.
+    #[arg(long)]
+    pub exclude_packages: bool,
+
+    /// Exclude text generated by templates and show rules.
+    ///
+    /// Skips text that wasn't written in any source file, such as "Figure 1:"
+    /// supplements, "Chapter" prefixes, and page headers, which official
+    /// counts usually leave out.
+    #[arg(long)]
+    pub exclude_generated: bool,
+
+    /// Count the text of page headers and footers.
+    ///
+    /// Running headers and footers repeat on every page and are left out of
+    /// the count by default. The `header-footer-words` metric reports them
+    /// separately.
+    #[arg(long)]
+    pub include_chrome: bool,
+
+    /// Count the entries of the table of contents and other outlines.
+    ///
+    /// Outline entries repeat the headings they list, so they are left out
+    /// of the count by default.
+    #[arg(long)]
+    pub include_outline: bool,
+
+    /// Exclude numbering generated by counters.
+    ///
+    /// Skips heading numbers, list markers, and figure numbering such as
+    /// "1.2.3" or "Figure 4", which aren't authored prose.
+    #[arg(long)]
+    pub exclude_numbering: bool,
+
+    /// Don't count content marked with this label or element, e.g. `<todo>`.
+    ///
+    /// Skips work-in-progress passages and everything nested inside them.
+    /// Values in angle brackets only match labels; bare names match labels
+    /// and elements such as `strike`. Can be repeated or given as a
+    /// comma-separated list.
+    #[arg(long, value_delimiter = ',', value_name = "MARKER")]
+    pub exclude_marked: Vec<String>,
+
+    /// Treat these elements as styling wrappers whose text is already counted.
+    ///
+    /// For wrappers defined by templates or packages that cause text to be
+    /// counted twice; `--audit` shows candidates. Can be repeated or given as
+    /// a comma-separated list of element names.
+    #[arg(long = "styling-element", value_delimiter = ',', value_name = "NAME")]
+    pub styling_elements: Vec<String>,
+
+    /// Normalize layout artifacts in the text before counting.
+    ///
+    /// `soft-hyphens` joins words split by a soft hyphen at a line break,
+    /// and `spaces` turns non-breaking, narrow, and zero-width spaces into
+    /// plain ones. Can be repeated or given as a comma-separated list.
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "PASS")]
+    pub normalize: Vec<Normalization>,
+
+    /// Count every match of this regular expression as a word.
+    ///
+    /// Replaces splitting at whitespace everywhere words are counted. For
+    /// example, `[\w']+` counts the parts of hyphenated compounds as two
+    /// words, and `\p{L}+` leaves out numbers.
+    #[arg(long, value_name = "REGEX")]
+    pub word_pattern: Option<WordPattern>,
+}
+
+impl ExclusionArgs {
+    /// Returns the counting options selected by these arguments, including
+    /// the exclusions of the selected preset.
+    #[must_use]
+    pub fn count_options(&self) -> CountOptions {
+        let profile = self.preset.map(Preset::profile);
+        let preset = |exclude: fn(Profile) -> bool| profile.is_some_and(exclude);
+        CountOptions {
+            exclude_imports: self.exclude_imports,
+            exclude_captions: self.exclude_captions || preset(|p| p.exclude_captions),
+            exclude_footnotes: self.exclude_footnotes || preset(|p| p.exclude_footnotes),
+            exclude_bibliography: self.exclude_bibliography || preset(|p| p.exclude_bibliography),
+            exclude_floats: self.exclude_floats,
+            exclude_urls: self.exclude_urls,
+            exclude_numbers: self.exclude_numbers,
+            exclude_packages: self.exclude_packages,
+            exclude_generated: self.exclude_generated,
+            include_chrome: self.include_chrome,
+            include_outline: self.include_outline,
+            exclude_numbering: self.exclude_numbering,
+            exclude_marked: self.exclude_marked.clone(),
+            styling_elements: self.styling_elements.clone(),
+            normalize: self.normalize.clone(),
+            word_pattern: self.word_pattern.clone(),
+            ..CountOptions::default()
+        }
+    }
 }
 
 /// Output format for displaying count results.
 ///
 /// Determines how the word and character counts are formatted and presented.
-#[derive(Clone, Copy, ValueEnum, Debug)]
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug)]
 pub enum OutputFormat {
     /// Human-readable table format (default).
     ///
@@ -113,6 +1475,33 @@ pub enum OutputFormat {
     /// Outputs results in comma-separated values format, suitable for
     /// importing into spreadsheet applications or data analysis tools.
     Csv,
+    /// GitLab metrics report for merge requests.
+    ///
+    /// Outputs one `name value` line per count, e.g. `words 12345`, which
+    /// GitLab shows in the merge request widget when the file is uploaded
+    /// as a `metrics` report artifact.
+    GitlabMetrics,
+}
+
+/// Format of the text written by the `export` subcommand.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    /// Plain text, as in the chapters of an EPUB, with a blank line between
+    /// paragraphs and two before each heading.
+    EpubText,
+    /// Markdown, with headings marked by their level (default).
+    Markdown,
+}
+
+/// When to use colors in the output.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug)]
+pub enum ColorChoice {
+    /// Use colors when writing to a terminal (default).
+    Auto,
+    /// Always use colors.
+    Always,
+    /// Never use colors.
+    Never,
 }
 
 /// What to count in the document.
@@ -132,6 +1521,31 @@ pub enum CountMode {
     Characters,
 }
 
+/// Unit of the price given with `--rate`.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug)]
+pub enum RateUnit {
+    /// Price per word (default).
+    Word,
+    /// Price per character, including spaces.
+    Character,
+    /// Price per billable unit, see `--billing-region`.
+    Unit,
+}
+
+/// How file paths are shown in the table.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug, Default)]
+pub enum PathStyle {
+    /// Paths as given on the command line.
+    Full,
+    /// Paths relative to the working directory (default).
+    #[default]
+    Relative,
+    /// File names only.
+    Filename,
+    /// Relative paths, shortened from the start to fit the table's width.
+    Truncate,
+}
+
 /// Display mode for formatting output when processing multiple files.
 ///
 /// Controls how detailed the output should be and how results are presented.
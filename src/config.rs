@@ -0,0 +1,888 @@
+//! Project configuration loaded from `typst-count.toml`.
+//!
+//! The configuration file lets a project describe its manuscript structure
+//! once instead of repeating it on every invocation. Named groups collect
+//! input files by glob pattern and may carry their own limits:
+//!
+//! ```toml
+//! [groups]
+//! frontmatter = ["abstract.typ", "preface.typ"]
+//! body = { inputs = ["chapters/ch*.typ"], max-words = 80000 }
+//! ```
+//!
+//! Composite scores weigh the counts against each other, e.g. for venues
+//! that convert figures into word equivalents, and may carry limits:
+//!
+//! ```toml
+//! [scores]
+//! budget = { formula = "words + 250 * figures", max = 8000 }
+//! ```
+//!
+//! Files that input directories shouldn't pick up are excluded by pattern,
+//! and the documents among their Typst files can be listed if detecting
+//! them goes wrong:
+//!
+//! ```toml
+//! exclude = ["drafts/**", "vendor/**"]
+//! entry-points = ["thesis.typ", "slides/*.typ"]
+//! ```
+//!
+//! Progress logs written with `--append` can name files by a hash of their
+//! name and hold only totals (see [`progress`](crate::progress)):
+//!
+//! ```toml
+//! [progress]
+//! private = true
+//! secret = "correct horse battery staple"
+//! ```
+//!
+//! Stopwords and word lists for the frequency and density reports are read
+//! from files per language (see [`wordlists`](crate::wordlists)):
+//!
+//! ```toml
+//! [word-lists.en]
+//! stopwords = "lists/stopwords-en.txt"
+//! exclude = ["lists/names.txt"]
+//! ```
+//!
+//! Dated word-count targets are listed as milestones:
+//!
+//! ```toml
+//! [milestones]
+//! draft = { date = 2026-03-01, words = 20000 }
+//! full = { date = 2026-06-30, words = 80000 }
+//! ```
+
+use crate::counter::{Count, Metric};
+use crate::report::Limits;
+use crate::wordlists::WordFilter;
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// File name of the configuration file looked up in the working directory.
+pub const CONFIG_FILE: &str = "typst-count.toml";
+
+/// Settings read from a configuration file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    /// Directory containing the configuration file; patterns are relative to it
+    pub base_dir: PathBuf,
+    /// Named groups of input files, in the order they appear in the file
+    pub groups: Vec<Group>,
+    /// Composite scores with limits, in the order they appear in the file
+    pub scores: Vec<Score>,
+    /// Dated word-count targets, in the order they appear in the file
+    pub milestones: Vec<Milestone>,
+    /// Patterns of files and directories skipped when searching input
+    /// directories
+    pub exclude: Vec<String>,
+    /// Patterns of the Typst files counted when searching input directories,
+    /// instead of detecting the entry points
+    pub entry_points: Vec<String>,
+    /// Word list files per language, in the order they appear in the file
+    pub word_lists: Vec<WordLists>,
+    /// Write progress logs with hashed file names and only totals
+    pub private_progress: bool,
+    /// Secret the file names of private progress logs are hashed with
+    pub progress_secret: Option<String>,
+}
+
+/// A named group of input files with optional group-level limits.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Group {
+    /// Name of the group, shown in place of a file name
+    pub name: String,
+    /// Glob patterns selecting the group's files
+    pub inputs: Vec<String>,
+    /// Maximum allowed word count for the group
+    pub max_words: Option<usize>,
+    /// Minimum required word count for the group
+    pub min_words: Option<usize>,
+    /// Maximum allowed character count for the group
+    pub max_characters: Option<usize>,
+    /// Minimum required character count for the group
+    pub min_characters: Option<usize>,
+}
+
+/// A weighted sum of counts and metrics with optional limits.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Score {
+    /// Name of the score, shown in limit violations
+    pub name: String,
+    /// Weighted terms of the sum: a factor and `words`, `characters`, or the
+    /// key of a metric, e.g. `figures`
+    pub terms: Vec<(f64, String)>,
+    /// Maximum allowed value of the score
+    pub max: Option<f64>,
+    /// Minimum required value of the score
+    pub min: Option<f64>,
+}
+
+/// The word list files of a language.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordLists {
+    /// Language the lists are for, e.g. `en`
+    pub language: String,
+    /// Files listing stopwords
+    pub stopwords: Vec<String>,
+    /// Files listing words that are always kept
+    pub include: Vec<String>,
+    /// Files listing words that are never counted
+    pub exclude: Vec<String>,
+}
+
+/// A word-count target to reach by a date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Milestone {
+    /// Name of the milestone, e.g. "draft"
+    pub name: String,
+    /// Date by which the target should be reached
+    pub date: NaiveDate,
+    /// Number of words to reach
+    pub words: usize,
+}
+
+impl Config {
+    /// Loads the configuration from `path`, or from [`CONFIG_FILE`] in the
+    /// working directory if no path is given.
+    ///
+    /// Returns `Ok(None)` if no path is given and there is no configuration
+    /// file in the working directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid
+    /// configuration.
+    pub fn load(path: Option<&Path>) -> Result<Option<Self>> {
+        let path = match path {
+            Some(path) => path,
+            None if Path::new(CONFIG_FILE).is_file() => Path::new(CONFIG_FILE),
+            None => return Ok(None),
+        };
+
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let mut config = Self::parse(&text)
+            .with_context(|| format!("Invalid config file: {}", path.display()))?;
+        config.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(Some(config))
+    }
+
+    /// Parses a configuration from TOML text.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not valid TOML or contains unknown
+    /// keys or values of the wrong type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use typst_count::config::Config;
+    ///
+    /// let config = Config::parse("[groups]\nbody = [\"ch*.typ\"]").unwrap();
+    /// assert_eq!(config.groups[0].name, "body");
+    /// ```
+    pub fn parse(text: &str) -> Result<Self> {
+        let table: Table = text.parse()?;
+        let mut config = Self::default();
+
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("groups", Value::Table(groups)) => {
+                    for (name, value) in groups {
+                        config.groups.push(Group::parse(name, value)?);
+                    }
+                }
+                ("groups", _) => bail!("`groups` must be a table"),
+                ("scores", Value::Table(scores)) => {
+                    for (name, value) in scores {
+                        config.scores.push(Score::parse(name, value)?);
+                    }
+                }
+                ("scores", _) => bail!("`scores` must be a table"),
+                ("milestones", Value::Table(milestones)) => {
+                    for (name, value) in milestones {
+                        config.milestones.push(Milestone::parse(name, value)?);
+                    }
+                }
+                ("milestones", _) => bail!("`milestones` must be a table"),
+                ("word-lists", Value::Table(languages)) => {
+                    for (language, value) in languages {
+                        config.word_lists.push(WordLists::parse(language, value)?);
+                    }
+                }
+                ("word-lists", _) => bail!("`word-lists` must be a table"),
+                ("progress", Value::Table(progress)) => {
+                    for (key, value) in progress {
+                        match (key.as_str(), value) {
+                            ("private", Value::Boolean(private)) => {
+                                config.private_progress = private;
+                            }
+                            ("private", _) => bail!("`private` of `progress` must be a boolean"),
+                            ("secret", Value::String(secret)) => {
+                                config.progress_secret = Some(secret);
+                            }
+                            ("secret", _) => bail!("`secret` of `progress` must be a string"),
+                            (key, _) => bail!("Unknown key `{key}` in `progress`"),
+                        }
+                    }
+                }
+                ("progress", _) => bail!("`progress` must be a table"),
+                ("exclude", value) => config.exclude = path_patterns("exclude", value)?,
+                ("entry-points", value) => {
+                    config.entry_points = path_patterns("entry-points", value)?;
+                }
+                (key, _) => bail!("Unknown key `{key}`"),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Loads the word lists of a language, or of all languages if none is
+    /// given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no lists are configured for the language, or a
+    /// file cannot be read.
+    pub fn word_filter(&self, language: Option<&str>) -> Result<WordFilter> {
+        let mut filter = WordFilter::default();
+        let mut found = false;
+        for lists in &self.word_lists {
+            if language.is_some_and(|language| language != lists.language) {
+                continue;
+            }
+            found = true;
+            for (files, words) in [
+                (&lists.stopwords, &mut filter.stopwords),
+                (&lists.include, &mut filter.include),
+                (&lists.exclude, &mut filter.exclude),
+            ] {
+                for file in files {
+                    WordFilter::read_list(words, &self.base_dir.join(file))?;
+                }
+            }
+        }
+        if let Some(language) = language
+            && !found
+        {
+            bail!("No word lists configured for language `{language}`");
+        }
+        Ok(filter)
+    }
+}
+
+impl Group {
+    /// Returns the group-level limits.
+    #[must_use]
+    pub const fn limits(&self) -> Limits {
+        Limits {
+            max_words: self.max_words,
+            min_words: self.min_words,
+            max_characters: self.max_characters,
+            min_characters: self.min_characters,
+        }
+    }
+
+    /// Parses a group from either a list of patterns or a table with
+    /// `inputs` and limits.
+    fn parse(name: String, value: Value) -> Result<Self> {
+        let mut group = Self {
+            name,
+            ..Self::default()
+        };
+
+        let table = match value {
+            Value::Array(inputs) => {
+                Table::from_iter([("inputs".to_string(), Value::Array(inputs))])
+            }
+            Value::Table(table) => table,
+            _ => bail!("Group `{}` must be a list or a table", group.name),
+        };
+
+        for (key, value) in table {
+            let name = &group.name;
+            match key.as_str() {
+                "inputs" => group.inputs = patterns(name, value)?,
+                "max-words" => group.max_words = Some(limit(name, &key, &value)?),
+                "min-words" => group.min_words = Some(limit(name, &key, &value)?),
+                "max-characters" => group.max_characters = Some(limit(name, &key, &value)?),
+                "min-characters" => group.min_characters = Some(limit(name, &key, &value)?),
+                _ => bail!("Unknown key `{key}` in group `{name}`"),
+            }
+        }
+
+        if group.inputs.is_empty() {
+            bail!("Group `{}` has no inputs", group.name);
+        }
+        Ok(group)
+    }
+
+    /// Expands the group's patterns into file paths.
+    ///
+    /// Relative patterns are resolved against `base_dir`. Matches of each
+    /// pattern are sorted, and files matched by several patterns are only
+    /// listed once.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern is invalid or matches no files.
+    pub fn files(&self, base_dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for pattern in &self.inputs {
+            let full = base_dir.join(pattern);
+            let mut matches = glob::glob(&full.to_string_lossy())
+                .with_context(|| format!("Invalid pattern in group `{}`: {pattern}", self.name))?
+                .collect::<Result<Vec<_>, _>>()?;
+            if matches.is_empty() {
+                bail!(
+                    "Pattern in group `{}` matches no files: {pattern}",
+                    self.name
+                );
+            }
+            matches.sort();
+            for path in matches {
+                if !files.contains(&path) {
+                    files.push(path);
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// Checks the group's total against its limits.
+    ///
+    /// Returns a message for each violated limit.
+    #[must_use]
+    pub fn check_limits(&self, total: &Count) -> Vec<String> {
+        let mut errors = Vec::new();
+        let name = &self.name;
+
+        if let Some(max) = self.max_words
+            && total.words > max
+        {
+            errors.push(format!(
+                "Group `{name}` word count exceeds maximum ({} > {max})",
+                total.words
+            ));
+        }
+        if let Some(min) = self.min_words
+            && total.words < min
+        {
+            errors.push(format!(
+                "Group `{name}` word count below minimum ({} < {min})",
+                total.words
+            ));
+        }
+        if let Some(max) = self.max_characters
+            && total.characters > max
+        {
+            errors.push(format!(
+                "Group `{name}` character count exceeds maximum ({} > {max})",
+                total.characters
+            ));
+        }
+        if let Some(min) = self.min_characters
+            && total.characters < min
+        {
+            errors.push(format!(
+                "Group `{name}` character count below minimum ({} < {min})",
+                total.characters
+            ));
+        }
+
+        errors
+    }
+}
+
+impl Score {
+    /// Parses a score from a formula or a table with `formula` and limits.
+    fn parse(name: String, value: Value) -> Result<Self> {
+        let table = match value {
+            Value::String(formula) => {
+                Table::from_iter([("formula".to_string(), Value::String(formula))])
+            }
+            Value::Table(table) => table,
+            _ => bail!("Score `{name}` must be a formula or a table"),
+        };
+
+        let mut score = Self {
+            name,
+            ..Self::default()
+        };
+        for (key, value) in table {
+            let name = &score.name;
+            let number = || {
+                value
+                    .as_float()
+                    .or_else(|| value.as_integer().map(|n| n as f64))
+                    .with_context(|| format!("`{key}` of score `{name}` must be a number"))
+            };
+            match key.as_str() {
+                "formula" => {
+                    let formula = value
+                        .as_str()
+                        .with_context(|| format!("Formula of score `{name}` must be a string"))?;
+                    score.terms = parse_formula(formula)
+                        .with_context(|| format!("Invalid formula of score `{name}`"))?;
+                }
+                "max" => score.max = Some(number()?),
+                "min" => score.min = Some(number()?),
+                _ => bail!("Unknown key `{key}` in score `{name}`"),
+            }
+        }
+
+        if score.terms.is_empty() {
+            bail!("Score `{}` has no formula", score.name);
+        }
+        Ok(score)
+    }
+
+    /// Returns the metrics the score is computed from.
+    #[must_use]
+    pub fn metrics(&self) -> Vec<Metric> {
+        self.terms
+            .iter()
+            .filter_map(|(_, name)| Metric::ALL.into_iter().find(|m| m.key() == name))
+            .collect()
+    }
+
+    /// Computes the score of a count.
+    ///
+    /// Metrics that weren't computed for the count contribute nothing.
+    #[must_use]
+    pub fn value(&self, count: &Count) -> f64 {
+        self.terms
+            .iter()
+            .map(|(factor, name)| {
+                let value = match name.as_str() {
+                    "words" => count.words,
+                    "characters" => count.characters,
+                    name => Metric::ALL
+                        .into_iter()
+                        .find(|m| m.key() == name)
+                        .and_then(|metric| count.metrics.get(metric))
+                        .unwrap_or(0),
+                };
+                factor * value as f64
+            })
+            .sum()
+    }
+
+    /// Checks the score of a count against its limits.
+    ///
+    /// Returns one message per violated limit.
+    #[must_use]
+    pub fn check_limits(&self, count: &Count) -> Vec<String> {
+        let value = self.value(count);
+        let name = &self.name;
+        let mut errors = Vec::new();
+        if let Some(max) = self.max
+            && value > max
+        {
+            errors.push(format!("Score `{name}` exceeds maximum ({value} > {max})"));
+        }
+        if let Some(min) = self.min
+            && value < min
+        {
+            errors.push(format!("Score `{name}` below minimum ({value} < {min})"));
+        }
+        errors
+    }
+}
+
+/// Parses a formula such as `words + 250 * figures` into weighted terms.
+///
+/// A formula is a sum of terms, each the product of numbers and exactly one
+/// of `words`, `characters`, or the key of a metric.
+fn parse_formula(formula: &str) -> Result<Vec<(f64, String)>> {
+    formula
+        .split('+')
+        .map(|term| {
+            let mut factor = 1.0;
+            let mut name = None;
+            for part in term.split('*').map(str::trim) {
+                if let Ok(number) = part.parse::<f64>() {
+                    factor *= number;
+                } else if part == "words"
+                    || part == "characters"
+                    || Metric::ALL.iter().any(|m| m.key() == part)
+                {
+                    if name.replace(part.to_string()).is_some() {
+                        bail!("Term `{}` multiplies several counts", term.trim());
+                    }
+                } else {
+                    bail!("Unknown count or metric `{part}`");
+                }
+            }
+            let name = name.with_context(|| format!("Term `{}` has no count", term.trim()))?;
+            Ok((factor, name))
+        })
+        .collect()
+}
+
+impl Milestone {
+    /// Parses a milestone from a table with `date` and `words`.
+    ///
+    /// The date may be a TOML date or a `YYYY-MM-DD` string.
+    fn parse(name: String, value: Value) -> Result<Self> {
+        let Value::Table(table) = value else {
+            bail!("Milestone `{name}` must be a table");
+        };
+
+        let (mut date, mut words) = (None, None);
+        for (key, value) in table {
+            match key.as_str() {
+                "date" => {
+                    let text = match value {
+                        Value::Datetime(datetime) => datetime.to_string(),
+                        Value::String(text) => text,
+                        _ => bail!("`date` of milestone `{name}` must be a date"),
+                    };
+                    date = Some(NaiveDate::parse_from_str(&text, "%Y-%m-%d").with_context(
+                        || format!("`date` of milestone `{name}` must have the form YYYY-MM-DD"),
+                    )?);
+                }
+                "words" => {
+                    words = Some(
+                        value
+                            .as_integer()
+                            .and_then(|n| usize::try_from(n).ok())
+                            .with_context(|| {
+                                format!(
+                                    "`words` of milestone `{name}` must be a non-negative integer"
+                                )
+                            })?,
+                    );
+                }
+                _ => bail!("Unknown key `{key}` in milestone `{name}`"),
+            }
+        }
+
+        let date = date.with_context(|| format!("Milestone `{name}` has no date"))?;
+        let words = words.with_context(|| format!("Milestone `{name}` has no word target"))?;
+        Ok(Self { name, date, words })
+    }
+}
+
+impl WordLists {
+    /// Parses the word lists of a language from a table of files.
+    fn parse(language: String, value: Value) -> Result<Self> {
+        let Value::Table(table) = value else {
+            bail!("Word lists `{language}` must be a table");
+        };
+
+        let mut lists = Self {
+            language,
+            ..Self::default()
+        };
+        for (key, value) in table {
+            let files = match value {
+                Value::String(file) => vec![Value::String(file)],
+                Value::Array(files) => files,
+                _ => bail!(
+                    "`{key}` of word lists `{}` must be a file or a list of files",
+                    lists.language
+                ),
+            };
+            let files = files
+                .into_iter()
+                .map(|file| match file {
+                    Value::String(file) => Ok(file),
+                    _ => bail!("Files of word lists `{}` must be strings", lists.language),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            match key.as_str() {
+                "stopwords" => lists.stopwords = files,
+                "include" => lists.include = files,
+                "exclude" => lists.exclude = files,
+                _ => bail!("Unknown key `{key}` in word lists `{}`", lists.language),
+            }
+        }
+        Ok(lists)
+    }
+}
+
+/// Converts a TOML array into a list of patterns.
+fn patterns(group: &str, value: Value) -> Result<Vec<String>> {
+    let Value::Array(values) = value else {
+        bail!("`inputs` of group `{group}` must be a list");
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::String(pattern) => Ok(pattern),
+            _ => bail!("Inputs of group `{group}` must be strings"),
+        })
+        .collect()
+}
+
+/// Reads a list of glob patterns for searching input directories.
+fn path_patterns(key: &str, value: Value) -> Result<Vec<String>> {
+    let Value::Array(values) = value else {
+        bail!("`{key}` must be a list");
+    };
+    values
+        .into_iter()
+        .map(|value| {
+            let Value::String(pattern) = value else {
+                bail!("Patterns in `{key}` must be strings");
+            };
+            glob::Pattern::new(&pattern)
+                .with_context(|| format!("Invalid pattern in `{key}`: {pattern}"))?;
+            Ok(pattern)
+        })
+        .collect()
+}
+
+/// Reads a non-negative integer limit.
+fn limit(group: &str, key: &str, value: &Value) -> Result<usize> {
+    value
+        .as_integer()
+        .and_then(|n| usize::try_from(n).ok())
+        .with_context(|| format!("`{key}` of group `{group}` must be a non-negative integer"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_groups() {
+        let config = Config::parse(
+            r#"
+            [groups]
+            frontmatter = ["abstract.typ", "preface.typ"]
+            body = { inputs = ["ch*.typ"], max-words = 8000, min-words = 6000 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.groups,
+            [
+                Group {
+                    name: "frontmatter".to_string(),
+                    inputs: vec!["abstract.typ".to_string(), "preface.typ".to_string()],
+                    ..Group::default()
+                },
+                Group {
+                    name: "body".to_string(),
+                    inputs: vec!["ch*.typ".to_string()],
+                    max_words: Some(8000),
+                    min_words: Some(6000),
+                    ..Group::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_groups() {
+        assert!(Config::parse("[groups]\nbody = []").is_err());
+        assert!(Config::parse("[groups]\nbody = { inputs = [\"a.typ\"], max-pages = 3 }").is_err());
+        assert!(
+            Config::parse("[groups]\nbody = { inputs = [\"a.typ\"], max-words = -1 }").is_err()
+        );
+        assert!(Config::parse("[group]\nbody = [\"a.typ\"]").is_err());
+    }
+
+    #[test]
+    fn test_parse_exclude() {
+        let config = Config::parse(r#"exclude = ["drafts/**", "**/*.txt"]"#).unwrap();
+        assert_eq!(config.exclude, ["drafts/**", "**/*.txt"]);
+
+        assert!(Config::parse(r#"exclude = "drafts/**""#).is_err());
+        assert!(Config::parse(r#"exclude = ["["]"#).is_err());
+
+        let config = Config::parse(r#"entry-points = ["thesis.typ"]"#).unwrap();
+        assert_eq!(config.entry_points, ["thesis.typ"]);
+    }
+
+    #[test]
+    fn test_parse_progress() {
+        assert!(
+            Config::parse("[progress]\nprivate = true")
+                .unwrap()
+                .private_progress
+        );
+        assert!(!Config::parse("").unwrap().private_progress);
+        assert_eq!(
+            Config::parse("[progress]\nsecret = \"hunter2\"")
+                .unwrap()
+                .progress_secret
+                .as_deref(),
+            Some("hunter2")
+        );
+        assert!(Config::parse("[progress]\nsecret = 1").is_err());
+        assert!(Config::parse("[progress]\nprivate = \"yes\"").is_err());
+        assert!(Config::parse("[progress]\nencrypt = true").is_err());
+    }
+
+    #[test]
+    fn test_word_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("en.txt"), "the\nof\n").unwrap();
+        fs::write(dir.path().join("de.txt"), "der\n").unwrap();
+        fs::write(dir.path().join("names.txt"), "Smith\n").unwrap();
+        let mut config = Config::parse(
+            r#"
+            [word-lists.en]
+            stopwords = "en.txt"
+            exclude = ["names.txt"]
+            [word-lists.de]
+            stopwords = ["de.txt"]
+            "#,
+        )
+        .unwrap();
+        config.base_dir = dir.path().to_path_buf();
+
+        let english = config.word_filter(Some("en")).unwrap();
+        assert_eq!(english.stopwords.len(), 2);
+        assert!(english.exclude.contains("smith"));
+        assert_eq!(config.word_filter(None).unwrap().stopwords.len(), 3);
+        assert!(config.word_filter(Some("fr")).is_err());
+
+        assert!(Config::parse("[word-lists.en]\nstopwords = 1").is_err());
+        assert!(Config::parse("[word-lists.en]\nkeep = \"keep.txt\"").is_err());
+    }
+
+    #[test]
+    fn test_parse_milestones() {
+        let config = Config::parse(
+            r#"
+            [milestones]
+            draft = { date = 2026-03-01, words = 20000 }
+            full = { date = "2026-06-30", words = 80000 }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.milestones,
+            [
+                Milestone {
+                    name: "draft".to_string(),
+                    date: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+                    words: 20000,
+                },
+                Milestone {
+                    name: "full".to_string(),
+                    date: NaiveDate::from_ymd_opt(2026, 6, 30).unwrap(),
+                    words: 80000,
+                },
+            ]
+        );
+        assert!(
+            Config::parse(
+                "[milestones]
+draft = { date = 2026-03-01 }"
+            )
+            .is_err()
+        );
+        assert!(
+            Config::parse(
+                "[milestones]
+draft = { date = \"March\", words = 1 }"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_scores() {
+        let config = Config::parse(
+            r#"
+            [scores]
+            budget = { formula = "words + 250 * figures + display_equations * 0.5", max = 8000 }
+            "#,
+        )
+        .unwrap();
+        let score = &config.scores[0];
+
+        assert_eq!(
+            score.terms,
+            [
+                (1.0, "words".to_string()),
+                (250.0, "figures".to_string()),
+                (0.5, "display_equations".to_string()),
+            ]
+        );
+        assert_eq!(score.max, Some(8000.0));
+        assert_eq!(score.metrics(), [Metric::Figures, Metric::DisplayEquations]);
+        assert!(Config::parse("[scores]\nbudget = \"words + 2 * tables\"").is_err());
+        assert!(Config::parse("[scores]\nbudget = \"words * figures\"").is_err());
+        assert!(Config::parse("[scores]\nbudget = \"words + 100\"").is_err());
+    }
+
+    #[test]
+    fn test_score_check_limits() {
+        let score = Score {
+            name: "budget".to_string(),
+            terms: parse_formula("words + 250 * figures").unwrap(),
+            max: Some(8000.0),
+            min: None,
+        };
+        let mut count = Count {
+            words: 7000,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::Figures, 4);
+
+        assert_eq!(score.value(&count), 8000.0);
+        assert!(score.check_limits(&count).is_empty());
+
+        count.words = 7200;
+        assert_eq!(
+            score.check_limits(&count),
+            ["Score `budget` exceeds maximum (8200 > 8000)"]
+        );
+    }
+
+    #[test]
+    fn test_group_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["ch2.typ", "ch1.typ", "notes.typ"] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let group = Group {
+            name: "body".to_string(),
+            inputs: vec!["ch*.typ".to_string(), "ch1.typ".to_string()],
+            ..Group::default()
+        };
+
+        assert_eq!(
+            group.files(dir.path()).unwrap(),
+            [dir.path().join("ch1.typ"), dir.path().join("ch2.typ")]
+        );
+
+        let missing = Group {
+            inputs: vec!["appendix*.typ".to_string()],
+            ..group
+        };
+        assert!(missing.files(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_group_check_limits() {
+        let group = Group {
+            name: "body".to_string(),
+            max_words: Some(100),
+            ..Group::default()
+        };
+        let count = Count {
+            words: 150,
+            ..Count::default()
+        };
+
+        assert_eq!(
+            group.check_limits(&count),
+            ["Group `body` word count exceeds maximum (150 > 100)"]
+        );
+    }
+}
@@ -4,14 +4,28 @@
 //! Typst documents by traversing the document's element tree and extracting
 //! rendered text content.
 
-use typst::introspection::Introspector;
+use crate::terms::Terms;
+use clap::ValueEnum;
+use regex::Regex;
+use std::ops::{Add, AddAssign, Range, SubAssign};
+use std::str::FromStr;
+use typst::World;
+use typst::foundations::{Content, PlainText, Value};
+use typst::introspection::{CounterDisplayElem, Introspector};
+use typst::layout::{BoxElem, PlaceElem};
 use typst::math::EquationElem;
-use typst::model::{EmphElem, StrongElem};
-use typst::syntax::FileId;
-use typst::text::{OverlineElem, RawElem, StrikeElem, SubElem, SuperElem, UnderlineElem};
+use typst::model::{
+    BibliographyElem, CiteElem, EmphElem, EnumElem, FigureCaption, FigureElem, FootnoteElem,
+    FootnoteEntry, HeadingElem, LinkElem, ListElem, OutlineElem, OutlineEntry, QuoteElem,
+    StrongElem, TermsElem,
+};
+use typst::syntax::{FileId, LinkedNode, Span, SyntaxKind, SyntaxNode};
+use typst::text::{
+    OverlineElem, RawElem, SmallcapsElem, StrikeElem, SubElem, SuperElem, TextElem, UnderlineElem,
+};
 
 /// Result of counting words and characters in a document.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct Count {
     /// Total number of words in the document.
     ///
@@ -20,90 +34,2413 @@ pub struct Count {
     /// Chinese or Japanese where words are not separated by spaces.
     pub words: usize,
 
-    /// Total number of characters in the document.
-    ///
-    /// This includes all rendered characters including spaces and punctuation,
-    /// but excludes markup syntax that doesn't appear in the rendered output.
-    pub characters: usize,
+    /// Total number of characters in the document.
+    ///
+    /// This includes all rendered characters including spaces and punctuation,
+    /// but excludes markup syntax that doesn't appear in the rendered output.
+    pub characters: usize,
+
+    /// Additional metrics, present only when requested.
+    pub metrics: Metrics,
+
+    /// Whether the count is an estimate from the document's source text,
+    /// because the document failed to compile.
+    ///
+    /// A sum is approximate if any of its parts is.
+    pub approximate: bool,
+}
+
+impl Add for Count {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl AddAssign for Count {
+    fn add_assign(&mut self, other: Self) {
+        self.words += other.words;
+        self.characters += other.characters;
+        self.metrics += other.metrics;
+        self.metrics.update_densities(self.words);
+        self.approximate |= other.approximate;
+    }
+}
+
+impl SubAssign for Count {
+    /// Subtracts a part of a count, such as the count of one of its files,
+    /// saturating at zero.
+    fn sub_assign(&mut self, other: Self) {
+        self.words = self.words.saturating_sub(other.words);
+        self.characters = self.characters.saturating_sub(other.characters);
+        self.metrics -= other.metrics;
+        self.metrics.update_densities(self.words);
+    }
+}
+
+/// An additional metric that can be reported alongside words and characters.
+///
+/// Metrics are opt-in (`--metric`), so the default output stays unchanged.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug)]
+pub enum Metric {
+    /// Words in the abstract.
+    ///
+    /// The abstract is the content labelled `<abstract>` (see
+    /// `--abstract-label`), or the section under a heading titled "Abstract".
+    AbstractWords,
+    /// Words inside quotations.
+    ///
+    /// Covers `quote` elements as well as text between quotation marks.
+    QuotedWords,
+    /// Words inside bullet lists, numbered lists, and term lists.
+    ListWords,
+    /// Words outside of lists, i.e. flowing paragraphs, headings, etc.
+    ProseWords,
+    /// Words in footnotes and other notes.
+    ///
+    /// Endnotes and margin notes of templates and packages are included if
+    /// they carry one of the `--note-marker` labels or element names.
+    NoteWords,
+    /// Words outside of notes, i.e. the main text.
+    BodyWords,
+    /// Number of hyperlinks.
+    Links,
+    /// Number of comments in the source files.
+    ///
+    /// Like the metrics below, this is measured on the sources rather than
+    /// the compiled document, as an indicator of remaining work.
+    Comments,
+    /// Number of comments marking open work with `TODO`, `FIXME`, or `XXX`.
+    TodoComments,
+    /// Words inside comments, e.g. commented-out passages.
+    CommentedWords,
+    /// Entries in the reference list.
+    ///
+    /// These are the distinct works cited, or all entries of the
+    /// bibliography files if the bibliography is shown in `full`.
+    References,
+    /// Distinct works cited in the text.
+    CitedWorks,
+    /// Citations in the text, counting every occurrence.
+    Citations,
+    /// Citations per 1000 words, rounded.
+    CitationDensity,
+    /// Figures, including tables and listings placed in a `figure`.
+    Figures,
+    /// Figures per 1000 words, rounded.
+    FigureDensity,
+    /// Equations within a line of text, e.g. `$x^2$`.
+    InlineEquations,
+    /// Equations set apart on their own line, e.g. `$ x^2 $`.
+    DisplayEquations,
+    /// Inline equations per 1000 words, rounded.
+    InlineEquationDensity,
+    /// Display equations per 1000 words, rounded.
+    DisplayEquationDensity,
+    /// Estimated pages of a standard manuscript, i.e. words divided by the
+    /// words per page (250 by default), rounded up.
+    ///
+    /// Each document starts on a new page, as chapters do in a manuscript,
+    /// so the pages of several documents add up.
+    ManuscriptPages,
+    /// Units billed by translation agencies, see [`BillingRegion`].
+    ///
+    /// Chinese, Japanese, and Korean characters count one unit each, while
+    /// other text counts per word or per character depending on the region.
+    BillableUnits,
+    /// Words from the files of packages, e.g. text a `@preview` template
+    /// adds to every document.
+    PackageWords,
+    /// Words in page headers and footers, which are left out of the count
+    /// unless `include_chrome` is set.
+    HeaderFooterWords,
+    /// Words in placed content, e.g. floating figures, callout boxes, or
+    /// margin notes positioned with `place`.
+    FloatWords,
+    /// Words that are purely numeric, e.g. `42`, `3.14`, or `50%`.
+    NumericWords,
+    /// Words that are dates, e.g. `2026-03-01` or `01.03.2026`.
+    DateWords,
+    /// Characters with each run of whitespace counting as one, as web forms
+    /// and social media limits measure text.
+    CharactersCollapsed,
+    /// Milliseconds the document took to compile, to find slow chapters.
+    ///
+    /// Unlike the other metrics, this varies from run to run, and documents
+    /// read as text take none.
+    CompileMs,
+    /// Occurrences of the discouraged variants of the terms given with
+    /// `--terms`, e.g. "e-mail" where the style guide prefers "email".
+    DiscouragedTerms,
+}
+
+impl Metric {
+    /// All metrics, in the order they are reported.
+    pub const ALL: [Self; 30] = [
+        Self::AbstractWords,
+        Self::QuotedWords,
+        Self::ListWords,
+        Self::ProseWords,
+        Self::NoteWords,
+        Self::BodyWords,
+        Self::Links,
+        Self::Comments,
+        Self::TodoComments,
+        Self::CommentedWords,
+        Self::References,
+        Self::CitedWorks,
+        Self::Citations,
+        Self::CitationDensity,
+        Self::Figures,
+        Self::FigureDensity,
+        Self::InlineEquations,
+        Self::DisplayEquations,
+        Self::InlineEquationDensity,
+        Self::DisplayEquationDensity,
+        Self::ManuscriptPages,
+        Self::BillableUnits,
+        Self::PackageWords,
+        Self::HeaderFooterWords,
+        Self::FloatWords,
+        Self::NumericWords,
+        Self::DateWords,
+        Self::CharactersCollapsed,
+        Self::CompileMs,
+        Self::DiscouragedTerms,
+    ];
+
+    /// Machine-readable name, used as the JSON key and CSV column.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::AbstractWords => "abstract_words",
+            Self::QuotedWords => "quoted_words",
+            Self::ListWords => "list_words",
+            Self::ProseWords => "prose_words",
+            Self::NoteWords => "note_words",
+            Self::BodyWords => "body_words",
+            Self::Links => "links",
+            Self::Comments => "comments",
+            Self::TodoComments => "todo_comments",
+            Self::CommentedWords => "commented_words",
+            Self::References => "references",
+            Self::CitedWorks => "cited_works",
+            Self::Citations => "citations",
+            Self::CitationDensity => "citation_density",
+            Self::Figures => "figures",
+            Self::FigureDensity => "figure_density",
+            Self::InlineEquations => "inline_equations",
+            Self::DisplayEquations => "display_equations",
+            Self::InlineEquationDensity => "inline_equation_density",
+            Self::DisplayEquationDensity => "display_equation_density",
+            Self::ManuscriptPages => "manuscript_pages",
+            Self::BillableUnits => "billable_units",
+            Self::PackageWords => "package_words",
+            Self::HeaderFooterWords => "header_footer_words",
+            Self::FloatWords => "float_words",
+            Self::NumericWords => "numeric_words",
+            Self::DateWords => "date_words",
+            Self::CharactersCollapsed => "characters_collapsed",
+            Self::CompileMs => "compile_ms",
+            Self::DiscouragedTerms => "discouraged_terms",
+        }
+    }
+
+    /// Human-readable name, used in tables and labels.
+    #[must_use]
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::AbstractWords => "Abstract words",
+            Self::QuotedWords => "Quoted words",
+            Self::ListWords => "List words",
+            Self::ProseWords => "Prose words",
+            Self::NoteWords => "Note words",
+            Self::BodyWords => "Body words",
+            Self::Links => "Links",
+            Self::Comments => "Comments",
+            Self::TodoComments => "TODO comments",
+            Self::CommentedWords => "Commented words",
+            Self::References => "References",
+            Self::CitedWorks => "Cited works",
+            Self::Citations => "Citations",
+            Self::CitationDensity => "Citations/1000 words",
+            Self::Figures => "Figures",
+            Self::FigureDensity => "Figures/1000 words",
+            Self::InlineEquations => "Inline equations",
+            Self::DisplayEquations => "Display equations",
+            Self::InlineEquationDensity => "Inline eq./1000 words",
+            Self::DisplayEquationDensity => "Display eq./1000 words",
+            Self::ManuscriptPages => "Manuscript pages",
+            Self::BillableUnits => "Billable units",
+            Self::PackageWords => "Package words",
+            Self::HeaderFooterWords => "Header/footer words",
+            Self::FloatWords => "Float words",
+            Self::NumericWords => "Numeric words",
+            Self::DateWords => "Date words",
+            Self::CharactersCollapsed => "Characters (collapsed)",
+            Self::CompileMs => "Compile ms",
+            Self::DiscouragedTerms => "Discouraged terms",
+        }
+    }
+
+    /// For metrics given per 1000 words, returns the metric counting the
+    /// occurrences.
+    ///
+    /// Densities can't be summed, so they are recomputed from the counted
+    /// occurrences when counts are added, see [`Metrics::update_densities`].
+    #[must_use]
+    pub const fn density_of(self) -> Option<Self> {
+        match self {
+            Self::CitationDensity => Some(Self::Citations),
+            Self::FigureDensity => Some(Self::Figures),
+            Self::InlineEquationDensity => Some(Self::InlineEquations),
+            Self::DisplayEquationDensity => Some(Self::DisplayEquations),
+            _ => None,
+        }
+    }
+}
+
+/// Values of the additional metrics for a count.
+///
+/// A metric that wasn't requested has no value, which keeps it out of the
+/// output entirely (as opposed to reporting it as zero).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metrics([Option<usize>; Metric::ALL.len()]);
+
+impl Metrics {
+    /// Returns the value of a metric, if it was computed.
+    #[must_use]
+    pub const fn get(&self, metric: Metric) -> Option<usize> {
+        self.0[metric as usize]
+    }
+
+    /// Sets the value of a metric.
+    pub const fn set(&mut self, metric: Metric, value: usize) {
+        self.0[metric as usize] = Some(value);
+    }
+
+    /// Iterates over the computed metrics and their values.
+    pub fn iter(&self) -> impl Iterator<Item = (Metric, usize)> + '_ {
+        Metric::ALL
+            .into_iter()
+            .filter_map(|metric| self.get(metric).map(|value| (metric, value)))
+    }
+
+    /// Recomputes the metrics given per 1000 words from the occurrences they
+    /// are based on.
+    pub fn update_densities(&mut self, words: usize) {
+        for metric in Metric::ALL {
+            if let Some(base) = metric.density_of()
+                && self.get(metric).is_some()
+                && let Some(occurrences) = self.get(base)
+            {
+                self.set(metric, per_thousand(occurrences, words));
+            }
+        }
+    }
+
+    /// Returns `true` if no metric was computed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(Option::is_none)
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self([None; Metric::ALL.len()])
+    }
+}
+
+impl AddAssign for Metrics {
+    /// Sums metric values; a metric present on either side is present in the result.
+    fn add_assign(&mut self, other: Self) {
+        for (slot, value) in self.0.iter_mut().zip(other.0) {
+            if let Some(value) = value {
+                *slot = Some(slot.unwrap_or(0) + value);
+            }
+        }
+    }
+}
+
+impl SubAssign for Metrics {
+    /// Subtracts metric values, saturating at zero; only metrics present on
+    /// the left side are present in the result.
+    fn sub_assign(&mut self, other: Self) {
+        for (slot, value) in self.0.iter_mut().zip(other.0) {
+            if let (Some(current), Some(value)) = (slot.as_mut(), value) {
+                *current = current.saturating_sub(value);
+            }
+        }
+    }
+}
+
+/// Billing conventions of translation agencies by language region.
+///
+/// Characters of Chinese, Japanese, and Korean scripts always count one unit
+/// each. The regions differ in how runs of other text, e.g. Latin words and
+/// numbers, are billed.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug, Default)]
+pub enum BillingRegion {
+    /// Chinese: every other word counts one unit, like a character (default).
+    #[default]
+    Zh,
+    /// Japanese: every other character counts one unit.
+    Ja,
+    /// Korean: every other character counts one unit.
+    Ko,
+}
+
+/// Passes that normalize layout artifacts in text before it is counted.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug)]
+pub enum Normalization {
+    /// Remove soft hyphens, joining words split by one at a line break.
+    SoftHyphens,
+    /// Replace non-breaking, narrow, and zero-width spaces by plain spaces,
+    /// and remove word joiners.
+    Spaces,
+}
+
+/// A regular expression defining what a word is, instead of splitting text
+/// at whitespace.
+///
+/// Every match of the expression is one word. For example, `[\w']+` counts
+/// the parts of hyphenated compounds as separate words, and `\p{L}+` skips
+/// numbers.
+#[derive(Debug, Clone)]
+pub struct WordPattern(Regex);
+
+impl WordPattern {
+    /// Returns the words of a text, in order.
+    pub fn words<'t>(&self, text: &'t str) -> impl Iterator<Item = &'t str> + use<'_, 't> {
+        self.0.find_iter(text).map(|word| word.as_str())
+    }
+
+    /// Returns the regular expression the pattern was parsed from.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl FromStr for WordPattern {
+    type Err = regex::Error;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        Regex::new(pattern).map(Self)
+    }
+}
+
+impl PartialEq for WordPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for WordPattern {}
+
+/// Words per page of a standard manuscript, used for the `ManuscriptPages`
+/// metric unless configured otherwise.
+pub const DEFAULT_WORDS_PER_PAGE: usize = 250;
+
+/// Options controlling which parts of a document are counted.
+///
+/// The default options count everything that is rendered except page
+/// headers and footers and the table of contents, matching the behavior of the command-line tool
+/// without any filtering flags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CountOptions {
+    /// Only count text from the main file, excluding imported/included files.
+    pub exclude_imports: bool,
+
+    /// Skip figure and table captions.
+    pub exclude_captions: bool,
+
+    /// Skip footnotes, both the in-text markers and the footnote bodies.
+    pub exclude_footnotes: bool,
+
+    /// Skip the bibliography / reference list.
+    pub exclude_bibliography: bool,
+
+    /// Skip placed content, such as floating figures and callout boxes
+    /// positioned with `place`.
+    pub exclude_floats: bool,
+
+    /// Don't count raw URLs (`https://…`, `www.…`) as words.
+    pub exclude_urls: bool,
+
+    /// Don't count numbers and dates (`42`, `3.14`, `2026-03-01`) as words.
+    pub exclude_numbers: bool,
+
+    /// Skip text from the files of packages, such as the boilerplate of a
+    /// `@preview` template.
+    pub exclude_packages: bool,
+
+    /// Skip text without a source file, which templates and show rules
+    /// generate, e.g. "Figure 1:" supplements, "Chapter" prefixes, or
+    /// page headers.
+    pub exclude_generated: bool,
+
+    /// Count the text of page headers and footers, which is set with the
+    /// `header` and `footer` arguments of `page` and repeats on every page.
+    pub include_chrome: bool,
+
+    /// Count the entries of outlines such as the table of contents, which
+    /// repeat the text of the headings they list.
+    pub include_outline: bool,
+
+    /// Skip numbering generated by counters, such as heading numbers, list
+    /// markers, and "Figure 4" supplements, which isn't authored prose.
+    pub exclude_numbering: bool,
+
+    /// Skip content marked with one of these labels or element names, e.g.
+    /// `todo` for content labelled `<todo>`. Surrounding angle brackets are
+    /// ignored.
+    pub exclude_marked: Vec<String>,
+
+    /// Labels or element names of notes besides footnotes, e.g. endnotes
+    /// or margin notes, for the note metrics. Marked like `exclude_marked`.
+    pub note_markers: Vec<String>,
+
+    /// Label marking the abstract, `abstract` if not set.
+    pub abstract_label: Option<String>,
+
+    /// Additional metrics to compute.
+    pub metrics: Vec<Metric>,
+
+    /// Names of further elements to treat as styling wrappers, e.g. elements
+    /// of a template whose text is already counted in their parents.
+    pub styling_elements: Vec<String>,
+
+    /// Words per page for the `ManuscriptPages` metric,
+    /// [`DEFAULT_WORDS_PER_PAGE`] if not set.
+    pub words_per_page: Option<usize>,
+
+    /// Billing conventions for the `BillableUnits` metric.
+    pub billing_region: BillingRegion,
+
+    /// Units billed per word outside Chinese, Japanese, and Korean scripts,
+    /// overriding the convention of the `billing_region`.
+    pub latin_word_units: Option<usize>,
+
+    /// Normalization passes applied to text before it is counted.
+    pub normalize: Vec<Normalization>,
+
+    /// Pattern matching the words of a text; words are separated by
+    /// whitespace if not set.
+    pub word_pattern: Option<WordPattern>,
+
+    /// Lay out text in font families that are not available with a fallback
+    /// font instead of failing, warning about the missing families. Word
+    /// counts rarely depend on the exact face.
+    pub font_fallback: bool,
+
+    /// Serve placeholders for images and data files that are not found
+    /// instead of failing, warning about the missing files.
+    pub allow_missing_assets: bool,
+
+    /// Skip imported and included files with compile errors instead of
+    /// failing, warning about the skipped files. Errors in the main file
+    /// still fail the compilation.
+    pub lenient_imports: bool,
+
+    /// Count documents that fail to compile from their source text instead
+    /// of failing, marking their counts as approximate.
+    pub approximate_failed: bool,
+
+    /// Preferred terms whose discouraged variants the `DiscouragedTerms`
+    /// metric counts.
+    pub terms: Terms,
+}
+
+impl CountOptions {
+    /// Checks if an element is excluded by these options.
+    ///
+    /// Excluded elements are skipped together with all content nested inside
+    /// them, see [`count_document`].
+    fn excludes(&self, element: &Content) -> bool {
+        (self.exclude_captions && element.is::<FigureCaption>())
+            || (self.exclude_footnotes
+                && (element.is::<FootnoteElem>() || element.is::<FootnoteEntry>()))
+            || (self.exclude_bibliography && element.is::<BibliographyElem>())
+            || (self.exclude_floats && element.is::<PlaceElem>())
+            || (!self.include_outline
+                && (element.is::<OutlineElem>() || element.is::<OutlineEntry>()))
+            || (self.exclude_numbering && is_numbering(element))
+            || self.is_marked(element)
+    }
+
+    /// Checks if an element carries one of the `exclude_marked` labels or is
+    /// one of the `exclude_marked` elements.
+    fn is_marked(&self, element: &Content) -> bool {
+        has_marker(element, &self.exclude_marked)
+    }
+
+    /// Checks if an element is a footnote or one of the `note_markers`.
+    fn is_note(&self, element: &Content) -> bool {
+        element.is::<FootnoteElem>()
+            || element.is::<FootnoteEntry>()
+            || has_marker(element, &self.note_markers)
+    }
+
+    /// Checks if an element is a styling wrapper whose text is already
+    /// counted in its parent, including the configured `styling_elements`.
+    fn is_styling(&self, element: &Content) -> bool {
+        is_styling_element(element)
+            || self
+                .styling_elements
+                .iter()
+                .any(|name| name == element.func().name())
+    }
+
+    /// Checks if a metric was requested.
+    fn wants(&self, metric: Metric) -> bool {
+        self.metrics.contains(&metric)
+    }
+
+    /// Applies the `normalize` passes to a piece of text.
+    ///
+    /// A soft hyphen followed by a line break joins the fragments of a word
+    /// across the break, so they count as one word.
+    fn normalize(&self, text: String) -> String {
+        if self.normalize.is_empty() {
+            return text;
+        }
+        let soft_hyphens = self.normalize.contains(&Normalization::SoftHyphens);
+        let spaces = self.normalize.contains(&Normalization::Spaces);
+        let mut normalized = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\u{ad}' if soft_hyphens => {
+                    let mut gap = String::new();
+                    while let Some(space) = chars.next_if(|c| c.is_whitespace()) {
+                        gap.push(space);
+                    }
+                    if !gap.contains('\n') {
+                        normalized.push_str(&gap);
+                    }
+                }
+                '\u{a0}' | '\u{2007}' | '\u{2009}' | '\u{200a}' | '\u{200b}' | '\u{202f}'
+                    if spaces =>
+                {
+                    normalized.push(' ');
+                }
+                '\u{2060}' | '\u{feff}' if spaces => {}
+                c => normalized.push(c),
+            }
+        }
+        normalized
+    }
+
+    /// Counts the words in a piece of text.
+    ///
+    /// Words are separated by whitespace, or matched by the `word_pattern`
+    /// if set. URLs are skipped if `exclude_urls` is set, and numbers and
+    /// dates if `exclude_numbers` is set.
+    fn count_words(&self, text: &str) -> usize {
+        self.tokens(text)
+            .filter(|word| {
+                !(self.exclude_urls && is_url(word))
+                    && !(self.exclude_numbers && (is_numeric(word) || is_date(word)))
+            })
+            .count()
+    }
+
+    /// Counts the numeric and the date-like words in a piece of text, for
+    /// the `NumericWords` and `DateWords` metrics.
+    ///
+    /// They are counted whether or not `exclude_numbers` is set.
+    fn count_numbers(&self, text: &str) -> (usize, usize) {
+        let mut numbers = (0, 0);
+        for word in self.tokens(text) {
+            if is_date(word) {
+                numbers.1 += 1;
+            } else if is_numeric(word) {
+                numbers.0 += 1;
+            }
+        }
+        numbers
+    }
+
+    /// Splits text into words, without skipping any.
+    ///
+    /// Words are separated by whitespace, or matched by the `word_pattern`
+    /// if set, as when counting.
+    pub fn tokens<'a>(&'a self, text: &'a str) -> Box<dyn Iterator<Item = &'a str> + 'a> {
+        match &self.word_pattern {
+            Some(pattern) => Box::new(pattern.words(text)),
+            None => Box::new(text.split_whitespace()),
+        }
+    }
+
+    /// Counts the billable units in a piece of text.
+    ///
+    /// Each character of a Chinese, Japanese, or Korean script is one unit.
+    /// Runs of other characters between whitespace and such characters are
+    /// billed as words or by their characters, see [`BillingRegion`].
+    fn billable_units(&self, text: &str) -> usize {
+        let run_units = |chars: usize| match (self.latin_word_units, self.billing_region) {
+            (_, _) if chars == 0 => 0,
+            (Some(units), _) => units,
+            (None, BillingRegion::Zh) => 1,
+            (None, BillingRegion::Ja | BillingRegion::Ko) => chars,
+        };
+
+        let mut units = 0;
+        for word in text.split_whitespace() {
+            let mut run = 0;
+            for c in word.chars() {
+                if is_cjk(c) {
+                    units += run_units(run) + 1;
+                    run = 0;
+                } else {
+                    run += 1;
+                }
+            }
+            units += run_units(run);
+        }
+        units
+    }
+
+    /// Estimates the number of manuscript pages of a text with `words` words.
+    pub(crate) fn manuscript_pages(&self, words: usize) -> usize {
+        let per_page = self.words_per_page.unwrap_or(DEFAULT_WORDS_PER_PAGE).max(1);
+        words.div_ceil(per_page)
+    }
+
+    /// Returns the label marking the abstract.
+    fn abstract_label(&self) -> &str {
+        self.abstract_label.as_deref().unwrap_or("abstract")
+    }
+}
+
+/// Counts words and characters in a compiled Typst document.
+///
+/// This function traverses all elements in the document using the introspector
+/// and extracts plain text content. It handles the following cases:
+///
+/// - **Text styling**: Skips styling elements (bold, italic, etc.) to avoid
+///   double-counting since their text is already included in parent elements.
+/// - **Math equations**: Skips mathematical notation to avoid counting math symbols as words.
+/// - **Imports**: Optionally excludes text from imported/included files.
+/// - **Excluded elements**: Optionally skips captions, footnotes, and the
+///   bibliography, including everything nested inside them.
+/// - **Rendered content**: Only counts text that appears in the final rendered
+///   document, ignoring code, comments, and markup syntax.
+///
+/// # Arguments
+///
+/// * `world` - The world the document was compiled in, used to resolve source spans
+/// * `introspector` - The Typst introspector providing access to document elements
+/// * `options` - Options controlling which content is counted
+///
+/// # Returns
+///
+/// A `Count` struct containing the word and character counts.
+///
+/// # Examples
+///
+/// ```ignore
+/// use typst_count::counter::{count_document, CountOptions};
+///
+/// let count = count_document(&world, &document.introspector, &CountOptions::default());
+/// println!("Words: {}, Characters: {}", count.words, count.characters);
+/// ```
+///
+/// # Counting Method
+///
+/// - **Words**: Split by Unicode whitespace (equivalent to Rust's `split_whitespace()`)
+/// - **Characters**: Total Unicode scalar values (equivalent to Rust's `chars().count()`)
+///
+/// # Avoiding Double-Counting
+///
+/// Typst's document tree includes both container elements and their styled children.
+/// For example, `*bold text*` creates:
+/// - A paragraph element containing "bold text"
+/// - A `strong` element also containing "bold text"
+///
+/// To avoid counting the same text twice, we skip known styling elements whose
+/// content is already included in their parent elements.
+///
+/// # Nested Content
+///
+/// The introspector yields a flat list of elements, so content nested inside an
+/// excluded element (such as the paragraphs of a footnote) is detected through
+/// its source span: any element whose span lies within the source range of an
+/// excluded element is skipped as well.
+pub fn count_document(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+) -> Count {
+    count_elements(world, introspector, options, None, None)
+}
+
+/// Counts the words and characters of a document like [`count_document`],
+/// passing every counted piece of text to a callback as it goes.
+///
+/// The callback sees exactly the text that is counted, in document order,
+/// with the element it belongs to and its source location, so concordances,
+/// search indexes, or custom metrics can be built in the same pass as the
+/// count.
+///
+/// # Examples
+///
+/// ```ignore
+/// use typst_count::counter::{count_document_with, CountOptions};
+///
+/// let mut headings = Vec::new();
+/// let count = count_document_with(&world, &document.introspector, &CountOptions::default(), |chunk| {
+///     if chunk.element == "heading" {
+///         headings.push(chunk.text);
+///     }
+/// });
+/// ```
+pub fn count_document_with(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+    mut on_chunk: impl FnMut(TextChunk),
+) -> Count {
+    count_elements(world, introspector, options, Some(&mut on_chunk), None)
+}
+
+/// Counts the elements of a document, see [`count_document`], passing the
+/// counted text to `on_chunk` if given.
+///
+/// With `source`, only the elements written in that file are counted, and
+/// the metrics of the bibliography, which can't be attributed to a file,
+/// are left out.
+fn count_elements(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+    mut on_chunk: Option<&mut dyn FnMut(TextChunk)>,
+    source: Option<FileId>,
+) -> Count {
+    let filter = Filter::new(world, introspector, options);
+    let mut count = Count::default();
+    let mut abstract_part = options
+        .wants(Metric::AbstractWords)
+        .then(|| Section::new(world, introspector, options.abstract_label()));
+    let quotes = Regions::collect(
+        world,
+        introspector
+            .all()
+            .filter(|element| options.wants(Metric::QuotedWords) && element.is::<QuoteElem>()),
+    );
+    let mut quoted_words = 0;
+    let wants_lists = options.wants(Metric::ListWords) || options.wants(Metric::ProseWords);
+    let lists = Regions::collect(
+        world,
+        introspector
+            .all()
+            .filter(|element| wants_lists && is_list_element(element)),
+    );
+    let mut list_words = 0;
+    let wants_notes = options.wants(Metric::NoteWords) || options.wants(Metric::BodyWords);
+    let notes = Regions::collect(
+        world,
+        introspector
+            .all()
+            .filter(|element| wants_notes && options.is_note(element)),
+    );
+    let mut note_words = 0;
+    let wants_floats = options.wants(Metric::FloatWords);
+    let floats = Regions::collect(
+        world,
+        introspector
+            .all()
+            .filter(|element| wants_floats && element.is::<PlaceElem>()),
+    );
+    let mut float_words = 0;
+    let wants_numbers = options.wants(Metric::NumericWords) || options.wants(Metric::DateWords);
+    let mut numeric_words = 0;
+    let mut characters_collapsed = 0;
+    let mut date_words = 0;
+    let wants_terms = options.wants(Metric::DiscouragedTerms);
+    let mut discouraged_terms = 0;
+    let wants_billing = options.wants(Metric::BillableUnits);
+    let mut billable_units = 0;
+    let mut package_words = 0;
+    let mut header_footer_words = 0;
+    let mut figures = 0;
+    let mut inline_equations = 0;
+    let mut display_equations = 0;
+    let mut links = 0;
+
+    for element in introspector.all() {
+        // Sections end at the next heading of the same or a higher level, even
+        // if that heading itself isn't counted.
+        let in_abstract = abstract_part
+            .as_mut()
+            .is_some_and(|part| part.advance(world, element));
+
+        if filter.excludes_content(element)
+            || source.is_some_and(|source| element.span().id() != Some(source))
+        {
+            continue;
+        }
+        if filter.is_chrome(element) {
+            if !options.is_styling(element) {
+                header_footer_words += options.count_words(&filter.text(element));
+            }
+            if !options.include_chrome {
+                continue;
+            }
+        }
+
+        if element.is::<LinkElem>() {
+            links += 1;
+        }
+        if element.is::<FigureElem>() {
+            figures += 1;
+        }
+        if element.is::<EquationElem>() {
+            if matches!(element.get_by_name("block"), Ok(Value::Bool(true))) {
+                display_equations += 1;
+            } else {
+                inline_equations += 1;
+            }
+        }
+
+        // Skip styling elements to avoid double-counting.
+        // These elements' text is already included in their parent elements
+        // (typically paragraphs or other text containers).
+        if options.is_styling(element) {
+            continue;
+        }
+
+        let text = filter.text(element);
+        if !text.is_empty() {
+            let words = options.count_words(&text);
+            count.characters += text.chars().count();
+            count.words += words;
+            if wants_billing {
+                billable_units += options.billable_units(&text);
+            }
+            if element.span().id().is_some_and(|id| id.package().is_some()) {
+                package_words += words;
+            }
+            characters_collapsed += collapsed_characters(&text);
+            if wants_terms {
+                discouraged_terms += options.terms.discouraged(&text);
+            }
+            if wants_numbers {
+                let (numbers, dates) = options.count_numbers(&text);
+                numeric_words += numbers;
+                date_words += dates;
+            }
+
+            if in_abstract && let Some(part) = &mut abstract_part {
+                part.words += words;
+            }
+
+            // Text nested in a quote element is covered by the quote element
+            // itself, and text surrounding one is only scanned if the quote
+            // element doesn't already account for its quoted part.
+            if options.wants(Metric::QuotedWords) {
+                if element.is::<QuoteElem>() {
+                    quoted_words += words;
+                } else if !quotes.contains(world, element.span())
+                    && !quotes.within(world, element.span())
+                {
+                    quoted_words += count_quoted_words(&text);
+                }
+            }
+
+            if wants_lists && lists.contains(world, element.span()) {
+                list_words += words;
+            }
+
+            if wants_notes && notes.contains(world, element.span()) {
+                note_words += words;
+            }
+
+            if wants_floats && floats.contains(world, element.span()) {
+                float_words += words;
+            }
+
+            if let Some(on_chunk) = on_chunk.as_mut() {
+                on_chunk(TextChunk {
+                    text,
+                    element: element.func().name(),
+                    heading_level: heading_level(element),
+                    location: locate(world, element.span()),
+                });
+            }
+        }
+    }
+
+    if let Some(part) = abstract_part {
+        count.metrics.set(Metric::AbstractWords, part.words);
+    }
+    if options.wants(Metric::QuotedWords) {
+        count.metrics.set(Metric::QuotedWords, quoted_words);
+    }
+    if options.wants(Metric::Links) {
+        count.metrics.set(Metric::Links, links);
+    }
+    if wants_billing {
+        count.metrics.set(Metric::BillableUnits, billable_units);
+    }
+    if options.wants(Metric::PackageWords) {
+        count.metrics.set(Metric::PackageWords, package_words);
+    }
+    if options.wants(Metric::HeaderFooterWords) {
+        count
+            .metrics
+            .set(Metric::HeaderFooterWords, header_footer_words);
+    }
+    if source.is_none()
+        && (options.wants(Metric::References)
+            || options.wants(Metric::CitedWorks)
+            || options.wants(Metric::Citations))
+    {
+        let citations = count_citations(world, introspector);
+        for (metric, value) in [
+            (Metric::References, citations.references),
+            (Metric::CitedWorks, citations.cited_works),
+            (Metric::Citations, citations.citations),
+        ] {
+            if options.wants(metric) {
+                count.metrics.set(metric, value);
+            }
+        }
+    }
+    for (metric, value) in [
+        (Metric::Figures, figures),
+        (Metric::InlineEquations, inline_equations),
+        (Metric::DisplayEquations, display_equations),
+    ] {
+        if options.wants(metric) {
+            count.metrics.set(metric, value);
+        }
+    }
+    // Densities are computed from their occurrences by `update_densities`.
+    for metric in Metric::ALL.into_iter().filter(|m| m.density_of().is_some()) {
+        if options.wants(metric) {
+            count.metrics.set(metric, 0);
+        }
+    }
+    count.metrics.update_densities(count.words);
+    if options.wants(Metric::ManuscriptPages) {
+        count.metrics.set(
+            Metric::ManuscriptPages,
+            options.manuscript_pages(count.words),
+        );
+    }
+    if options.wants(Metric::Comments)
+        || options.wants(Metric::TodoComments)
+        || options.wants(Metric::CommentedWords)
+    {
+        let comments = count_comments(world, introspector, options, source);
+        for (metric, value) in [
+            (Metric::Comments, comments.comments),
+            (Metric::TodoComments, comments.todos),
+            (Metric::CommentedWords, comments.words),
+        ] {
+            if options.wants(metric) {
+                count.metrics.set(metric, value);
+            }
+        }
+    }
+    if options.wants(Metric::ListWords) {
+        count.metrics.set(Metric::ListWords, list_words);
+    }
+    if options.wants(Metric::ProseWords) {
+        count
+            .metrics
+            .set(Metric::ProseWords, count.words.saturating_sub(list_words));
+    }
+    if options.wants(Metric::NoteWords) {
+        count.metrics.set(Metric::NoteWords, note_words);
+    }
+    if wants_floats {
+        count.metrics.set(Metric::FloatWords, float_words);
+    }
+    if options.wants(Metric::NumericWords) {
+        count.metrics.set(Metric::NumericWords, numeric_words);
+    }
+    if options.wants(Metric::DateWords) {
+        count.metrics.set(Metric::DateWords, date_words);
+    }
+    if options.wants(Metric::CharactersCollapsed) {
+        count
+            .metrics
+            .set(Metric::CharactersCollapsed, characters_collapsed);
+    }
+    if wants_terms {
+        count
+            .metrics
+            .set(Metric::DiscouragedTerms, discouraged_terms);
+    }
+    if options.wants(Metric::BodyWords) {
+        count
+            .metrics
+            .set(Metric::BodyWords, count.words.saturating_sub(note_words));
+    }
+
+    count
+}
+
+/// Counts the words and characters of plain text with the rules used for
+/// documents.
+///
+/// Used for text that isn't a Typst document, such as the text extracted
+/// from an exported PDF. Line breaks are not counted as characters, and only
+/// the metrics that can be computed from the text alone are set: quoted
+/// words, billable units, numeric and date words, collapsed characters,
+/// discouraged terms, and manuscript pages.
+#[must_use]
+pub fn count_text(text: &str, options: &CountOptions) -> Count {
+    let text = &options.normalize(text.to_string());
+    let mut count = Count::default();
+    for line in text.lines() {
+        count.words += options.count_words(line);
+        count.characters += line.chars().count();
+    }
+    if options.wants(Metric::QuotedWords) {
+        count
+            .metrics
+            .set(Metric::QuotedWords, count_quoted_words(text));
+    }
+    if options.wants(Metric::BillableUnits) {
+        count
+            .metrics
+            .set(Metric::BillableUnits, options.billable_units(text));
+    }
+    let (numbers, dates) = options.count_numbers(text);
+    let collapsed = text.lines().map(collapsed_characters).sum();
+    for (metric, value) in [
+        (Metric::NumericWords, numbers),
+        (Metric::DateWords, dates),
+        (Metric::CharactersCollapsed, collapsed),
+        (Metric::DiscouragedTerms, options.terms.discouraged(text)),
+    ] {
+        if options.wants(metric) {
+            count.metrics.set(metric, value);
+        }
+    }
+    if options.wants(Metric::ManuscriptPages) {
+        count.metrics.set(
+            Metric::ManuscriptPages,
+            options.manuscript_pages(count.words),
+        );
+    }
+    count
+}
+
+/// Extracts the counted text of a compiled Typst document.
+///
+/// Returns the plain text of every element that [`count_document`] counts,
+/// in document order, using the same options and the same rules for
+/// skipping styling elements. This is the basis for text analyses such as
+/// acronym detection.
+///
+/// # Arguments
+///
+/// * `world` - The world the document was compiled in, used to resolve source spans
+/// * `introspector` - The Typst introspector providing access to document elements
+/// * `options` - Options controlling which content is included
+pub fn extract_text(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+) -> Vec<String> {
+    extract_chunks(world, introspector, options)
+        .into_iter()
+        .map(|chunk| chunk.text)
+        .collect()
+}
+
+/// A piece of counted text, as returned by [`extract_chunks`] and
+/// [`iter_text`] and passed to the callback of [`count_document_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextChunk {
+    /// Plain text of the element
+    pub text: String,
+    /// Name of the element the text belongs to, e.g. `par` or `heading`
+    pub element: &'static str,
+    /// Level of the heading, starting at 1, if the element is a heading
+    pub heading_level: Option<usize>,
+    /// Where the element is written, if it comes from a source file
+    pub location: Option<SourceLocation>,
+}
+
+/// A position in a source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// Path of the file, relative to the project root or package
+    pub file: String,
+    /// Line number, starting at 1
+    pub line: usize,
+    /// Column number in characters, starting at 1
+    pub column: usize,
+}
+
+impl std::fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// Extracts the counted text of a compiled Typst document with its origin.
+///
+/// Like [`extract_text`], but keeps the element name and the source location
+/// of every chunk, so heavy paragraphs can be found in the source and
+/// double-counted text can be traced back to the elements involved.
+///
+/// # Arguments
+///
+/// * `world` - The world the document was compiled in, used to resolve source spans
+/// * `introspector` - The Typst introspector providing access to document elements
+/// * `options` - Options controlling which content is included
+pub fn extract_chunks(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+) -> Vec<TextChunk> {
+    iter_text(world, introspector, options).collect()
+}
+
+/// Iterates over the counted text of a compiled Typst document.
+///
+/// Like [`extract_chunks`], but produces each chunk only when it is asked
+/// for, so NLP pipelines and other consumers can process long documents
+/// piece by piece without holding all of their text at once. Only the
+/// regions of excluded elements are collected up front.
+///
+/// # Examples
+///
+/// ```ignore
+/// use typst_count::counter::{iter_text, CountOptions};
+///
+/// let document: PagedDocument = typst::compile(&world).output?;
+/// for chunk in iter_text(&world, &document.introspector, &CountOptions::default()) {
+///     pipeline.feed(&chunk.text);
+/// }
+/// ```
+pub fn iter_text<'a>(
+    world: &'a dyn World,
+    introspector: &'a Introspector,
+    options: &'a CountOptions,
+) -> impl Iterator<Item = TextChunk> + 'a {
+    let filter = Filter::new(world, introspector, options);
+    introspector.all().filter_map(move |element| {
+        if filter.excludes(element) || options.is_styling(element) {
+            return None;
+        }
+        let text = filter.text(element);
+        (!text.is_empty()).then(|| TextChunk {
+            text,
+            element: element.func().name(),
+            heading_level: heading_level(element),
+            location: locate(world, element.span()),
+        })
+    })
+}
+
+/// Diagnostics about how a document was counted, as reported by [`audit`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Audit {
+    /// Number of styling elements skipped to avoid double-counting
+    pub styling_elements: usize,
+    /// Words in the skipped styling elements
+    pub styling_words: usize,
+    /// Characters in the skipped styling elements
+    pub styling_characters: usize,
+    /// Counted elements whose text is also counted as part of another element
+    pub suspects: Vec<Suspect>,
+}
+
+/// A counted element that is likely counted twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suspect {
+    /// The element that is likely counted twice
+    pub chunk: TextChunk,
+    /// Name of the enclosing counted element that already contains its text
+    pub container: &'static str,
+    /// Words counted twice
+    pub words: usize,
+}
+
+/// Audits how a compiled Typst document is counted.
+///
+/// Reports how much text was skipped as styling (see
+/// [`is_styling_element`]), and flags counted elements whose source lies
+/// within another counted element that already contains their text. The
+/// latter is a heuristic for double-counting: wrapper elements that are
+/// missing from the styling list show up here.
+///
+/// # Arguments
+///
+/// * `world` - The world the document was compiled in, used to resolve source spans
+/// * `introspector` - The Typst introspector providing access to document elements
+/// * `options` - Options controlling which content is counted
+pub fn audit(world: &dyn World, introspector: &Introspector, options: &CountOptions) -> Audit {
+    let filter = Filter::new(world, introspector, options);
+    let mut audit = Audit::default();
+    let mut counted = Vec::new();
+
+    for element in introspector.all() {
+        if filter.excludes(element) {
+            continue;
+        }
+
+        let text = element.plain_text();
+        if options.is_styling(element) {
+            audit.styling_elements += 1;
+            audit.styling_words += options.count_words(&text);
+            audit.styling_characters += text.chars().count();
+        } else if !text.is_empty() {
+            counted.push((element, resolve_span(world, element.span()), text));
+        }
+    }
+
+    for (element, span, text) in &counted {
+        let Some((id, range)) = span else {
+            continue;
+        };
+        let container = counted.iter().find(|(_, other_span, other_text)| {
+            other_span.as_ref().is_some_and(|(other_id, other_range)| {
+                other_id == id
+                    && other_range.start <= range.start
+                    && range.end <= other_range.end
+                    && other_range != range
+            }) && other_text.contains(text.as_str())
+        });
+
+        if let Some((other, _, _)) = container {
+            audit.suspects.push(Suspect {
+                chunk: TextChunk {
+                    text: text.to_string(),
+                    element: element.func().name(),
+                    heading_level: heading_level(element),
+                    location: locate(world, element.span()),
+                },
+                container: other.func().name(),
+                words: options.count_words(text),
+            });
+        }
+    }
+
+    audit
+}
+
+/// Word count of a document section, as reported by [`count_sections`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionCount {
+    /// Heading level, starting at 1; 0 for text before the first heading
+    pub level: usize,
+    /// Heading text
+    pub title: String,
+    /// Words in the section itself, including its heading but not its subsections
+    pub words: usize,
+    /// Words in the section including all of its subsections
+    pub cumulative: usize,
+}
+
+/// Counts the words in each section of a compiled Typst document.
+///
+/// Sections are delimited by headings. Every counted word is attributed to
+/// exactly one section, so the `words` of all sections add up to the
+/// document's word count. Text before the first heading is reported as a
+/// level-0 section, if there is any.
+///
+/// # Arguments
+///
+/// * `world` - The world the document was compiled in, used to resolve source spans
+/// * `introspector` - The Typst introspector providing access to document elements
+/// * `options` - Options controlling which content is counted
+///
+/// # Returns
+///
+/// The sections in document order.
+pub fn count_sections(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+) -> Vec<SectionCount> {
+    let filter = Filter::new(world, introspector, options);
+    let mut sections = vec![SectionCount {
+        level: 0,
+        title: String::new(),
+        words: 0,
+        cumulative: 0,
+    }];
+
+    for element in introspector.all() {
+        if filter.excludes(element) || options.is_styling(element) {
+            continue;
+        }
+
+        let text = filter.text(element);
+        if let Some(level) = heading_level(element) {
+            sections.push(SectionCount {
+                level,
+                title: text.trim().to_string(),
+                words: 0,
+                cumulative: 0,
+            });
+        }
+
+        if let Some(section) = sections.last_mut() {
+            section.words += options.count_words(&text);
+        }
+    }
+
+    if sections[0].words == 0 {
+        sections.remove(0);
+    }
+
+    for i in 0..sections.len() {
+        let level = sections[i].level;
+        let nested: usize = sections[i + 1..]
+            .iter()
+            .take_while(|section| level > 0 && section.level > level)
+            .map(|section| section.words)
+            .sum();
+        sections[i].cumulative = sections[i].words + nested;
+    }
+
+    sections
+}
+
+/// Label of the metadata elements that declare the author of a section.
+const AUTHOR_LABEL: &str = "author";
+
+/// Words attributed to an author, as reported by [`count_authors`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorCount {
+    /// Name of the author, or `None` for text without a declared author
+    pub author: Option<String>,
+    /// Words attributed to the author
+    pub words: usize,
+}
+
+/// Counts the text coming from each source file of a compiled Typst
+/// document.
+///
+/// Text is attributed to the file its element was written in, so the text
+/// of an included file counts towards that file. Text without a source
+/// file, e.g. generated by a template, is not attributed. Each file's count
+/// has the requested metrics, except those of the bibliography, which can't
+/// be attributed to a file.
+///
+/// # Arguments
+///
+/// * `world` - The world the document was compiled in, used to resolve source spans
+/// * `introspector` - The Typst introspector providing access to document elements
+/// * `options` - Options controlling which content is counted
+///
+/// # Returns
+///
+/// The files in order of their first counted text.
+pub fn count_sources(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+) -> Vec<(FileId, Count)> {
+    let filter = Filter::new(world, introspector, options);
+    let mut sources: Vec<FileId> = Vec::new();
+    for element in introspector.all() {
+        if filter.excludes(element) || options.is_styling(element) {
+            continue;
+        }
+        if let Some(id) = element.span().id()
+            && !sources.contains(&id)
+        {
+            sources.push(id);
+        }
+    }
+
+    sources
+        .into_iter()
+        .map(|id| {
+            let count = count_elements(world, introspector, options, None, Some(id));
+            (id, count)
+        })
+        .collect()
+}
+
+/// Counts the words written by each author declared in a compiled Typst
+/// document.
+///
+/// Authors are declared with `#metadata("Name") <author>` inside a section.
+/// The declaration applies from where it is placed to the end of the
+/// section it is in, or up to the next declaration. Declarations before the
+/// first heading apply until the next declaration. Text without a declared
+/// author is reported with `author` set to `None`, if there is any.
+///
+/// # Arguments
+///
+/// * `world` - The world the document was compiled in, used to resolve source spans
+/// * `introspector` - The Typst introspector providing access to document elements
+/// * `options` - Options controlling which content is counted
+///
+/// # Returns
+///
+/// The authors in order of their first declaration, followed by the
+/// unattributed words.
+pub fn count_authors(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+) -> Vec<AuthorCount> {
+    let filter = Filter::new(world, introspector, options);
+    let mut authors: Vec<AuthorCount> = Vec::new();
+    let mut unattributed = 0;
+    // Index of the current author and the level of the section they wrote.
+    let mut current: Option<(usize, usize)> = None;
+    let mut section_level = 0;
+
+    for element in introspector.all() {
+        if let Some(level) = heading_level(element) {
+            if current.is_some_and(|(_, scope)| scope > 0 && level <= scope) {
+                current = None;
+            }
+            section_level = level;
+        }
+
+        if has_label(element, AUTHOR_LABEL)
+            && element.func().name() == "metadata"
+            && let Ok(Value::Str(name)) = element.get_by_name("value")
+        {
+            let index = match authors
+                .iter()
+                .position(|a| a.author.as_deref() == Some(name.as_str()))
+            {
+                Some(index) => index,
+                None => {
+                    authors.push(AuthorCount {
+                        author: Some(name.to_string()),
+                        words: 0,
+                    });
+                    authors.len() - 1
+                }
+            };
+            current = Some((index, section_level));
+        }
+
+        if filter.excludes(element) || options.is_styling(element) {
+            continue;
+        }
+
+        let words = options.count_words(&filter.text(element));
+        match current {
+            Some((index, _)) => authors[index].words += words,
+            None => unattributed += words,
+        }
+    }
+
+    if unattributed > 0 {
+        authors.push(AuthorCount {
+            author: None,
+            words: unattributed,
+        });
+    }
+    authors
+}
+
+/// Prefix of the labels that declare a limit inside a document.
+const LIMIT_LABEL_PREFIX: &str = "limit-";
+
+/// A limit declared inside a document, as found by [`declared_limits`].
+///
+/// Limits are declared with a metadata element whose label names the part
+/// of the document they apply to, e.g.
+/// `#metadata((max-words: 300)) <limit-abstract>` for the part labelled
+/// `abstract`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclaredLimit {
+    /// Label of the part the limit applies to
+    pub part: String,
+    /// Maximum number of words in the part
+    pub max_words: Option<usize>,
+    /// Minimum number of words in the part
+    pub min_words: Option<usize>,
+    /// Maximum number of characters in the part
+    pub max_characters: Option<usize>,
+    /// Minimum number of characters in the part
+    pub min_characters: Option<usize>,
+    /// Words counted in the part
+    pub words: usize,
+    /// Characters counted in the part
+    pub characters: usize,
+    /// Start of the part, or of the declaration if the part wasn't found
+    pub location: Option<SourceLocation>,
+}
+
+impl DeclaredLimit {
+    /// Checks the part's count against the limit.
+    ///
+    /// Returns a message for each violated limit.
+    #[must_use]
+    pub fn violations(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        let part = &self.part;
+
+        if let Some(max) = self.max_words
+            && self.words > max
+        {
+            errors.push(format!(
+                "Section `{part}` word count exceeds maximum ({} > {max})",
+                self.words
+            ));
+        }
+        if let Some(min) = self.min_words
+            && self.words < min
+        {
+            errors.push(format!(
+                "Section `{part}` word count below minimum ({} < {min})",
+                self.words
+            ));
+        }
+        if let Some(max) = self.max_characters
+            && self.characters > max
+        {
+            errors.push(format!(
+                "Section `{part}` character count exceeds maximum ({} > {max})",
+                self.characters
+            ));
+        }
+        if let Some(min) = self.min_characters
+            && self.characters < min
+        {
+            errors.push(format!(
+                "Section `{part}` character count below minimum ({} < {min})",
+                self.characters
+            ));
+        }
+
+        errors
+    }
+
+    /// Reads a limit from a metadata element labelled `<limit-PART>`.
+    ///
+    /// The metadata's value must be a dictionary with any of the keys
+    /// `max-words`, `min-words`, `max-characters`, and `min-characters`.
+    fn from_element(element: &Content) -> Option<Self> {
+        if element.func().name() != "metadata" {
+            return None;
+        }
+        let label = element.label()?;
+        let part = label
+            .resolve()
+            .as_str()
+            .strip_prefix(LIMIT_LABEL_PREFIX)?
+            .to_string();
+        let Ok(Value::Dict(value)) = element.get_by_name("value") else {
+            return None;
+        };
+        let limit = |key: &str| match value.get(key) {
+            Ok(Value::Int(n)) => usize::try_from(*n).ok(),
+            _ => None,
+        };
+
+        Some(Self {
+            part,
+            max_words: limit("max-words"),
+            min_words: limit("min-words"),
+            max_characters: limit("max-characters"),
+            min_characters: limit("min-characters"),
+            words: 0,
+            characters: 0,
+            location: None,
+        })
+    }
+}
+
+/// Finds the limits declared inside a compiled Typst document and counts
+/// the parts they apply to.
+///
+/// Parts are located like the abstract: a limit for `part` applies to the
+/// content labelled `<part>`, or to the section below a heading that is
+/// labelled `<part>` or reads "part". Parts are counted with the same rules
+/// as [`count_document`].
+///
+/// # Arguments
+///
+/// * `world` - The world the document was compiled in, used to resolve source spans
+/// * `introspector` - The Typst introspector providing access to document elements
+/// * `options` - Options controlling which content is counted
+///
+/// # Returns
+///
+/// The declared limits in document order, with the counts of their parts.
+pub fn declared_limits(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+) -> Vec<DeclaredLimit> {
+    let mut limits = Vec::new();
+    for element in introspector.all() {
+        if let Some(mut limit) = DeclaredLimit::from_element(element) {
+            let start = introspector.all().find(|candidate| {
+                has_label(candidate, &limit.part)
+                    || (heading_level(candidate).is_some()
+                        && candidate
+                            .plain_text()
+                            .trim()
+                            .eq_ignore_ascii_case(&limit.part))
+            });
+            limit.location = locate(world, start.unwrap_or(element).span());
+            limits.push(limit);
+        }
+    }
+    if limits.is_empty() {
+        return limits;
+    }
+
+    let filter = Filter::new(world, introspector, options);
+    let mut parts: Vec<_> = limits
+        .iter()
+        .map(|limit| Section::new(world, introspector, &limit.part))
+        .collect();
+
+    for element in introspector.all() {
+        let inside: Vec<bool> = parts
+            .iter_mut()
+            .map(|part| part.advance(world, element))
+            .collect();
+
+        if filter.excludes(element) || options.is_styling(element) {
+            continue;
+        }
+
+        let text = filter.text(element);
+        if text.is_empty() {
+            continue;
+        }
+        let words = options.count_words(&text);
+        let characters = text.chars().count();
+        for (limit, inside) in limits.iter_mut().zip(inside) {
+            if inside {
+                limit.words += words;
+                limit.characters += characters;
+            }
+        }
+    }
+
+    limits
+}
+
+/// Decides which elements are excluded by a set of counting options.
+struct Filter<'a> {
+    /// The world the document was compiled in
+    world: &'a dyn World,
+    /// The options deciding what is excluded
+    options: &'a CountOptions,
+    /// Source regions of excluded elements
+    excluded: Regions,
+    /// Source regions and text of marked elements, whose text is removed
+    /// from the elements enclosing them
+    marked: Vec<((FileId, Range<usize>), String)>,
+    /// Source regions of page headers and footers
+    chrome: Regions,
+}
+
+impl<'a> Filter<'a> {
+    /// Prepares a filter for the elements of a document.
+    fn new(world: &'a dyn World, introspector: &Introspector, options: &'a CountOptions) -> Self {
+        let excluded = Regions::collect(
+            world,
+            introspector
+                .all()
+                .filter(|element| options.excludes(element)),
+        );
+        let marked = introspector
+            .all()
+            .filter(|element| options.is_marked(element))
+            .filter_map(|element| {
+                let region = resolve_span(world, element.span())?;
+                Some((region, element.plain_text().to_string()))
+            })
+            .collect();
+        let chrome = if options.include_chrome && !options.wants(Metric::HeaderFooterWords) {
+            Regions { ranges: Vec::new() }
+        } else {
+            Regions::page_chrome(world, introspector)
+        };
+        Self {
+            world,
+            options,
+            excluded,
+            marked,
+            chrome,
+        }
+    }
+
+    /// Returns the text of an element that is counted.
+    ///
+    /// This is the element's plain text without the text of marked elements
+    /// nested inside it, since a paragraph's text includes the text of
+    /// inline content such as `#strike[..]`. The text is normalized by the
+    /// `normalize` passes last.
+    fn text(&self, element: &Content) -> String {
+        let mut text = if self.options.exclude_numbering {
+            let mut text = String::new();
+            push_authored_text(element, &mut text);
+            text
+        } else {
+            element.plain_text().to_string()
+        };
+        if !self.marked.is_empty()
+            && let Some((id, range)) = resolve_span(self.world, element.span())
+        {
+            for ((marked_id, marked), marked_text) in &self.marked {
+                if *marked_id == id
+                    && range.start <= marked.start
+                    && marked.end <= range.end
+                    && *marked != range
+                {
+                    text = text.replacen(marked_text.as_str(), "", 1);
+                }
+            }
+        }
+        self.options.normalize(text)
+    }
+
+    /// Checks if an element is excluded, either because of its content (see
+    /// [`Self::excludes_content`]) or because it is part of a page header
+    /// or footer that isn't counted.
+    fn excludes(&self, element: &Content) -> bool {
+        self.excludes_content(element) || (!self.options.include_chrome && self.is_chrome(element))
+    }
+
+    /// Checks if an element is part of a page header or footer.
+    fn is_chrome(&self, element: &Content) -> bool {
+        self.chrome.contains(self.world, element.span())
+    }
+
+    /// Checks if an element is excluded, either because it comes from an
+    /// imported file or a package or was generated, or because it is (or
+    /// lies within) an excluded element.
+    fn excludes_content(&self, element: &Content) -> bool {
+        if self.options.exclude_generated && element.span().id().is_none() {
+            return true;
+        }
+        if let Some(file_id) = element.span().id()
+            && ((self.options.exclude_imports && file_id != self.world.main())
+                || (self.options.exclude_packages && file_id.package().is_some()))
+        {
+            return true;
+        }
+
+        self.options.excludes(element) || self.excluded.contains(self.world, element.span())
+    }
 }
 
-/// Counts words and characters in a compiled Typst document.
-///
-/// This function traverses all elements in the document using the introspector
-/// and extracts plain text content. It handles the following cases:
-///
-/// - **Text styling**: Skips styling elements (bold, italic, etc.) to avoid
-///   double-counting since their text is already included in parent elements.
-/// - **Math equations**: Skips mathematical notation to avoid counting math symbols as words.
-/// - **Imports**: Optionally excludes text from imported/included files.
-/// - **Rendered content**: Only counts text that appears in the final rendered
-///   document, ignoring code, comments, and markup syntax.
-///
-/// # Arguments
+/// A labelled part of the document, such as the abstract.
 ///
-/// * `introspector` - The Typst introspector providing access to document elements
-/// * `exclude_imports` - If `true`, only counts text from the main file
-/// * `main_file_id` - File ID of the main document (used when `exclude_imports` is `true`)
+/// The part is either the content carrying the label (and everything nested
+/// inside it), or, if the label is attached to a heading or a heading's text
+/// matches the label, the section below that heading.
+struct Section {
+    /// Label identifying the part
+    label: String,
+    /// Source regions of labelled, non-heading elements
+    regions: Regions,
+    /// Level of the heading whose section we are currently in, if any
+    heading_level: Option<usize>,
+    /// Words counted inside the part so far
+    words: usize,
+}
+
+impl Section {
+    /// Prepares to track the part identified by `label`.
+    fn new(world: &dyn World, introspector: &Introspector, label: &str) -> Self {
+        let regions = Regions::collect(
+            world,
+            introspector
+                .all()
+                .filter(|element| has_label(element, label) && !element.is::<HeadingElem>()),
+        );
+        Self {
+            label: label.to_string(),
+            regions,
+            heading_level: None,
+            words: 0,
+        }
+    }
+
+    /// Advances past an element in document order.
+    ///
+    /// Returns whether the element belongs to the part. The heading that opens
+    /// a section is not part of it.
+    fn advance(&mut self, world: &dyn World, element: &Content) -> bool {
+        if let Some(level) = heading_level(element) {
+            if self.heading_level.is_some_and(|open| level <= open) {
+                self.heading_level = None;
+            }
+            if has_label(element, &self.label)
+                || element
+                    .plain_text()
+                    .trim()
+                    .eq_ignore_ascii_case(&self.label)
+            {
+                self.heading_level = Some(level);
+                return false;
+            }
+        }
+
+        self.heading_level.is_some()
+            || has_label(element, &self.label)
+            || self.regions.contains(world, element.span())
+    }
+}
+
+/// Checks if an element is a bullet, numbered, or term list.
+fn is_list_element(element: &Content) -> bool {
+    element.is::<ListElem>() || element.is::<EnumElem>() || element.is::<TermsElem>()
+}
+
+/// Checks if an element carries one of the given labels or is one of the
+/// given elements.
 ///
-/// # Returns
+/// Markers in angle brackets, e.g. `<todo>`, only match labels; bare names
+/// match both labels and element names.
+fn has_marker(element: &Content, markers: &[String]) -> bool {
+    markers.iter().any(|marker| {
+        let name = marker.trim_start_matches('<').trim_end_matches('>');
+        has_label(element, name) || (name == marker && element.func().name() == name)
+    })
+}
+
+/// Fields holding the numbering and supplements of elements, e.g. the
+/// "Figure" supplement of a caption or the marker of a list.
+const NUMBERING_FIELDS: [&str; 4] = ["supplement", "numbering", "counter", "marker"];
+
+/// Checks if an element is numbering generated by a counter: a displayed
+/// counter, or generated text that consists of a number such as "1.2.3" or
+/// "Figure 4".
+fn is_numbering(element: &Content) -> bool {
+    element.is::<CounterDisplayElem>()
+        || (element.span().is_detached() && is_numbering_text(&element.plain_text()))
+}
+
+/// Checks if text is a number as displayed by a counter, optionally after a
+/// supplement: "1.2.3", "iv.", "(a)", "Figure 4:", or a list bullet.
+fn is_numbering_text(text: &str) -> bool {
+    let mut words = text.split_whitespace().rev();
+    let Some(number) = words.next() else {
+        return false;
+    };
+    let supplement = words.next();
+    if words.next().is_some()
+        || supplement.is_some_and(|word| !word.chars().all(char::is_alphabetic))
+    {
+        return false;
+    }
+    if supplement.is_none() && matches!(number, "•" | "‣" | "◦" | "–" | "-") {
+        return true;
+    }
+    let number = number
+        .trim_start_matches('(')
+        .trim_end_matches([':', '.', ')']);
+    !number.is_empty()
+        && number.split(['.', '-']).all(|part| {
+            !part.is_empty()
+                && (part.chars().all(|c| c.is_ascii_digit())
+                    || part.chars().all(|c| "ivxlcdmIVXLCDM".contains(c))
+                    || (part.len() == 1 && part.chars().all(|c| c.is_ascii_alphabetic())))
+        })
+}
+
+/// Appends the plain text of content without the numbering nested in it,
+/// see [`CountOptions::exclude_numbering`].
 ///
-/// A `Count` struct containing the word and character counts.
+/// Like [`Content::plain_text`], this collects the text of the content and
+/// of all content in its fields, but skips the [`NUMBERING_FIELDS`] and
+/// displayed counters.
+fn push_authored_text(content: &Content, text: &mut String) {
+    if is_numbering(content) {
+        return;
+    }
+    if let Some(textable) = content.with::<dyn PlainText>() {
+        let mut plain = Default::default();
+        textable.plain_text(&mut plain);
+        text.push_str(&plain);
+    }
+    for (name, value) in content.fields() {
+        if !NUMBERING_FIELDS.contains(&name.as_str()) {
+            push_value_text(value, text);
+        }
+    }
+}
+
+/// Appends the authored text of the content in a field value.
+fn push_value_text(value: Value, text: &mut String) {
+    match value {
+        Value::Content(content) => push_authored_text(&content, text),
+        Value::Array(array) => {
+            for value in array {
+                push_value_text(value, text);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Checks if an element carries the given label.
+fn has_label(element: &Content, label: &str) -> bool {
+    element
+        .label()
+        .is_some_and(|l| l.resolve().as_str() == label)
+}
+
+/// Returns the level of a heading element, or `None` for other elements.
+fn heading_level(element: &Content) -> Option<usize> {
+    if !element.is::<HeadingElem>() {
+        return None;
+    }
+
+    let level = ["level", "depth"]
+        .into_iter()
+        .find_map(|field| match element.get_by_name(field).ok()? {
+            Value::Int(level) => usize::try_from(level).ok(),
+            _ => None,
+        })
+        .unwrap_or(1);
+    Some(level)
+}
+
+/// Source regions covered by a set of elements.
 ///
-/// # Examples
+/// Used to determine whether an element is nested inside another one, which
+/// the introspector's flat element list doesn't tell us directly.
+struct Regions {
+    /// File and byte range of each region
+    ranges: Vec<(FileId, Range<usize>)>,
+}
+
+impl Regions {
+    /// Collects the source regions spanned by the given elements.
+    ///
+    /// Elements without a resolvable span (e.g. synthesized content) are ignored.
+    fn collect<'a>(world: &dyn World, elements: impl Iterator<Item = &'a Content>) -> Self {
+        let ranges = elements
+            .filter_map(|element| resolve_span(world, element.span()))
+            .collect();
+        Self { ranges }
+    }
+
+    /// Checks if a span lies within any of the regions.
+    fn contains(&self, world: &dyn World, span: Span) -> bool {
+        if self.ranges.is_empty() {
+            return false;
+        }
+        let Some((id, range)) = resolve_span(world, span) else {
+            return false;
+        };
+        self.ranges.iter().any(|(region_id, region)| {
+            *region_id == id && region.start <= range.start && range.end <= region.end
+        })
+    }
+}
+
+impl Regions {
+    /// Collects the source regions of page headers and footers.
+    ///
+    /// These are the `header` and `footer` arguments of `page`, in set rules
+    /// and calls alike, in any of the document's files, including packages
+    /// such as templates.
+    fn page_chrome(world: &dyn World, introspector: &Introspector) -> Self {
+        let mut files = vec![world.main()];
+        for id in introspector.all().filter_map(|element| element.span().id()) {
+            if !files.contains(&id) {
+                files.push(id);
+            }
+        }
+
+        let mut ranges = Vec::new();
+        for id in files {
+            if let Ok(source) = world.source(id) {
+                scan_chrome(&LinkedNode::new(source.root()), id, &mut ranges);
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Checks if any of the regions lies within the source range of a span.
+    fn within(&self, world: &dyn World, span: Span) -> bool {
+        if self.ranges.is_empty() {
+            return false;
+        }
+        let Some((id, range)) = resolve_span(world, span) else {
+            return false;
+        };
+        self.ranges.iter().any(|(region_id, region)| {
+            *region_id == id && range.start <= region.start && region.end <= range.end
+        })
+    }
+}
+
+/// Returns the number of occurrences per 1000 words, rounded.
+fn per_thousand(occurrences: usize, words: usize) -> usize {
+    (occurrences * 1000 + words / 2)
+        .checked_div(words)
+        .unwrap_or(0)
+}
+
+/// Citation statistics of a document, see [`count_citations`].
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Citations {
+    /// Entries in the reference list
+    references: usize,
+    /// Distinct works cited
+    cited_works: usize,
+    /// Citations, counting every occurrence
+    citations: usize,
+}
+
+/// Counts the citations of a document and the entries of its reference list.
 ///
-/// ```ignore
-/// use typst_count::count_document;
+/// Both `@key` references to bibliography entries and `#cite` calls are
+/// citations.
+fn count_citations(world: &dyn World, introspector: &Introspector) -> Citations {
+    let mut keys: Vec<Value> = Vec::new();
+    let mut citations = 0;
+    for element in introspector.all().filter(|e| e.is::<CiteElem>()) {
+        citations += 1;
+        if let Ok(key) = element.get_by_name("key")
+            && !keys.contains(&key)
+        {
+            keys.push(key);
+        }
+    }
+
+    let full: Vec<&Content> = introspector
+        .all()
+        .filter(|e| {
+            e.is::<BibliographyElem>() && matches!(e.get_by_name("full"), Ok(Value::Bool(true)))
+        })
+        .collect();
+    let references = if full.is_empty() {
+        keys.len()
+    } else {
+        full.iter()
+            .map(|bibliography| count_bibliography_entries(world, bibliography))
+            .sum()
+    };
+
+    Citations {
+        references,
+        cited_works: keys.len(),
+        citations,
+    }
+}
+
+/// Counts the entries in the files of a bibliography.
 ///
-/// let count = count_document(&introspector, false, main_file_id);
-/// println!("Words: {}, Characters: {}", count.words, count.characters);
-/// ```
+/// BibLaTeX files (`.bib`) and Hayagriva files (`.yml`, `.yaml`) are
+/// supported; other data sources count as empty.
+fn count_bibliography_entries(world: &dyn World, bibliography: &Content) -> usize {
+    let Some(id) = bibliography.span().id() else {
+        return 0;
+    };
+    let paths = match bibliography.get_by_name("sources") {
+        Ok(Value::Str(path)) => vec![path],
+        Ok(Value::Array(paths)) => paths
+            .into_iter()
+            .filter_map(|path| path.cast::<typst::foundations::Str>().ok())
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            let data = world.file(id.join(path.as_str())).ok()?;
+            let text = std::str::from_utf8(&data).ok()?.to_string();
+            Some(if path.as_str().ends_with(".bib") {
+                count_bib_entries(&text)
+            } else {
+                count_yaml_entries(&text)
+            })
+        })
+        .sum()
+}
+
+/// Counts the entries of a BibLaTeX file, ignoring `@string`, `@preamble`,
+/// and `@comment` blocks.
+fn count_bib_entries(text: &str) -> usize {
+    text.split('@')
+        .skip(1)
+        .filter(|entry| {
+            let kind: String = entry
+                .chars()
+                .take_while(char::is_ascii_alphabetic)
+                .collect::<String>()
+                .to_ascii_lowercase();
+            !kind.is_empty()
+                && entry[kind.len()..].trim_start().starts_with(['{', '('])
+                && !matches!(kind.as_str(), "string" | "preamble" | "comment")
+        })
+        .count()
+}
+
+/// Counts the entries of a Hayagriva file, i.e. its top-level keys.
+fn count_yaml_entries(text: &str) -> usize {
+    text.lines()
+        .filter(|line| !line.starts_with([' ', '\t', '#', '-']) && line.trim_end().contains(':'))
+        .count()
+}
+
+/// Comments found in the sources of a document, see [`count_comments`].
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Comments {
+    /// Number of line and block comments
+    comments: usize,
+    /// Number of comments marking open work
+    todos: usize,
+    /// Words inside comments
+    words: usize,
+}
+
+/// Markers of comments that note open work.
+const TODO_MARKERS: [&str; 3] = ["TODO", "FIXME", "XXX"];
+
+/// Counts the comments in the source files of a document.
 ///
-/// # Counting Method
+/// The sources are the main file and every file that content of the
+/// document comes from, or only the main file if imports are excluded.
+/// Files of packages are never included. With `source`, only that file is
+/// scanned.
+fn count_comments(
+    world: &dyn World,
+    introspector: &Introspector,
+    options: &CountOptions,
+    source: Option<FileId>,
+) -> Comments {
+    let mut files = vec![source.unwrap_or_else(|| world.main())];
+    if source.is_none() && !options.exclude_imports {
+        for id in introspector.all().filter_map(|element| element.span().id()) {
+            if id.package().is_none() && !files.contains(&id) {
+                files.push(id);
+            }
+        }
+    }
+
+    let mut comments = Comments::default();
+    for id in files {
+        if let Ok(source) = world.source(id) {
+            scan_comments(source.root(), options, &mut comments);
+        }
+    }
+    comments
+}
+
+/// Adds the source ranges of the `header` and `footer` arguments of `page`
+/// in a syntax tree to `ranges`.
+fn scan_chrome(node: &LinkedNode, id: FileId, ranges: &mut Vec<(FileId, Range<usize>)>) {
+    let is_page = matches!(node.kind(), SyntaxKind::SetRule | SyntaxKind::FuncCall)
+        && node
+            .children()
+            .find(|child| child.kind() == SyntaxKind::Ident)
+            .is_some_and(|callee| callee.text() == "page");
+    for child in node.children() {
+        if is_page && child.kind() == SyntaxKind::Args {
+            for arg in child
+                .children()
+                .filter(|arg| arg.kind() == SyntaxKind::Named)
+            {
+                if arg
+                    .children()
+                    .next()
+                    .is_some_and(|name| matches!(name.text().as_str(), "header" | "footer"))
+                {
+                    ranges.push((id, arg.range()));
+                }
+            }
+        }
+        scan_chrome(&child, id, ranges);
+    }
+}
+
+/// Adds the comments in a syntax tree to `comments`.
+fn scan_comments(node: &SyntaxNode, options: &CountOptions, comments: &mut Comments) {
+    match node.kind() {
+        SyntaxKind::LineComment | SyntaxKind::BlockComment => {
+            let text = comment_text(node.text());
+            comments.comments += 1;
+            comments.words += options.count_words(text);
+            if text
+                .split(|c: char| !c.is_alphanumeric())
+                .any(|word| TODO_MARKERS.contains(&word))
+            {
+                comments.todos += 1;
+            }
+        }
+        _ => {
+            for child in node.children() {
+                scan_comments(child, options, comments);
+            }
+        }
+    }
+}
+
+/// Strips the comment markers from the text of a comment.
+fn comment_text(comment: &str) -> &str {
+    comment
+        .strip_prefix("//")
+        .or_else(|| {
+            comment
+                .strip_prefix("/*")
+                .map(|text| text.strip_suffix("*/").unwrap_or(text))
+        })
+        .unwrap_or(comment)
+}
+
+/// Checks if a character belongs to a Chinese, Japanese, or Korean script,
+/// including full-width punctuation.
+const fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3000}'..='\u{30FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF00}'..='\u{FFEF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+/// Checks if a word is a raw URL.
 ///
-/// - **Words**: Split by Unicode whitespace (equivalent to Rust's `split_whitespace()`)
-/// - **Characters**: Total Unicode scalar values (equivalent to Rust's `chars().count()`)
+/// Leading brackets are ignored so that parenthesized URLs are recognized.
+fn is_url(word: &str) -> bool {
+    let word = word.trim_start_matches(['(', '[', '<']);
+    ["http://", "https://", "ftp://", "mailto:", "www."]
+        .iter()
+        .any(|prefix| word.starts_with(prefix))
+}
+
+/// Checks if a word is a number, such as `42`, `-3.14`, `1,000`, `50%`, or
+/// `$20`.
 ///
-/// # Avoiding Double-Counting
+/// Surrounding brackets, quotes, and punctuation are ignored.
+fn is_numeric(word: &str) -> bool {
+    let number = trim_punctuation(word)
+        .trim_start_matches(['+', '-', '\u{2212}', '$', '€', '£', '¥'])
+        .trim_end_matches('%');
+    number.starts_with(|c: char| c.is_ascii_digit())
+        && number
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '\''))
+}
+
+/// Checks if a word is a numeric date, such as `2026-03-01`, `01.03.2026`,
+/// or `3/1/26`.
 ///
-/// Typst's document tree includes both container elements and their styled children.
-/// For example, `*bold text*` creates:
-/// - A paragraph element containing "bold text"
-/// - A `strong` element also containing "bold text"
+/// Dates consist of three numbers separated by the same `-`, `/`, or `.`,
+/// with either the year first in four digits or the year last in two or four
+/// digits, so section numbers like `1.2.3` are not mistaken for dates.
+fn is_date(word: &str) -> bool {
+    let date = trim_punctuation(word);
+    let Some(separator) = date.chars().find(|c| matches!(c, '-' | '/' | '.')) else {
+        return false;
+    };
+    let parts: Vec<&str> = date.split(separator).collect();
+    let [first, second, third] = parts[..] else {
+        return false;
+    };
+    let digits = |part: &str, lengths: &[usize]| {
+        lengths.contains(&part.len()) && part.chars().all(|c| c.is_ascii_digit())
+    };
+    digits(second, &[1, 2])
+        && ((digits(first, &[4]) && digits(third, &[1, 2]))
+            || (digits(first, &[1, 2]) && digits(third, &[2, 4])))
+}
+
+/// Counts the characters of text, with each run of whitespace counting as
+/// one character.
+fn collapsed_characters(text: &str) -> usize {
+    let mut characters = 0;
+    let mut previous_space = false;
+    for c in text.chars() {
+        let space = c.is_whitespace();
+        if !(space && previous_space) {
+            characters += 1;
+        }
+        previous_space = space;
+    }
+    characters
+}
+
+/// Removes surrounding brackets, quotes, and trailing punctuation from a word.
+fn trim_punctuation(word: &str) -> &str {
+    word.trim_start_matches(['(', '[', '"', '\'', '“', '‘'])
+        .trim_end_matches([')', ']', '"', '\'', '”', '’', '.', ',', ';', ':', '!', '?'])
+}
+
+/// Counts the words enclosed in quotation marks.
 ///
-/// To avoid counting the same text twice, we skip known styling elements whose
-/// content is already included in their parent elements.
-pub fn count_document(
-    introspector: &Introspector,
-    exclude_imports: bool,
-    main_file_id: FileId,
-) -> Count {
+/// Recognizes straight double quotes as well as English (“…”), German („…“),
+/// and French («…») quotation marks. Single quotes are ignored since they
+/// can't be told apart from apostrophes. Unterminated quotations don't count.
+fn count_quoted_words(text: &str) -> usize {
     let mut words = 0;
-    let mut characters = 0;
+    let mut closing = None;
+    let mut quoted = String::new();
 
-    for element in introspector.all() {
-        // Skip elements from imported/included files if requested
-        if exclude_imports
-            && let Some(file_id) = element.span().id()
-            && file_id != main_file_id
-        {
-            continue;
+    for c in text.chars() {
+        match closing {
+            Some(close) if c == close => {
+                words += quoted.split_whitespace().count();
+                quoted.clear();
+                closing = None;
+            }
+            Some(_) => quoted.push(c),
+            None => closing = closing_quote(c),
         }
+    }
 
-        // Skip styling elements to avoid double-counting.
-        // These elements' text is already included in their parent elements
-        // (typically paragraphs or other text containers).
-        if is_styling_element(element) {
-            continue;
-        }
+    words
+}
 
-        let text = element.plain_text();
-        if !text.is_empty() {
-            characters += text.chars().count();
-            words += text.split_whitespace().count();
-        }
+/// Returns the closing quotation mark for an opening one.
+const fn closing_quote(open: char) -> Option<char> {
+    match open {
+        '"' => Some('"'),
+        '\u{201C}' => Some('\u{201D}'),
+        '\u{201E}' => Some('\u{201C}'),
+        '\u{00AB}' => Some('\u{00BB}'),
+        _ => None,
     }
+}
+
+/// Resolves a span to its file and byte range in that file's source.
+fn resolve_span(world: &dyn World, span: Span) -> Option<(FileId, Range<usize>)> {
+    let id = span.id()?;
+    let range = world.source(id).ok()?.range(span)?;
+    Some((id, range))
+}
+
+/// Resolves a span to the line and column where it starts.
+fn locate(world: &dyn World, span: Span) -> Option<SourceLocation> {
+    let id = span.id()?;
+    let source = world.source(id).ok()?;
+    let offset = source.range(span)?.start;
+    let lines = source.lines();
 
-    Count { words, characters }
+    let path = id.vpath().as_rootless_path().display().to_string();
+    let file = match id.package() {
+        Some(package) => format!("{package}/{path}"),
+        None => path,
+    };
+    Some(SourceLocation {
+        file,
+        line: lines.byte_to_line(offset)? + 1,
+        column: lines.byte_to_column(offset)? + 1,
+    })
 }
 
 /// Checks if an element is a text styling element that should be skipped during counting.
@@ -131,9 +2468,15 @@ pub fn count_document(
 /// - `sub` - Subscript text
 /// - `super` - Superscript text
 /// - `highlight` - Highlighted text
+/// - `smallcaps` - Small capitals
+/// - `text` - Text runs, e.g. from `#text(..)[..]` wrappers in show rules
+/// - `box` - Inline containers, whose content is part of the surrounding paragraph
 /// - `equation` - Math equations (`$...$` or `$ ... $`)
 /// - `raw` - code blocks `code`
 ///
+/// Wrappers defined by templates and packages can be added with
+/// [`CountOptions::styling_elements`].
+///
 /// # Examples
 ///
 /// ```ignore
@@ -142,7 +2485,7 @@ pub fn count_document(
 ///     continue;
 /// }
 /// ```
-fn is_styling_element(element: &typst::foundations::Content) -> bool {
+fn is_styling_element(element: &Content) -> bool {
     element.is::<StrongElem>()
         || element.is::<EmphElem>()
         || element.is::<UnderlineElem>()
@@ -152,18 +2495,23 @@ fn is_styling_element(element: &typst::foundations::Content) -> bool {
         || element.is::<SuperElem>()
         || element.is::<EquationElem>() // Skip math equations
         || element.is::<RawElem>()
+        || element.is::<SmallcapsElem>()
+        || element.is::<TextElem>()
+        || element.is::<BoxElem>()
         || element.func().name() == "highlight" // highlight doesn't have a public struct
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::compile_source;
 
     #[test]
     fn test_count_struct_creation() {
         let count = Count {
             words: 42,
             characters: 256,
+            ..Count::default()
         };
         assert_eq!(count.words, 42);
         assert_eq!(count.characters, 256);
@@ -174,17 +2522,325 @@ mod tests {
         let count1 = Count {
             words: 10,
             characters: 50,
+            ..Count::default()
         };
         let count2 = Count {
             words: 10,
             characters: 50,
+            ..Count::default()
         };
         let count3 = Count {
             words: 11,
             characters: 50,
+            ..Count::default()
         };
 
         assert_eq!(count1, count2);
         assert_ne!(count1, count3);
     }
+
+    #[test]
+    fn test_count_quoted_words() {
+        assert_eq!(count_quoted_words("no quotes here"), 0);
+        assert_eq!(count_quoted_words("He said \"hello there\" twice"), 2);
+        assert_eq!(count_quoted_words("“one” and „zwei drei“ and «quatre»"), 4);
+        assert_eq!(count_quoted_words("it's a \"dangling quote"), 0);
+    }
+
+    #[test]
+    fn test_declared_limit_violations() {
+        let limit = DeclaredLimit {
+            part: "abstract".to_string(),
+            max_words: Some(300),
+            min_words: Some(100),
+            max_characters: None,
+            min_characters: Some(2000),
+            words: 320,
+            characters: 1900,
+            location: None,
+        };
+        assert_eq!(
+            limit.violations(),
+            [
+                "Section `abstract` word count exceeds maximum (320 > 300)",
+                "Section `abstract` character count below minimum (1900 < 2000)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_per_thousand() {
+        assert_eq!(per_thousand(12, 4000), 3);
+        assert_eq!(per_thousand(3, 2000), 2);
+        assert_eq!(per_thousand(5, 0), 0);
+    }
+
+    #[test]
+    fn test_densities_are_recomputed_when_adding() {
+        let mut a = Count {
+            words: 1000,
+            ..Count::default()
+        };
+        a.metrics.set(Metric::Citations, 10);
+        a.metrics.set(Metric::CitationDensity, 10);
+        let mut b = Count {
+            words: 3000,
+            ..Count::default()
+        };
+        b.metrics.set(Metric::Citations, 2);
+        b.metrics.set(Metric::CitationDensity, 1);
+
+        let total = a + b;
+        assert_eq!(total.metrics.get(Metric::Citations), Some(12));
+        assert_eq!(total.metrics.get(Metric::CitationDensity), Some(3));
+    }
+
+    #[test]
+    fn test_count_bibliography_entries() {
+        let bib = "@string{jan = \"January\"}\n\
+                   @article{knuth1984, title = {Literate Programming}}\n\
+                   @book{lamport1994, author = {Lamport}, note = {a@b.c}}\n\
+                   @comment{ignored}";
+        assert_eq!(count_bib_entries(bib), 2);
+
+        let yaml = "# References\nknuth1984:\n  type: article\n  title: Literate Programming\n\
+                    lamport1994:\n  type: book\n";
+        assert_eq!(count_yaml_entries(yaml), 2);
+    }
+
+    #[test]
+    fn test_scan_comments() {
+        let source = typst::syntax::Source::detached(
+            "// TODO: rewrite\nSome text. /* An old\npassage */\n#let x = 1 // FIXME",
+        );
+        let mut comments = Comments::default();
+        scan_comments(source.root(), &CountOptions::default(), &mut comments);
+        assert_eq!(
+            comments,
+            Comments {
+                comments: 3,
+                todos: 2,
+                words: 6,
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_numbering_text() {
+        for numbering in [
+            "1.2.3",
+            "4.",
+            "iv.",
+            "(a)",
+            "A.1",
+            "Figure 4:",
+            "Table 2",
+            "•",
+        ] {
+            assert!(is_numbering_text(numbering), "{numbering}");
+        }
+        for text in ["", "1.2 kg", "Figure four", "See Figure 4", "word", "3rd"] {
+            assert!(!is_numbering_text(text), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_count_words_exclude_urls() {
+        let text = "See https://typst.app/docs (or www.example.com) for details";
+        let mut options = CountOptions::default();
+        assert_eq!(options.count_words(text), 6);
+
+        options.exclude_urls = true;
+        assert_eq!(options.count_words(text), 4);
+    }
+
+    #[test]
+    fn test_count_words_with_pattern() {
+        let text = "A well-known result from 2024.";
+        let options = CountOptions {
+            word_pattern: Some(r"[\w']+".parse().unwrap()),
+            ..CountOptions::default()
+        };
+        assert_eq!(options.count_words(text), 6);
+
+        let options = CountOptions {
+            word_pattern: Some(r"\p{L}+".parse().unwrap()),
+            ..CountOptions::default()
+        };
+        assert_eq!(options.count_words(text), 5);
+        assert!("[".parse::<WordPattern>().is_err());
+    }
+
+    #[test]
+    fn test_numbers_and_dates() {
+        for number in ["42", "-3.14", "1,000", "50%", "$20", "(7)", "2026."] {
+            assert!(is_numeric(number), "{number}");
+        }
+        for word in ["word", "3rd", "1.5kg", "%", "-", "F1"] {
+            assert!(!is_numeric(word), "{word}");
+        }
+        for date in ["2026-03-01", "01.03.2026", "3/1/26", "(2026-3-1),"] {
+            assert!(is_date(date), "{date}");
+        }
+        for word in ["1.2.3", "2026-03", "12-34-5", "2026-03-01T10", "a/b/c"] {
+            assert!(!is_date(word), "{word}");
+        }
+
+        let text = "On 2026-03-01, 42 of 50 (84%) samples passed.";
+        let mut options = CountOptions::default();
+        assert_eq!(options.count_numbers(text), (3, 1));
+        assert_eq!(options.count_words(text), 8);
+        options.exclude_numbers = true;
+        assert_eq!(options.count_words(text), 4);
+    }
+
+    #[test]
+    fn test_collapsed_characters() {
+        assert_eq!(collapsed_characters(""), 0);
+        assert_eq!(collapsed_characters("one two"), 7);
+        assert_eq!(collapsed_characters("  one \t\n two  "), 9);
+    }
+
+    #[test]
+    fn test_count_text() {
+        let options = CountOptions {
+            metrics: vec![
+                Metric::QuotedWords,
+                Metric::ManuscriptPages,
+                Metric::Figures,
+            ],
+            ..CountOptions::default()
+        };
+        let count = count_text("She said \"not yet\".\nDone.\n", &options);
+
+        assert_eq!(count.words, 5);
+        assert_eq!(count.characters, 24);
+        assert_eq!(count.metrics.get(Metric::QuotedWords), Some(2));
+        assert_eq!(count.metrics.get(Metric::ManuscriptPages), Some(1));
+        assert_eq!(count.metrics.get(Metric::Figures), None);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let text = "hy\u{ad}\nphen\u{ad}ated 10\u{a0}kg\u{2060}s zero\u{200b}width".to_string();
+        let options = CountOptions::default();
+        assert_eq!(options.normalize(text.clone()), text);
+
+        let options = CountOptions {
+            normalize: vec![Normalization::SoftHyphens, Normalization::Spaces],
+            ..CountOptions::default()
+        };
+        assert_eq!(options.normalize(text), "hyphenated 10 kgs zero width");
+        assert_eq!(count_text("a\u{ad}\nb", &options).words, 1);
+    }
+
+    #[test]
+    fn test_billable_units() {
+        let text = "我们使用Typst排版。 See typst.app 日本語とEnglish";
+        let mut options = CountOptions::default();
+        // 11 CJK characters and the runs "Typst", "See", "typst.app", "English"
+        assert_eq!(options.billable_units(text), 11 + 4);
+
+        options.billing_region = BillingRegion::Ja;
+        assert_eq!(options.billable_units(text), 11 + 5 + 3 + 9 + 7);
+
+        options.latin_word_units = Some(2);
+        assert_eq!(options.billable_units(text), 11 + 4 * 2);
+    }
+
+    #[test]
+    fn test_headers_and_footers_are_excluded() {
+        let source = "\
+#set page(header: [Running title], footer: context counter(page).display())
+Body text here.
+#pagebreak()
+More body text.";
+        let options = CountOptions {
+            metrics: vec![Metric::HeaderFooterWords],
+            ..CountOptions::default()
+        };
+        let count = compile_source(source, &options).unwrap();
+        assert_eq!(count.words, 6);
+        // "Running title" and the page number on each of the two pages
+        assert_eq!(count.metrics.get(Metric::HeaderFooterWords), Some(6));
+
+        let included = compile_source(
+            source,
+            &CountOptions {
+                include_chrome: true,
+                ..options
+            },
+        )
+        .unwrap();
+        assert_eq!(included.words, 12);
+        assert_eq!(included.metrics.get(Metric::HeaderFooterWords), Some(6));
+    }
+
+    #[test]
+    fn test_float_words() {
+        let source = "\
+Body text here.
+#place(top + right, box[Callout box text])
+More body text.";
+        let options = CountOptions {
+            metrics: vec![Metric::FloatWords],
+            ..CountOptions::default()
+        };
+        let count = compile_source(source, &options).unwrap();
+        assert_eq!(count.words, 9);
+        assert_eq!(count.metrics.get(Metric::FloatWords), Some(3));
+
+        let excluded = compile_source(
+            source,
+            &CountOptions {
+                exclude_floats: true,
+                ..options
+            },
+        )
+        .unwrap();
+        assert_eq!(excluded.words, 6);
+        assert_eq!(excluded.metrics.get(Metric::FloatWords), Some(0));
+    }
+
+    #[test]
+    fn test_outline_entries_are_excluded() {
+        let source = "\
+#outline(title: none)
+= Introduction
+Outlines repeat headings.
+= Method
+Counting words.";
+        let count = compile_source(source, &CountOptions::default()).unwrap();
+        assert_eq!(count.words, 7);
+
+        let included = compile_source(
+            source,
+            &CountOptions {
+                include_outline: true,
+                ..CountOptions::default()
+            },
+        )
+        .unwrap();
+        // The entries repeat "Introduction" and "Method".
+        assert_eq!(included.words, 9);
+    }
+
+    #[test]
+    fn test_numbering_is_excluded() {
+        let source = "\
+#set heading(numbering: \"1.1\")
+= Introduction
+Some text.
+#figure(rect(), caption: [A box])";
+        let options = CountOptions {
+            exclude_numbering: true,
+            ..CountOptions::default()
+        };
+        let count = compile_source(source, &options).unwrap();
+        assert_eq!(count.words, 5);
+
+        let numbered = compile_source(source, &CountOptions::default()).unwrap();
+        // The heading number "1" and the "Figure 1:" of the caption
+        assert_eq!(numbered.words, 8);
+    }
 }
@@ -0,0 +1,353 @@
+//! Counting in a long-running daemon.
+//!
+//! Build systems run typst-count over and over on documents that barely
+//! changed. `typst-count daemon` keeps a process running that serves these
+//! runs over a Unix socket: the system fonts are searched only once, and the
+//! compiler's caches stay warm between runs, so unchanged documents are
+//! counted almost instantly. While a daemon is listening, the command line
+//! sends its arguments to it and only formats the returned counts; without
+//! a daemon, it counts on its own.
+//!
+//! A request is a line of JSON with the working directory and arguments of
+//! the run. The response is a line of JSON with the daemon's version and
+//! either the counts of the files or an error. Clients of another version
+//! count on their own, so a daemon left running across an upgrade doesn't
+//! change the counts.
+//!
+//! Only the counts of a run are delegated. Runs with options that compile
+//! the documents again for other reports, namely `--dedupe-shared`,
+//! `--document-limits`, `--keyword`, `--spellcheck`, `--explain`,
+//! `--with-doc-metadata`, `--bundle`, `--audit`, `--find-duplicates`,
+//! `--terms`, and `--baseline`, count on their own.
+//!
+//! The daemon reads the files of anyone who can connect to it, and its
+//! clients trust the counts it returns. The socket is therefore only
+//! accessible to its owner, and clients only talk to sockets owned by their
+//! own user.
+
+use crate::cli::Cli;
+use crate::counter::Count;
+use crate::report::parse_counts;
+use crate::{count_inputs, load_run_config};
+use anyhow::{Context, Result, anyhow, bail};
+use clap::Parser;
+use serde_json::{Map, Value, json};
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variable overriding the path of the daemon's socket.
+pub const SOCKET_VAR: &str = "TYPST_COUNT_SOCKET";
+
+/// Version of typst-count, which the daemon and its clients must share.
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Time the daemon waits for a client to send its request or read the
+/// response.
+const SERVER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time a client waits for the daemon, after which it counts on its own.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Returns the path of the daemon's socket.
+///
+/// This is `$TYPST_COUNT_SOCKET` if set, otherwise `typst-count.sock` in
+/// `$XDG_RUNTIME_DIR`, or in a directory named after the user's ID in the
+/// temporary directory, which only the user can access.
+#[must_use]
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = env::var_os(SOCKET_VAR) {
+        return PathBuf::from(path);
+    }
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => Path::new(&dir).join("typst-count.sock"),
+        None => fallback_dir().join("typst-count.sock"),
+    }
+}
+
+/// Returns the directory of the socket if there is no runtime directory.
+fn fallback_dir() -> PathBuf {
+    env::temp_dir().join(format!("typst-count-{}", user_id()))
+}
+
+/// Returns the ID of the user running the process.
+fn user_id() -> u32 {
+    rustix::process::getuid().as_raw()
+}
+
+/// Creates the directory of the socket if there is no runtime directory.
+///
+/// The temporary directory is shared with other users, who could create
+/// the directory first, so it is only used if it belongs to the user and
+/// nobody else can access it.
+fn create_fallback_dir(dir: &Path) -> Result<()> {
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => {
+            return Err(e).with_context(|| format!("Failed to create {}", dir.display()));
+        }
+        _ => {}
+    }
+    let metadata =
+        fs::symlink_metadata(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    if !metadata.is_dir() || metadata.uid() != user_id() || metadata.mode() & 0o077 != 0 {
+        bail!(
+            "{} is not a private directory of the current user",
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Checks whether a socket belongs to the user running the process.
+fn owned_by_user(socket: &Path) -> bool {
+    fs::symlink_metadata(socket).is_ok_and(|metadata| metadata.uid() == user_id())
+}
+
+/// Serves counting requests on a socket until the process is stopped.
+///
+/// Requests are handled one at a time, as each runs in the working
+/// directory of its client.
+///
+/// # Errors
+///
+/// Returns an error if another daemon is listening on the socket or the
+/// socket cannot be created.
+pub fn serve(socket: &Path) -> Result<()> {
+    if UnixStream::connect(socket).is_ok() {
+        bail!("A daemon is already listening on {}", socket.display());
+    }
+    if let Some(dir) = socket.parent()
+        && dir == fallback_dir()
+    {
+        create_fallback_dir(dir)?;
+    }
+    let listener =
+        bind(socket).with_context(|| format!("Failed to listen on {}", socket.display()))?;
+    eprintln!("Listening on {}", socket.display());
+
+    for stream in listener.incoming() {
+        let result = stream.map_err(anyhow::Error::from).and_then(|stream| {
+            // A client that stops halfway would keep everyone else waiting.
+            stream.set_read_timeout(Some(SERVER_TIMEOUT))?;
+            stream.set_write_timeout(Some(SERVER_TIMEOUT))?;
+            respond(&stream)
+        });
+        if let Err(e) = result {
+            eprintln!("Warning: {e}");
+        }
+        // Keep the compiler's caches from growing with every request, while
+        // what recent requests used stays warm.
+        comemo::evict(10);
+    }
+    Ok(())
+}
+
+/// Creates a socket that only the user can connect to.
+///
+/// The socket is created under the process's umask, so it is bound in a
+/// private directory first and moved into place once only the user can
+/// access it.
+fn bind(socket: &Path) -> Result<UnixListener> {
+    let parent = match socket.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let dir = tempfile::Builder::new()
+        .prefix(".typst-count-")
+        .tempdir_in(parent)?;
+    let private = dir.path().join("typst-count.sock");
+    let listener = UnixListener::bind(&private)?;
+    fs::set_permissions(&private, fs::Permissions::from_mode(0o600))?;
+    // A socket left behind by a daemon that was killed is replaced.
+    fs::rename(&private, socket)?;
+    Ok(listener)
+}
+
+/// Checks whether a run can be delegated to a daemon.
+///
+/// The daemon only counts the inputs, so runs with options that compile the
+/// documents again for other reports count on their own.
+#[must_use]
+pub fn serves(args: &Cli) -> bool {
+    !(args.dedupe_shared
+        || args.document_limits
+        || !args.keyword.is_empty()
+        || args.spellcheck.is_some()
+        || args.explain
+        || args.with_doc_metadata
+        || args.bundle.is_some()
+        || args.audit
+        || args.find_duplicates
+        || args.terms.is_some()
+        || args.baseline.is_some())
+}
+
+/// Counts the inputs of a run in a running daemon.
+///
+/// `args` are the run's command-line arguments, including the program
+/// name. Returns `None` if no daemon is listening, the socket belongs to
+/// another user, the daemon runs another version of typst-count or doesn't
+/// answer in time, or an argument is not valid UTF-8, so the caller counts
+/// on its own.
+#[must_use]
+pub fn delegate(args: &[OsString]) -> Option<Result<Vec<(String, Count)>>> {
+    let args: Vec<&str> = args.iter().map(|arg| arg.to_str()).collect::<Option<_>>()?;
+    let cwd = env::current_dir().ok()?;
+    let request = json!({ "cwd": cwd.to_str()?, "args": args });
+
+    let socket = socket_path();
+    if !owned_by_user(&socket) {
+        return None;
+    }
+    let mut stream = UnixStream::connect(socket).ok()?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT)).ok()?;
+    writeln!(stream, "{request}").ok()?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line).ok()?;
+    let response: Value = serde_json::from_str(&line).ok()?;
+    if response["version"] != VERSION {
+        return None;
+    }
+    Some(parse_response(&response))
+}
+
+/// Answers a single request.
+fn respond(stream: &UnixStream) -> Result<()> {
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let response = match handle(&line) {
+        Ok(results) => json!({
+            "version": VERSION,
+            "results": results
+                .iter()
+                .map(|(name, count)| entry(name, count))
+                .collect::<Vec<_>>(),
+        }),
+        Err(e) => json!({
+            "version": VERSION,
+            "error": e.to_string(),
+            "causes": e.chain().skip(1).map(ToString::to_string).collect::<Vec<_>>(),
+        }),
+    };
+    let mut stream = stream;
+    writeln!(stream, "{response}")?;
+    Ok(())
+}
+
+/// Counts the inputs of a request like the command line would.
+fn handle(request: &str) -> Result<Vec<(String, Count)>> {
+    let request: Value = serde_json::from_str(request).context("Invalid request")?;
+    let cwd = request["cwd"].as_str().context("Request has no `cwd`")?;
+    let args: Vec<&str> = request["args"]
+        .as_array()
+        .and_then(|args| args.iter().map(Value::as_str).collect())
+        .context("Request has no `args`")?;
+
+    env::set_current_dir(cwd).with_context(|| format!("Failed to change to directory: {cwd}"))?;
+    let mut args = Cli::try_parse_from(args)?;
+    args.apply_preset();
+    let config =
+        load_run_config(&mut args)?.context("no input files given and no groups configured")?;
+    count_inputs(&args, &config)
+}
+
+/// Writes the count of a file as an object like those of a JSON report.
+fn entry(name: &str, count: &Count) -> Value {
+    let mut object = Map::new();
+    object.insert("file".to_string(), json!(name));
+    object.insert("words".to_string(), json!(count.words));
+    object.insert("characters".to_string(), json!(count.characters));
+    for (metric, value) in count.metrics.iter() {
+        object.insert(metric.key().to_string(), json!(value));
+    }
+    if count.approximate {
+        object.insert("approximate".to_string(), json!(true));
+    }
+    Value::Object(object)
+}
+
+/// Reads the counts of a response, or the error the daemon ran into.
+fn parse_response(response: &Value) -> Result<Vec<(String, Count)>> {
+    if let Some(message) = response["error"].as_str() {
+        let causes = response["causes"].as_array().into_iter().flatten();
+        let mut error: Option<anyhow::Error> = None;
+        for cause in causes.rev() {
+            let cause = cause.as_str().unwrap_or_default().to_string();
+            error = Some(match error {
+                Some(error) => error.context(cause),
+                None => anyhow!(cause),
+            });
+        }
+        return Err(match error {
+            Some(error) => error.context(message.to_string()),
+            None => anyhow!(message.to_string()),
+        });
+    }
+    response["results"]
+        .as_array()
+        .context("Invalid response from the daemon")?
+        .iter()
+        .map(|entry| {
+            let Value::Object(object) = entry else {
+                bail!("Invalid response from the daemon");
+            };
+            let name = object
+                .get("file")
+                .and_then(Value::as_str)
+                .context("Invalid response from the daemon")?;
+            Ok((name.to_string(), parse_counts(object)?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::counter::Metric;
+
+    #[test]
+    fn test_response_round_trip() {
+        let mut count = Count {
+            words: 12,
+            characters: 60,
+            approximate: true,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::QuotedWords, 2);
+        let response = json!({
+            "version": VERSION,
+            "results": [entry("a.typ", &count)],
+        });
+        let results = parse_response(&response).unwrap();
+        assert_eq!(results, [("a.typ".to_string(), count)]);
+
+        let error = json!({
+            "version": VERSION,
+            "error": "Failed to compile: a.typ",
+            "causes": ["unknown variable: x"],
+        });
+        let error = parse_response(&error).unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "Failed to compile: a.typ: unknown variable: x"
+        );
+    }
+
+    #[test]
+    fn test_create_fallback_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("typst-count");
+        create_fallback_dir(&dir).unwrap();
+        create_fallback_dir(&dir).unwrap();
+        assert!(owned_by_user(&dir));
+
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(create_fallback_dir(&dir).is_err());
+    }
+}
@@ -0,0 +1,512 @@
+//! Finding the files to count in directories.
+//!
+//! A directory given as input stands for every countable file below it:
+//! Typst documents as well as Markdown and plain text files, so a mixed
+//! project can be counted as a whole.
+//!
+//! Directories that don't hold the project's own writing are skipped:
+//! hidden directories such as `.git`, build output in `target`, and
+//! vendored Typst packages, recognized by their `typst.toml` manifest.
+//! Further files and directories are skipped with exclude patterns.
+//!
+//! Only the entry points among the Typst files are counted, so chapters are
+//! not counted both on their own and as part of the main document. A file is
+//! an entry point if no other file includes or imports it, or if it sets the
+//! document's metadata with `#set document(..)`. Projects where this guess
+//! is wrong list their entry points explicitly.
+
+use crate::revision;
+use anyhow::{Context, Result};
+use glob::Pattern;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use typst::syntax::{SyntaxKind, SyntaxNode};
+
+/// Extensions of the files counted in directories.
+pub const EXTENSIONS: [&str; 4] = ["typ", "md", "markdown", "txt"];
+
+/// Names of directories skipped when searching a directory.
+pub const SKIPPED_DIRS: [&str; 1] = ["target"];
+
+/// How input directories are searched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiscoveryOptions {
+    /// Patterns of files and directories to skip, e.g. `drafts/**`
+    pub exclude: Vec<String>,
+    /// Patterns of the Typst files to count as entry points; detected if
+    /// empty
+    pub entry_points: Vec<String>,
+}
+
+/// Replaces directories among the inputs by the countable files below them.
+///
+/// Files given directly are kept as they are, whatever their extension.
+/// While searching a directory, files and directories matching one of the
+/// exclude patterns are skipped, and of the Typst files only the entry
+/// points are kept. Patterns are relative to the searched directory, e.g.
+/// `drafts/**` or `**/*.txt`.
+///
+/// # Errors
+///
+/// Returns an error if a pattern is invalid, or a directory or Typst file
+/// cannot be read.
+pub fn expand_inputs(inputs: &[PathBuf], options: &DiscoveryOptions) -> Result<Vec<PathBuf>> {
+    expand(inputs, options, None)
+}
+
+/// Replaces directories among the inputs by the countable files below them
+/// as of a git revision.
+///
+/// Works like [`expand_inputs`], but the directories' files are listed and
+/// read from `rev` instead of the working tree, see [`revision::list_files`].
+///
+/// # Errors
+///
+/// Returns an error if a pattern is invalid, or a directory or Typst file
+/// cannot be read at the revision.
+pub fn expand_inputs_at(
+    inputs: &[PathBuf],
+    options: &DiscoveryOptions,
+    rev: &str,
+) -> Result<Vec<PathBuf>> {
+    expand(inputs, options, Some(rev))
+}
+
+/// Expands the inputs from the working tree or, with `rev`, from a git
+/// revision.
+fn expand(
+    inputs: &[PathBuf],
+    options: &DiscoveryOptions,
+    rev: Option<&str>,
+) -> Result<Vec<PathBuf>> {
+    let exclude = compile_patterns(&options.exclude, "exclude")?;
+    let entry_points = compile_patterns(&options.entry_points, "entry point")?;
+    let mut files = Vec::new();
+    for input in inputs {
+        if !input.is_dir() {
+            files.push(input.clone());
+            continue;
+        }
+        let selected = match rev {
+            Some(rev) => select_entry_points(
+                input,
+                find_revision_files(input, rev, &exclude)?,
+                &entry_points,
+                |path| {
+                    let contents = revision::read_file(path, rev)?;
+                    String::from_utf8(contents)
+                        .with_context(|| format!("Failed to read file: {}", path.display()))
+                },
+                |path| Some(normalize(path)),
+            )?,
+            None => {
+                let mut found = Vec::new();
+                find_files(input, input, &exclude, &mut found)?;
+                select_entry_points(
+                    input,
+                    found,
+                    &entry_points,
+                    |path| {
+                        fs::read_to_string(path)
+                            .with_context(|| format!("Failed to read file: {}", path.display()))
+                    },
+                    |path| path.canonicalize().ok(),
+                )?
+            }
+        };
+        files.extend(selected);
+    }
+    Ok(files)
+}
+
+/// Checks whether a file is a Markdown or plain text file, which is read as
+/// text instead of being compiled.
+#[must_use]
+pub fn is_plain(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        EXTENSIONS[1..]
+            .iter()
+            .any(|plain| ext.eq_ignore_ascii_case(plain))
+    })
+}
+
+/// Collects the countable files below `dir`, in sorted order.
+///
+/// `root` is the directory the search started from, which exclude patterns
+/// are relative to.
+fn find_files(
+    root: &Path,
+    dir: &Path,
+    exclude: &[Pattern],
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let mut entries = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?;
+    entries.sort();
+
+    for path in entries {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        if exclude.iter().any(|pattern| pattern.matches_path(relative)) {
+            continue;
+        }
+        if path.is_dir() {
+            if !is_skipped(&path) {
+                find_files(root, &path, exclude, files)?;
+            }
+        } else if is_countable(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Collects the countable files below `root` as of `rev`, in sorted order.
+///
+/// Files are skipped the same way as by [`find_files`], based on the paths
+/// listed at the revision.
+fn find_revision_files(root: &Path, rev: &str, exclude: &[Pattern]) -> Result<Vec<PathBuf>> {
+    let mut listed = revision::list_files(root, rev)?;
+    listed.sort();
+    let packages: Vec<&Path> = listed
+        .iter()
+        .filter(|path| path.file_name().is_some_and(|name| name == "typst.toml"))
+        .filter_map(|path| path.parent())
+        .collect();
+    let excluded = |path: &Path| exclude.iter().any(|pattern| pattern.matches_path(path));
+    Ok(listed
+        .iter()
+        .filter(|relative| {
+            !excluded(relative)
+                && is_countable(relative)
+                && !relative
+                    .ancestors()
+                    .skip(1)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .any(|dir| excluded(dir) || is_skipped_name(dir) || packages.contains(&dir))
+        })
+        .map(|relative| root.join(relative))
+        .collect())
+}
+
+/// Checks whether a file has one of the [`EXTENSIONS`].
+fn is_countable(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        EXTENSIONS
+            .iter()
+            .any(|known| ext.eq_ignore_ascii_case(known))
+    })
+}
+
+/// Keeps the plain text files and the Typst entry points among `files`.
+///
+/// With `patterns`, the entry points are the Typst files matching one of
+/// them. Otherwise they are detected as described in the module docs, with
+/// `read` returning the text of a file and `identify` a path that is the
+/// same for every path of a file, e.g. its canonical path.
+fn select_entry_points(
+    root: &Path,
+    files: Vec<PathBuf>,
+    patterns: &[Pattern],
+    read: impl Fn(&Path) -> Result<String>,
+    identify: impl Fn(&Path) -> Option<PathBuf>,
+) -> Result<Vec<PathBuf>> {
+    if !patterns.is_empty() {
+        return Ok(files
+            .into_iter()
+            .filter(|path| {
+                let relative = path.strip_prefix(root).unwrap_or(path);
+                is_plain(path)
+                    || patterns
+                        .iter()
+                        .any(|pattern| pattern.matches_path(relative))
+            })
+            .collect());
+    }
+
+    let mut included = Vec::new();
+    let mut standalone = Vec::new();
+    for path in files.iter().filter(|path| !is_plain(path)) {
+        let text = read(path)?;
+        let mut references = Vec::new();
+        let sets_document = scan_references(&typst::syntax::parse(&text), &mut references);
+        if sets_document {
+            standalone.push(path.clone());
+        }
+        let dir = path.parent().unwrap_or(root);
+        for reference in references {
+            let target = match reference.strip_prefix('/') {
+                Some(absolute) => root.join(absolute),
+                None => dir.join(reference),
+            };
+            included.extend(identify(&target));
+        }
+    }
+
+    Ok(files
+        .into_iter()
+        .filter(|path| {
+            is_plain(path)
+                || standalone.contains(path)
+                || identify(path).is_none_or(|path| !included.contains(&path))
+        })
+        .collect())
+}
+
+/// Collects the paths of the files a syntax tree includes or imports.
+///
+/// Returns whether the tree contains a `set document(..)` rule.
+fn scan_references(node: &SyntaxNode, references: &mut Vec<String>) -> bool {
+    match node.kind() {
+        SyntaxKind::ModuleInclude | SyntaxKind::ModuleImport => {
+            if let Some(path) = node
+                .children()
+                .find(|child| child.kind() == SyntaxKind::Str)
+            {
+                references.push(path.text().trim_matches('"').to_string());
+            }
+            false
+        }
+        SyntaxKind::SetRule => node
+            .children()
+            .any(|child| child.kind() == SyntaxKind::Ident && child.text() == "document"),
+        _ => {
+            let mut sets_document = false;
+            for child in node.children() {
+                sets_document |= scan_references(child, references);
+            }
+            sets_document
+        }
+    }
+}
+
+/// Compiles patterns from the command line or configuration file.
+fn compile_patterns(patterns: &[String], kind: &str) -> Result<Vec<Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Pattern::new(pattern).with_context(|| format!("Invalid {kind} pattern: {pattern}"))
+        })
+        .collect()
+}
+
+/// Checks whether a directory is skipped by default: hidden directories,
+/// the [`SKIPPED_DIRS`], and Typst packages.
+fn is_skipped(dir: &Path) -> bool {
+    is_skipped_name(dir) || dir.join("typst.toml").is_file()
+}
+
+/// Checks whether a directory is skipped by its name: hidden directories and
+/// the [`SKIPPED_DIRS`].
+fn is_skipped_name(dir: &Path) -> bool {
+    let name = dir.file_name().unwrap_or_default().to_string_lossy();
+    name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref())
+}
+
+/// Resolves `.` and `..` components without accessing the file system, for
+/// files that only exist at a git revision.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_inputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("docs/chapters")).unwrap();
+        for file in [
+            "docs/main.typ",
+            "docs/chapters/one.typ",
+            "docs/notes.md",
+            "docs/todo.txt",
+            "docs/logo.png",
+            "extra.pdf",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        let files = expand_inputs(
+            &[root.join("docs"), root.join("extra.pdf")],
+            &DiscoveryOptions::default(),
+        )
+        .unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| {
+                file.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "docs/chapters/one.typ",
+                "docs/main.typ",
+                "docs/notes.md",
+                "docs/todo.txt",
+                "extra.pdf"
+            ]
+        );
+        assert!(is_plain(Path::new("docs/notes.md")));
+        assert!(!is_plain(Path::new("docs/main.typ")));
+        assert!(!is_plain(Path::new("extra.pdf")));
+    }
+
+    #[test]
+    fn test_expand_inputs_skips_excluded() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for subdir in [".git", "target", "drafts/old", "packages/lib", "chapters"] {
+            fs::create_dir_all(root.join(subdir)).unwrap();
+        }
+        for file in [
+            "main.typ",
+            "notes.txt",
+            ".git/stash.typ",
+            "target/generated.typ",
+            "drafts/old/intro.typ",
+            "packages/lib/typst.toml",
+            "packages/lib/lib.typ",
+            "chapters/one.typ",
+            "chapters/one.txt",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        let options = DiscoveryOptions {
+            exclude: vec!["drafts/**".to_string(), "**/*.txt".to_string()],
+            ..DiscoveryOptions::default()
+        };
+        let files = expand_inputs(&[root.to_path_buf()], &options).unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| {
+                file.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        assert_eq!(names, ["chapters/one.typ", "main.typ"]);
+
+        let invalid = DiscoveryOptions {
+            exclude: vec!["[".to_string()],
+            ..DiscoveryOptions::default()
+        };
+        assert!(expand_inputs(&[root.to_path_buf()], &invalid).is_err());
+    }
+
+    #[test]
+    fn test_expand_inputs_keeps_entry_points() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("chapters")).unwrap();
+        for (file, text) in [
+            (
+                "main.typ",
+                "#import \"template.typ\": *\n#include \"chapters/one.typ\"",
+            ),
+            ("template.typ", "#let conf(body) = body"),
+            ("chapters/one.typ", "#include \"/chapters/two.typ\"\nOne."),
+            ("chapters/two.typ", "Two."),
+            (
+                "chapters/standalone.typ",
+                "#set document(title: [Talk])\nThree.",
+            ),
+            ("appendix.typ", "#include \"chapters/standalone.typ\""),
+        ] {
+            fs::write(root.join(file), text).unwrap();
+        }
+        let names = |options: &DiscoveryOptions| -> Vec<String> {
+            expand_inputs(&[root.to_path_buf()], options)
+                .unwrap()
+                .iter()
+                .map(|file| {
+                    file.strip_prefix(root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            names(&DiscoveryOptions::default()),
+            ["appendix.typ", "chapters/standalone.typ", "main.typ"]
+        );
+        let explicit = DiscoveryOptions {
+            entry_points: vec!["main.typ".to_string()],
+            ..DiscoveryOptions::default()
+        };
+        assert_eq!(names(&explicit), ["main.typ"]);
+    }
+
+    #[test]
+    fn test_expand_inputs_at() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for subdir in ["chapters", "packages/lib", "drafts"] {
+            fs::create_dir_all(root.join(subdir)).unwrap();
+        }
+        for (file, text) in [
+            ("main.typ", "#include \"chapters/../chapters/one.typ\""),
+            ("chapters/one.typ", "One."),
+            ("notes.md", "# Notes"),
+            ("drafts/old.typ", "Old."),
+            ("packages/lib/typst.toml", ""),
+            ("packages/lib/lib.typ", ""),
+        ] {
+            fs::write(root.join(file), text).unwrap();
+        }
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(root)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "Draft"]);
+        fs::remove_file(root.join("notes.md")).unwrap();
+        fs::write(root.join("later.typ"), "Later.").unwrap();
+
+        let options = DiscoveryOptions {
+            exclude: vec!["drafts".to_string()],
+            ..DiscoveryOptions::default()
+        };
+        let files = expand_inputs_at(&[root.to_path_buf()], &options, "HEAD").unwrap();
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| {
+                file.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        assert_eq!(names, ["main.typ", "notes.md"]);
+        assert!(expand_inputs_at(&[root.to_path_buf()], &options, "no-such-rev").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+//! Running a user command after each count.
+//!
+//! `--exec` hands the counts to any tool, e.g. to send a notification,
+//! upload the report, or regenerate a badge, without an integration for
+//! every service. The command runs in the shell after the report is
+//! written, and again after every recount in watch mode.
+
+use crate::cli::Cli;
+use crate::counter::Count;
+use crate::output::calculate_total;
+use anyhow::{Context, Result, bail};
+use std::process::Command;
+
+/// Runs a command with the counts of a run substituted in.
+///
+/// `{json}` in the command is replaced by the report `--format json` would
+/// write and `{words}` by the total word count. The report is quoted for the
+/// shell, so the placeholder must not be quoted again.
+///
+/// # Errors
+///
+/// Returns an error if the shell cannot be started or the command fails.
+pub fn run(command: &str, args: &Cli, results: &[(String, Count)]) -> Result<()> {
+    let json = args.json_report(results);
+    let command = substitute(command, &json, calculate_total(results).words);
+    let status = shell(&command)
+        .status()
+        .with_context(|| format!("Failed to run command: {command}"))?;
+    if !status.success() {
+        bail!("Command failed with {status}: {command}");
+    }
+    Ok(())
+}
+
+/// Replaces the placeholders of a command.
+fn substitute(command: &str, json: &str, words: usize) -> String {
+    command
+        .replace("{words}", &words.to_string())
+        .replace("{json}", &quote(json.trim_end()))
+}
+
+/// Quotes text as a single argument for the shell.
+#[cfg(not(windows))]
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Quotes text as a single argument for the shell.
+#[cfg(windows)]
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+/// Returns the shell command running a command line.
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+/// Returns the shell command running a command line.
+///
+/// `cmd` doesn't split its command line like other programs, so the command
+/// is passed as is rather than escaped as an argument. `/S` makes it strip
+/// only the outer quotes added here.
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    let mut shell = Command::new("cmd");
+    shell.raw_arg(format!("/S /C \"{command}\""));
+    shell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_run() {
+        let json = "{\"file\":\"it's.typ\",\"words\":12}\n";
+        let command = substitute("notify {words} {json}", json, 12);
+        assert_eq!(command, r#"notify 12 '{"file":"it'\''s.typ","words":12}'"#);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.json");
+        let command = format!("printf %s {{json}} > '{}'", path.display());
+        let results = [(
+            "it's.typ".to_string(),
+            Count {
+                words: 12,
+                characters: 60,
+                ..Count::default()
+            },
+        )];
+        let args = Cli::parse_from(["typst-count"]);
+        run(&command, &args, &results).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            r#"{"words":12,"characters":60}"#
+        );
+        assert!(run("exit 3", &args, &results).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+//! HTTP requests to other services.
+//!
+//! Webhooks, uploads, and the lint checker make their requests with one
+//! agent, which uses the system's TLS library like package downloads do
+//! and honours the usual proxy environment variables.
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time a request may take before it is given up on.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Returns an agent for requests to other services.
+///
+/// # Errors
+///
+/// Returns an error if the TLS library cannot be initialized.
+pub fn agent() -> Result<ureq::Agent> {
+    let tls = native_tls::TlsConnector::new().context("Failed to initialize TLS")?;
+    Ok(ureq::AgentBuilder::new()
+        .tls_connector(Arc::new(tls))
+        .try_proxy_from_env(true)
+        .timeout(TIMEOUT)
+        .build())
+}
+
+/// Describes a failed request, with the body of an error response.
+#[must_use]
+pub fn describe(error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(status, response) => {
+            let body = response.into_string().unwrap_or_default();
+            match body.trim() {
+                "" => format!("status {status}"),
+                body => format!("status {status}: {body}"),
+            }
+        }
+        error => error.to_string(),
+    }
+}
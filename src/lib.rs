@@ -14,6 +14,7 @@
 //! - Handle imported and included files
 //! - Multiple output formats (human-readable, JSON, CSV)
 //! - CI/CD integration with limit checking
+//! - Venue presets that exclude captions, footnotes, or references
 //!
 //! # Examples
 //!
@@ -25,16 +26,69 @@
 //! let count = compile_document(path, false).unwrap();
 //! println!("Words: {}, Characters: {}", count.words, count.characters);
 //! ```
+pub mod analysis;
+pub mod certificate;
 pub mod cli;
+pub mod config;
 pub mod counter;
+#[cfg(unix)]
+pub mod daemon;
+pub mod discovery;
+pub mod exec;
+pub mod http;
+pub mod lint;
+pub mod markup;
+pub mod metadata;
 pub mod output;
+pub mod pdf;
+pub mod plain;
+pub mod preset;
+pub mod progress;
+pub mod report;
+pub mod revision;
+pub mod sarif;
+pub mod spelling;
+pub mod terms;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+pub mod upload;
+pub mod watch;
+pub mod webhook;
+pub mod wordlists;
 pub mod world;
 
 use anyhow::{Context, Result};
+use chrono::NaiveDate;
 use cli::Cli;
-use counter::Count;
-use std::path::Path;
-use typst::{World, layout::PagedDocument};
+use config::{Config, Group, Milestone, Score};
+use counter::{Audit, Count, CountOptions, DeclaredLimit, Metric, TextChunk};
+use metadata::DocumentInfo;
+use sarif::Violation;
+use spelling::Typo;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use typst::World;
+use typst::diag::SourceDiagnostic;
+use typst::layout::PagedDocument;
+use typst::syntax::package::PackageVersion;
+use typst::syntax::{FileId, VirtualPath};
+use wordlists::WordFilter;
+
+/// Returns the version of the Typst compiler that documents are compiled with.
+///
+/// Counts can legitimately differ between compiler versions, e.g. when the
+/// layout of an element changes, so reports should record which one was used.
+///
+/// # Examples
+///
+/// ```
+/// println!("Counted with Typst {}", typst_count::compiler_version());
+/// ```
+#[must_use]
+pub fn compiler_version() -> String {
+    PackageVersion::compiler().to_string()
+}
 
 /// Compiles a Typst document and counts its words and characters.
 ///
@@ -73,31 +127,704 @@ use typst::{World, layout::PagedDocument};
 /// # Ok::<(), anyhow::Error>(())
 /// ```
 pub fn compile_document(path: &Path, exclude_imports: bool) -> Result<Count> {
-    let world = world::SimpleWorld::new(path)
-        .with_context(|| format!("Failed to load {}", path.display()))?;
-    let main_file_id = world.main();
+    let options = CountOptions {
+        exclude_imports,
+        ..CountOptions::default()
+    };
+    compile_document_with(path, &options)
+}
 
-    let result = typst::compile(&world);
-    let document: PagedDocument = result.output.map_err(|errors| {
-        let error_msg = errors
-            .iter()
-            .map(|e| format!("{}", e.message))
-            .collect::<Vec<_>>()
-            .join(", ");
-        anyhow::anyhow!("Failed to compile {}: {}", path.display(), error_msg)
-    })?;
+/// Compiles a Typst document and counts it with the given options.
+///
+/// This is the general form of [`compile_document`], allowing captions,
+/// footnotes, and other parts of the document to be excluded.
+///
+/// With [`CountOptions::approximate_failed`], a document that fails to
+/// compile is counted from its source text instead, see
+/// [`markup::source_text`], and the count is marked as approximate.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+///
+/// # Examples
+///
+/// ```no_run
+/// use typst_count::compile_document_with;
+/// use typst_count::counter::CountOptions;
+/// use std::path::Path;
+///
+/// let options = CountOptions {
+///     exclude_bibliography: true,
+///     ..CountOptions::default()
+/// };
+/// let count = compile_document_with(Path::new("document.typ"), &options)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn compile_document_with(path: &Path, options: &CountOptions) -> Result<Count> {
+    count_with_dependencies(path, options).map(|(count, _)| count)
+}
+
+/// Compiles a Typst document and counts it like [`compile_document_with`],
+/// also returning the paths of the project files the compilation read.
+///
+/// The document itself is always among them. Watch mode recounts the
+/// document when one of them changes.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn count_with_dependencies(
+    path: &Path,
+    options: &CountOptions,
+) -> Result<(Count, Vec<PathBuf>)> {
+    count_compiled(
+        path,
+        options,
+        |world, _| world.dependencies(),
+        vec![path.to_path_buf()],
+    )
+}
+
+/// Compiles a Typst document and counts it like [`compile_document_with`],
+/// also returning the counts of its files, see [`count_document_sources`].
+///
+/// A document counted approximately because it failed to compile, see
+/// [`CountOptions::approximate_failed`], has no counts by file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn count_with_sources(
+    path: &Path,
+    options: &CountOptions,
+) -> Result<(Count, Vec<(PathBuf, Count)>)> {
+    let root = path
+        .canonicalize()?
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    count_compiled(
+        path,
+        options,
+        |world, document| source_counts(world, document, &root, options),
+        Vec::new(),
+    )
+}
+
+/// Compiles a Typst document and counts it, also returning what `inspect`
+/// takes from the compiled document, or `fallback` if the document failed
+/// to compile and was counted approximately.
+fn count_compiled<T>(
+    path: &Path,
+    options: &CountOptions,
+    inspect: impl FnOnce(&world::SimpleWorld, &PagedDocument) -> T,
+    fallback: T,
+) -> Result<(Count, T)> {
+    let start = Instant::now();
+    let (world, document) = match compile(path, options) {
+        Ok(compiled) => compiled,
+        Err(e) if options.approximate_failed => {
+            eprintln!("Warning: {e:#}; counted approximately from the source text");
+            let text = markup::source_text(path)?;
+            let count = Count {
+                approximate: true,
+                ..counter::count_text(&text, options)
+            };
+            return Ok((count, fallback));
+        }
+        Err(e) => return Err(e),
+    };
+    let elapsed = start.elapsed();
+    let mut count = counter::count_document(&world, &document.introspector, options);
+    if options.metrics.contains(&Metric::CompileMs) {
+        let millis = usize::try_from(elapsed.as_millis()).unwrap_or(usize::MAX);
+        count.metrics.set(Metric::CompileMs, millis);
+    }
+    Ok((count, inspect(&world, &document)))
+}
+
+/// Compiles a Typst document held in memory and counts it.
+///
+/// The source is compiled as `/main.typ` in a virtual world without access
+/// to the filesystem, which suits services and tests that don't have the
+/// document on disk. Use [`compile_source_with_files`] if the document
+/// includes other files.
+///
+/// # Errors
+///
+/// Returns an error if the source fails to compile.
+///
+/// # Examples
+///
+/// ```
+/// use typst_count::compile_source;
+/// use typst_count::counter::CountOptions;
+///
+/// let count = compile_source("Hello *world*.", &CountOptions::default())?;
+/// assert_eq!(count.words, 2);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn compile_source(source: &str, options: &CountOptions) -> Result<Count> {
+    compile_source_with_files(source, &[], options)
+}
 
+/// Compiles a Typst document held in memory, together with supplemental
+/// in-memory files, and counts it.
+///
+/// Each file is given as a path relative to the main document and its
+/// contents, and can be included, imported, or read by the document.
+///
+/// # Errors
+///
+/// Returns an error if the source fails to compile, e.g. because it reads a
+/// file that wasn't supplied.
+///
+/// # Examples
+///
+/// ```
+/// use typst_count::compile_source_with_files;
+/// use typst_count::counter::CountOptions;
+///
+/// let files = [("intro.typ", "Included words.".as_bytes())];
+/// let count =
+///     compile_source_with_files("#include \"intro.typ\"", &files, &CountOptions::default())?;
+/// assert_eq!(count.words, 2);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn compile_source_with_files(
+    source: &str,
+    files: &[(&str, &[u8])],
+    options: &CountOptions,
+) -> Result<Count> {
+    let world = files.iter().fold(
+        world::SimpleWorld::from_source(source),
+        |world, (path, data)| world.with_file(path, *data),
+    );
+    let (world, document) = typeset(world, "source", options)?;
     Ok(counter::count_document(
+        &world,
         &document.introspector,
-        exclude_imports,
-        main_file_id,
+        options,
+    ))
+}
+
+/// Compiles a Typst project held entirely in memory and counts it.
+///
+/// `files` maps the virtual path of every source file and asset of the
+/// project to its contents, and `main` selects the document to count. See
+/// [`world::SimpleWorld::from_files`].
+///
+/// # Errors
+///
+/// Returns an error if `main` is not one of the files or the document fails
+/// to compile.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use typst::syntax::VirtualPath;
+/// use typst_count::compile_files;
+/// use typst_count::counter::CountOptions;
+///
+/// let files = HashMap::from([
+///     (VirtualPath::new("thesis.typ"), b"#include \"chapters/ch1.typ\"".to_vec()),
+///     (VirtualPath::new("chapters/ch1.typ"), b"= Introduction\nFirst words.".to_vec()),
+/// ]);
+/// let count = compile_files(&VirtualPath::new("thesis.typ"), files, &CountOptions::default())?;
+/// assert_eq!(count.words, 3);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn compile_files(
+    main: &VirtualPath,
+    files: HashMap<VirtualPath, Vec<u8>>,
+    options: &CountOptions,
+) -> Result<Count> {
+    let world = world::SimpleWorld::from_files(main, files)?;
+    let name = main.as_rootless_path().display().to_string();
+    let (world, document) = typeset(world, &name, options)?;
+    Ok(counter::count_document(
+        &world,
+        &document.introspector,
+        options,
     ))
 }
 
+/// Compiles a Typst document as of a git revision and counts it.
+///
+/// The document and the files next to it are read from the commit without
+/// checking anything out, see [`revision::read_snapshot`].
+///
+/// # Errors
+///
+/// Returns an error if the files cannot be read from the revision or the
+/// document fails to compile.
+///
+/// # Examples
+///
+/// ```no_run
+/// use typst_count::compile_revision;
+/// use typst_count::counter::CountOptions;
+/// use std::path::Path;
+///
+/// let count = compile_revision(Path::new("thesis.typ"), "v1.0", &CountOptions::default())?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn compile_revision(path: &Path, rev: &str, options: &CountOptions) -> Result<Count> {
+    let snapshot = revision::read_snapshot(path, rev)?;
+    compile_files(&snapshot.main, snapshot.files, options)
+        .with_context(|| format!("Failed to count {} at revision {rev}", path.display()))
+}
+
+/// Counts documents at every commit that changed them.
+///
+/// Returns the commits listed by [`revision::history`], oldest first, each
+/// with the total count of all documents at that commit. The count is `None`
+/// if a document doesn't exist or fails to compile at the commit, e.g.
+/// before it was created.
+///
+/// # Errors
+///
+/// Returns an error if the history cannot be read from git.
+pub fn count_history(
+    paths: &[PathBuf],
+    since: Option<&str>,
+    options: &CountOptions,
+) -> Result<Vec<(revision::Commit, Option<Count>)>> {
+    let commits = revision::history(paths, since)?;
+    Ok(commits
+        .into_iter()
+        .map(|commit| {
+            let count = paths
+                .iter()
+                .try_fold(Count::default(), |total, path| {
+                    Ok::<_, anyhow::Error>(total + compile_revision(path, &commit.sha, options)?)
+                })
+                .ok();
+            (commit, count)
+        })
+        .collect())
+}
+
+/// Counts documents as of each milestone's date.
+///
+/// The count of a milestone is the total count of all documents at the last
+/// commit on or before its date. Only milestones due before `today` are
+/// counted; the others, and milestones before the first counted commit, get
+/// `None`.
+///
+/// # Errors
+///
+/// Returns an error if the history cannot be read from git.
+pub fn count_at_milestones(
+    paths: &[PathBuf],
+    milestones: &[Milestone],
+    today: NaiveDate,
+    options: &CountOptions,
+) -> Result<Vec<Option<Count>>> {
+    let commits = revision::history(paths, None)?;
+    Ok(milestones
+        .iter()
+        .map(|milestone| {
+            if milestone.date >= today {
+                return None;
+            }
+            // Commit dates are `YYYY-MM-DD`, so they sort like the dates.
+            let date = milestone.date.format("%Y-%m-%d").to_string();
+            let commit = commits.iter().rev().find(|commit| commit.date <= date)?;
+            paths
+                .iter()
+                .try_fold(Count::default(), |total, path| {
+                    Ok::<_, anyhow::Error>(total + compile_revision(path, &commit.sha, options)?)
+                })
+                .ok()
+        })
+        .collect())
+}
+
+/// Compiles a Typst document and extracts the text that would be counted.
+///
+/// Returns one string per counted element, in document order. See
+/// [`counter::extract_text`] for details.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn extract_document_text(path: &Path, options: &CountOptions) -> Result<Vec<String>> {
+    let (world, document) = compile(path, options)?;
+    Ok(counter::extract_text(
+        &world,
+        &document.introspector,
+        options,
+    ))
+}
+
+/// Compiles a Typst document and extracts the counted text with its origin.
+///
+/// See [`counter::extract_chunks`] for details.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn extract_document_chunks(path: &Path, options: &CountOptions) -> Result<Vec<TextChunk>> {
+    let (world, document) = compile(path, options)?;
+    Ok(counter::extract_chunks(
+        &world,
+        &document.introspector,
+        options,
+    ))
+}
+
+/// Compiles a Typst document and counts it, passing every counted piece of
+/// text to a callback.
+///
+/// See [`counter::count_document_with`] for details.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn stream_document(
+    path: &Path,
+    options: &CountOptions,
+    on_chunk: impl FnMut(TextChunk),
+) -> Result<Count> {
+    let (world, document) = compile(path, options)?;
+    Ok(counter::count_document_with(
+        &world,
+        &document.introspector,
+        options,
+        on_chunk,
+    ))
+}
+
+/// Compiles a Typst document and audits how it is counted.
+///
+/// See [`counter::audit`] for what is reported.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn audit_document(path: &Path, options: &CountOptions) -> Result<Audit> {
+    let (world, document) = compile(path, options)?;
+    Ok(counter::audit(&world, &document.introspector, options))
+}
+
+/// Compiles a Typst document and counts the words in each section.
+///
+/// See [`counter::count_sections`] for how sections are delimited.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn count_document_sections(
+    path: &Path,
+    options: &CountOptions,
+) -> Result<Vec<counter::SectionCount>> {
+    let (world, document) = compile(path, options)?;
+    Ok(counter::count_sections(
+        &world,
+        &document.introspector,
+        options,
+    ))
+}
+
+/// Compiles a Typst document and counts the text coming from each of its
+/// files.
+///
+/// See [`counter::count_sources`] for how text is attributed. Files are
+/// identified by their path on disk; text from package files is left out.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn count_document_sources(
+    path: &Path,
+    options: &CountOptions,
+) -> Result<Vec<(PathBuf, Count)>> {
+    let (world, document) = compile(path, options)?;
+    let root = path
+        .canonicalize()?
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    Ok(source_counts(&world, &document, &root, options))
+}
+
+/// Counts the text coming from each file of a compiled document, by the
+/// file's path below `root`, the document's directory.
+fn source_counts(
+    world: &world::SimpleWorld,
+    document: &PagedDocument,
+    root: &Path,
+    options: &CountOptions,
+) -> Vec<(PathBuf, Count)> {
+    counter::count_sources(world, &document.introspector, options)
+        .into_iter()
+        .filter(|(id, _)| id.package().is_none())
+        .map(|(id, count)| (root.join(id.vpath().as_rootless_path()), count))
+        .collect()
+}
+
+/// Results of counting the inputs with shared files counted once, see
+/// [`dedupe_shared`].
+#[derive(Debug, Clone, Default)]
+pub struct Deduplicated {
+    /// Name and count of each input
+    pub results: Vec<(String, Count)>,
+    /// Words of shared files left out of a result, by the result's name
+    pub shared_words: HashMap<String, usize>,
+}
+
+/// Counts the input files like [`process_files`], leaving files included by
+/// several inputs out of all but the first input's count.
+///
+/// Each document's text is attributed to its files while it is counted, see
+/// [`count_with_sources`]. The count of a file already counted for an
+/// earlier input, with all its metrics, is subtracted from the result of a
+/// later one, so the total counts every file once. Documents counted
+/// approximately can't be attributed and are kept whole.
+///
+/// # Errors
+///
+/// Returns an error if an input fails to compile.
+pub fn dedupe_shared(args: &Cli) -> Result<Deduplicated> {
+    let options = args.count_options();
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut shared_words = HashMap::new();
+    let results = count_files(args, |path| {
+        let (mut count, sources) = count_with_sources(path, &options)?;
+        for (source, source_count) in sources {
+            if !seen.contains(&source) {
+                seen.push(source);
+                continue;
+            }
+            count -= source_count;
+            *shared_words.entry(path.display().to_string()).or_default() += source_count.words;
+        }
+        if options.metrics.contains(&Metric::ManuscriptPages) {
+            let pages = options.manuscript_pages(count.words);
+            count.metrics.set(Metric::ManuscriptPages, pages);
+        }
+        Ok(count)
+    })?;
+    Ok(Deduplicated {
+        results,
+        shared_words,
+    })
+}
+
+/// Compiles a Typst document and counts the words written by each author.
+///
+/// See [`counter::count_authors`] for how authors are declared.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn count_document_authors(
+    path: &Path,
+    options: &CountOptions,
+) -> Result<Vec<counter::AuthorCount>> {
+    let (world, document) = compile(path, options)?;
+    Ok(counter::count_authors(
+        &world,
+        &document.introspector,
+        options,
+    ))
+}
+
+/// Compiles a Typst document and reads the title and authors it declares.
+///
+/// The document is compiled with the same `options` as when counting, e.g.
+/// with the same inputs and font fallback.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn document_info(path: &Path, options: &CountOptions) -> Result<DocumentInfo> {
+    let (_, document) = compile(path, options)?;
+    Ok(DocumentInfo {
+        title: document.info.title.as_ref().map(ToString::to_string),
+        authors: document
+            .info
+            .author
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    })
+}
+
+/// Compiles a Typst document and counts the parts that declare limits.
+///
+/// See [`counter::declared_limits`] for how limits are declared.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn document_limits(path: &Path, options: &CountOptions) -> Result<Vec<DeclaredLimit>> {
+    let (world, document) = compile(path, options)?;
+    Ok(counter::declared_limits(
+        &world,
+        &document.introspector,
+        options,
+    ))
+}
+
+/// Compiles a Typst document, returning the world it was compiled in.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be loaded or fails to compile, with
+/// all compiler error messages joined into one.
+fn compile(path: &Path, options: &CountOptions) -> Result<(world::SimpleWorld, PagedDocument)> {
+    let world = world::SimpleWorld::new(path)
+        .with_context(|| format!("Failed to load {}", path.display()))?;
+    typeset(world, &path.display().to_string(), options)
+}
+
+/// Compiles the main document of a world, returning the world it was
+/// finally compiled in.
+///
+/// With [`CountOptions::font_fallback`], font families the world doesn't
+/// have are replaced by a fallback font and the document is compiled again,
+/// with a warning naming the families. With
+/// [`CountOptions::allow_missing_assets`], missing images and data files are
+/// replaced by placeholders, with a warning naming the files. With
+/// [`CountOptions::lenient_imports`], files other than the main document
+/// that fail to compile are skipped, with a warning naming their errors.
+///
+/// # Errors
+///
+/// Returns an error naming the document as `name` if it fails to compile,
+/// with all compiler error messages joined into one.
+fn typeset(
+    world: world::SimpleWorld,
+    name: &str,
+    options: &CountOptions,
+) -> Result<(world::SimpleWorld, PagedDocument)> {
+    let mut world = if options.allow_missing_assets {
+        world.with_asset_placeholders()
+    } else {
+        world
+    };
+    let mut result = typst::compile::<PagedDocument>(&world);
+    let mut missing = missing_font_families(&result.warnings);
+    if options.font_fallback && !missing.is_empty() {
+        eprintln!(
+            "Warning: {name}: fonts not found: {}; counted with {}",
+            missing.join(", "),
+            world::fallback_family().unwrap_or("the default fonts")
+        );
+        world = world.with_font_substitutes(&missing);
+        result = typst::compile::<PagedDocument>(&world);
+        missing.clear();
+    }
+    let mut skipped = Vec::new();
+    while options.lenient_imports
+        && let Err(errors) = &result.output
+        && let Some(failed) = failed_imports(world.main(), errors)
+            .filter(|failed| failed.iter().any(|id| !skipped.contains(id)))
+    {
+        for (id, messages) in &failed {
+            eprintln!(
+                "Warning: {name}: skipped {} with errors: {messages}",
+                display_file(*id)
+            );
+        }
+        skipped.extend(failed.into_iter().map(|(id, _)| id));
+        world = world.with_skipped_sources(&skipped);
+        result = typst::compile::<PagedDocument>(&world);
+    }
+    let document = result
+        .output
+        .map_err(|errors| compile_error(name, &errors, &missing))?;
+    let assets = world.missing_assets();
+    if !assets.is_empty() {
+        eprintln!(
+            "Warning: {name}: files not found: {}; counted with placeholders",
+            assets
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Ok((world, document))
+}
+
+/// Returns the files other than `main` that the errors of a failed
+/// compilation are in, each with its error messages joined into one.
+///
+/// Returns `None` if an error is in the main file or in no file at all, as
+/// skipping imports can't resolve it.
+fn failed_imports(main: FileId, errors: &[SourceDiagnostic]) -> Option<Vec<(FileId, String)>> {
+    let mut failed: Vec<(FileId, String)> = Vec::new();
+    for error in errors {
+        let id = error.span.id().filter(|id| *id != main)?;
+        match failed.iter_mut().find(|(known, _)| *known == id) {
+            Some((_, messages)) => {
+                messages.push_str(", ");
+                messages.push_str(&error.message);
+            }
+            None => failed.push((id, error.message.to_string())),
+        }
+    }
+    Some(failed)
+}
+
+/// Returns the path of a file for messages, prefixed by its package if any.
+fn display_file(id: FileId) -> String {
+    let path = id.vpath().as_rootless_path().display();
+    match id.package() {
+        Some(spec) => format!("{spec}/{path}"),
+        None => path.to_string(),
+    }
+}
+
+/// Returns the font families a compilation warned are unknown, in order.
+fn missing_font_families(warnings: &[SourceDiagnostic]) -> Vec<String> {
+    let mut families: Vec<String> = Vec::new();
+    for warning in warnings {
+        if let Some(family) = warning.message.strip_prefix("unknown font family: ")
+            && !families.iter().any(|known| known == family)
+        {
+            families.push(family.to_string());
+        }
+    }
+    families
+}
+
+/// Joins the errors of a failed compilation into one.
+///
+/// Missing font families are named as a likely cause, with a hint to count
+/// with `--font-fallback`.
+fn compile_error(name: &str, errors: &[SourceDiagnostic], missing: &[String]) -> anyhow::Error {
+    let error_msg = errors
+        .iter()
+        .map(|e| format!("{}", e.message))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if missing.is_empty() {
+        anyhow::anyhow!("Failed to compile {name}: {error_msg}")
+    } else {
+        anyhow::anyhow!(
+            "Failed to compile {name}: {error_msg} (fonts not found: {}; use --font-fallback \
+             to count with a substitute)",
+            missing.join(", ")
+        )
+    }
+}
+
 /// Processes multiple Typst files and returns their counts.
 ///
 /// Compiles each input file specified in the CLI arguments and collects
-/// the word and character counts for each file.
+/// the word and character counts for each file. With `--rev`, the files are
+/// read from that git revision instead of the working tree. Directories are
+/// searched for Typst, Markdown, and plain text files, see
+/// [`discovery::expand_inputs`], and Markdown and plain text files are
+/// counted as text. PDFs given with
+/// `--from-pdf` and plain text files given with `--include-plain` are
+/// counted after the documents, see [`count_pdf`] and [`count_plain`].
 ///
 /// # Arguments
 ///
@@ -130,15 +857,294 @@ pub fn compile_document(path: &Path, exclude_imports: bool) -> Result<Count> {
 /// # Ok::<(), anyhow::Error>(())
 /// ```
 pub fn process_files(args: &Cli) -> Result<Vec<(String, Count)>> {
-    args.input
+    let options = args.count_options();
+    count_files(args, |path| compile_document_with(path, &options))
+}
+
+/// Counts the input files like [`process_files`], counting the Typst
+/// documents in the working tree with `count_document`.
+fn count_files(
+    args: &Cli,
+    mut count_document: impl FnMut(&Path) -> Result<Count>,
+) -> Result<Vec<(String, Count)>> {
+    let options = args.count_options();
+    let input = match &args.rev {
+        Some(rev) => discovery::expand_inputs_at(&args.input, &args.discovery_options(), rev)?,
+        None => discovery::expand_inputs(&args.input, &args.discovery_options())?,
+    };
+    let documents = input.iter().map(|path| match &args.rev {
+        Some(rev) if discovery::is_plain(path) => count_plain_revision(path, rev, &options),
+        Some(rev) => compile_revision(path, rev, &options),
+        None if discovery::is_plain(path) => count_plain(path, &options),
+        None => count_document(path),
+    });
+    let pdfs = args.from_pdf.iter().map(|path| count_pdf(path, &options));
+    let plain = args
+        .include_plain
         .iter()
-        .map(|path| {
-            compile_document(path, args.exclude_imports)
-                .map(|count| (path.display().to_string(), count))
+        .map(|path| count_plain(path, &options));
+    input
+        .iter()
+        .chain(&args.from_pdf)
+        .chain(&args.include_plain)
+        .zip(documents.chain(pdfs).chain(plain))
+        .map(|(path, count)| count.map(|count| (path.display().to_string(), count)))
+        .collect()
+}
+
+/// Counts the text of a PDF exported from a Typst document.
+///
+/// The text is extracted with `pdftotext` and counted with the same word
+/// rules as documents, see [`counter::count_text`].
+///
+/// # Errors
+///
+/// Returns an error if the text cannot be extracted from the PDF.
+pub fn count_pdf(path: &Path, options: &CountOptions) -> Result<Count> {
+    let text = pdf::extract_text(path)?;
+    Ok(counter::count_text(&text, options))
+}
+
+/// Counts the text of a plain text or Markdown file.
+///
+/// Markdown syntax is removed before counting, see [`plain::read_text`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read.
+pub fn count_plain(path: &Path, options: &CountOptions) -> Result<Count> {
+    let text = plain::read_text(path)?;
+    Ok(counter::count_text(&text, options))
+}
+
+/// Counts the text of a plain text or Markdown file as of a git revision.
+///
+/// The file is read with [`revision::read_file`] and counted like
+/// [`count_plain`].
+///
+/// # Errors
+///
+/// Returns an error if the file doesn't exist at the revision or is not
+/// UTF-8 text.
+pub fn count_plain_revision(path: &Path, rev: &str, options: &CountOptions) -> Result<Count> {
+    let contents = String::from_utf8(revision::read_file(path, rev)?)
+        .with_context(|| format!("Failed to read {} at revision {rev}", path.display()))?;
+    Ok(counter::count_text(
+        &plain::file_text(path, contents),
+        options,
+    ))
+}
+
+/// Extracts the counted text of an input file.
+///
+/// PDFs given with `--from-pdf` and plain text or Markdown files are read as
+/// a single text; other files are compiled, see [`extract_document_text`].
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or fails to compile.
+pub fn extract_input_text(args: &Cli, path: &Path, options: &CountOptions) -> Result<Vec<String>> {
+    if args.from_pdf.iter().any(|pdf| pdf == path) {
+        Ok(vec![pdf::extract_text(path)?])
+    } else if discovery::is_plain(path) {
+        Ok(vec![plain::read_text(path)?])
+    } else {
+        extract_document_text(path, options)
+    }
+}
+
+/// Counts the files of each configured group.
+///
+/// Each group's files are compiled and counted like regular inputs, and
+/// their counts are summed into a single result named after the group.
+///
+/// # Arguments
+///
+/// * `args` - Command-line arguments containing the counting options
+/// * `groups` - Groups to count
+/// * `base_dir` - Directory the groups' patterns are relative to
+///
+/// # Errors
+///
+/// Returns an error if a group's patterns match no files, or if any of its
+/// files fails to compile.
+pub fn process_groups(
+    args: &Cli,
+    groups: &[Group],
+    base_dir: &Path,
+) -> Result<Vec<(String, Count)>> {
+    let options = args.count_options();
+    groups
+        .iter()
+        .map(|group| {
+            let mut total = Count::default();
+            for path in group.files(base_dir)? {
+                total += compile_document_with(&path, &options)?;
+            }
+            Ok((group.name.clone(), total))
         })
         .collect()
 }
 
+/// Loads the configuration file of a counting run and applies it to the
+/// arguments.
+///
+/// The configuration's exclude patterns, entry points, and the metrics of
+/// its scores are added to the arguments. Its groups are kept only if no
+/// input files are given, as they stand in for missing input files.
+///
+/// Returns `None` if there are neither input files nor configured groups.
+///
+/// # Errors
+///
+/// Returns an error if the configuration file cannot be read or is invalid.
+pub fn load_run_config(args: &mut Cli) -> Result<Option<Config>> {
+    let config = Config::load(args.config.as_deref())?.unwrap_or_default();
+    let config = if args.has_inputs() {
+        // Scores apply to the given files as well; groups only stand in for
+        // missing input files.
+        Config {
+            groups: Vec::new(),
+            ..config
+        }
+    } else if config.groups.is_empty() {
+        return Ok(None);
+    } else {
+        config
+    };
+    args.exclude.extend(config.exclude.iter().cloned());
+    args.entry_points
+        .extend(config.entry_points.iter().cloned());
+    for metric in config.scores.iter().flat_map(Score::metrics) {
+        if !args.metrics.contains(&metric) {
+            args.metrics.push(metric);
+        }
+    }
+    Ok(Some(config))
+}
+
+/// Counts the input files, or the configured groups if none are given.
+///
+/// # Errors
+///
+/// Returns an error if any file fails to compile or cannot be read.
+pub fn count_inputs(args: &Cli, config: &Config) -> Result<Vec<(String, Count)>> {
+    if config.groups.is_empty() {
+        process_files(args)
+    } else {
+        process_groups(args, &config.groups, &config.base_dir)
+    }
+}
+
+/// Checks the limits declared inside the given documents.
+///
+/// Each violation is located at the part it applies to, with the path
+/// relative to the working directory like the document's own path.
+///
+/// # Errors
+///
+/// Returns an error if any of the files fails to compile.
+pub fn check_document_limits(args: &Cli, files: &[PathBuf]) -> Result<Vec<Violation>> {
+    let options = args.count_options();
+    let mut violations = Vec::new();
+    for path in files {
+        let root = path.parent().unwrap_or(Path::new(""));
+        for limit in document_limits(path, &options)? {
+            let location = limit.location.clone().map(|mut location| {
+                // Files of packages are located relative to the package.
+                if !location.file.starts_with('@') {
+                    location.file = root.join(&location.file).display().to_string();
+                }
+                location
+            });
+            for message in limit.violations() {
+                violations.push(Violation {
+                    message: format!("{}: {message}", path.display()),
+                    locations: location.clone().into_iter().collect(),
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// Checks the density of the keywords given with `--keyword` against their
+/// target ranges.
+///
+/// The density of a keyword is its number of occurrences in all of the
+/// files per 100 words of the total, not counting the words that `filter`
+/// ignores.
+///
+/// # Returns
+///
+/// A message for each keyword whose density is out of range.
+///
+/// # Errors
+///
+/// Returns an error if any of the files cannot be read or fails to compile.
+pub fn check_keywords(
+    args: &Cli,
+    files: &[PathBuf],
+    total: &Count,
+    filter: &WordFilter,
+) -> Result<Vec<String>> {
+    let options = args.count_options();
+    let mut texts = Vec::new();
+    for path in files {
+        texts.extend(extract_input_text(args, path, &options)?);
+    }
+    Ok(args
+        .keyword
+        .iter()
+        .filter_map(|target| target.check(&texts, total.words, filter, &options))
+        .collect())
+}
+
+/// Spell-checks the counted files with the dictionary given with
+/// `--spellcheck`.
+///
+/// Typst documents are checked in their counted text, and plain text and
+/// Markdown files as they are read; PDFs given with `--from-pdf` are checked
+/// without source locations.
+///
+/// # Errors
+///
+/// Returns an error if `hunspell` fails, or any of the files cannot be read
+/// or fails to compile.
+pub fn check_spelling(args: &Cli, dictionary: &str, files: &[PathBuf]) -> Result<Vec<Typo>> {
+    let options = args.count_options();
+    let mut typos = Vec::new();
+    for path in files {
+        let root = path.parent().unwrap_or(Path::new(""));
+        let is_pdf = args.from_pdf.contains(path);
+        let chunks = if is_pdf || discovery::is_plain(path) {
+            let location = (!is_pdf).then(|| counter::SourceLocation {
+                file: path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                line: 1,
+                column: 1,
+            });
+            extract_input_text(args, path, &options)?
+                .into_iter()
+                .map(|text| TextChunk {
+                    text,
+                    element: "text",
+                    heading_level: None,
+                    location: location.clone(),
+                })
+                .collect()
+        } else {
+            extract_document_chunks(path, &options)?
+        };
+        let file = path.display().to_string();
+        typos.extend(spelling::check(dictionary, &file, root, &chunks)?);
+    }
+    Ok(typos)
+}
+
 /// Checks if word and character counts are within specified limits.
 ///
 /// Validates that the total counts meet any minimum or maximum limits
@@ -162,6 +1168,13 @@ pub fn process_files(args: &Cli) -> Result<Vec<(String, Count)>> {
 /// - `min_words` - Minimum required word count
 /// - `max_characters` - Maximum allowed character count
 /// - `min_characters` - Minimum required character count
+/// - `max_abstract_words` - Maximum allowed word count of the abstract
+/// - `max_quote_percent` - Maximum allowed share of quoted words
+/// - `min_references` - Minimum required number of references
+/// - `max_figures` - Maximum allowed number of figures
+/// - `max_figure_density` - Maximum allowed figures per 1000 words
+/// - `max_discouraged_terms` - Maximum allowed occurrences of discouraged
+///   terms
 ///
 /// # Examples
 ///
@@ -170,7 +1183,7 @@ pub fn process_files(args: &Cli) -> Result<Vec<(String, Count)>> {
 /// use clap::Parser;
 ///
 /// let args = Cli::parse();
-/// let total = Count { words: 500, characters: 2500 };
+/// let total = Count { words: 500, characters: 2500, ..Count::default() };
 ///
 /// match check_limits(&args, &total) {
 ///     Ok(()) => println!("All limits satisfied"),
@@ -220,6 +1233,151 @@ pub fn check_limits(args: &Cli, total: &Count) -> Result<(), Vec<String>> {
         ));
     }
 
+    if let Some(max) = args.max_abstract_words
+        && let Some(words) = total.metrics.get(Metric::AbstractWords)
+        && words > max
+    {
+        errors.push(format!(
+            "Abstract word count exceeds maximum ({words} > {max})"
+        ));
+    }
+
+    if let Some(max) = args.max_quote_percent
+        && let Some(quoted) = total.metrics.get(Metric::QuotedWords)
+        && total.words > 0
+    {
+        let percent = quoted as f64 * 100.0 / total.words as f64;
+        if percent > max {
+            errors.push(format!(
+                "Quoted words exceed maximum share ({percent:.1}% > {max}%)"
+            ));
+        }
+    }
+
+    if let Some(max) = args.max_figures
+        && let Some(figures) = total.metrics.get(Metric::Figures)
+        && figures > max
+    {
+        errors.push(format!("Figure count exceeds maximum ({figures} > {max})"));
+    }
+
+    if let Some(max) = args.max_figure_density
+        && let Some(figures) = total.metrics.get(Metric::Figures)
+        && total.words > 0
+    {
+        let density = figures as f64 * 1000.0 / total.words as f64;
+        if density > max {
+            errors.push(format!(
+                "Figures per 1000 words exceed maximum ({density:.1} > {max})"
+            ));
+        }
+    }
+
+    if let Some(min) = args.min_references
+        && let Some(references) = total.metrics.get(Metric::References)
+        && references < min
+    {
+        errors.push(format!(
+            "Reference count below minimum ({references} < {min})"
+        ));
+    }
+
+    if let Some(max) = args.max_discouraged_terms
+        && let Some(terms) = total.metrics.get(Metric::DiscouragedTerms)
+        && terms > max
+    {
+        errors.push(format!(
+            "Discouraged terms exceed maximum ({terms} > {max})"
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Counts the input files at the baseline of the delta limits.
+///
+/// The baseline is read from the report given with `--baseline-report`, or
+/// counted from the revision given with `--baseline`. Directories are
+/// expanded and Markdown and plain text files are read as at the revision,
+/// as with `--rev`, see [`process_files`]. Input files that don't exist at
+/// the revision count as empty.
+///
+/// # Returns
+///
+/// The baseline count, or `None` if no baseline was given.
+///
+/// # Errors
+///
+/// Returns an error if the report cannot be read, or a file cannot be read
+/// from the revision or fails to compile.
+pub fn baseline_count(args: &Cli) -> Result<Option<Count>> {
+    if let Some(path) = &args.baseline_report {
+        return report::read_report(path).map(Some);
+    }
+    let Some(rev) = &args.baseline else {
+        return Ok(None);
+    };
+
+    let options = args.count_options();
+    let input = discovery::expand_inputs_at(&args.input, &args.discovery_options(), rev)?;
+    let mut total = Count::default();
+    for path in input.iter().chain(&args.include_plain) {
+        if !revision::contains(path, rev)? {
+            continue;
+        }
+        total += if discovery::is_plain(path) || args.include_plain.contains(path) {
+            count_plain_revision(path, rev, &options)?
+        } else {
+            compile_revision(path, rev, &options)?
+        };
+    }
+    Ok(Some(total))
+}
+
+/// Checks how much the total changed since the baseline against the delta
+/// limits.
+///
+/// The abstract limit is only checked if both counts include the abstract.
+///
+/// # Errors
+///
+/// Returns a list of error messages describing which limits were violated.
+pub fn check_delta(args: &Cli, total: &Count, baseline: &Count) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    let delta = total.words as i64 - baseline.words as i64;
+
+    if let Some(min) = args.min_delta_words
+        && delta < min
+    {
+        errors.push(format!(
+            "Word count change below minimum ({delta:+} < {min:+})"
+        ));
+    }
+
+    if let Some(max) = args.max_delta_words
+        && delta > max
+    {
+        errors.push(format!(
+            "Word count change exceeds maximum ({delta:+} > {max:+})"
+        ));
+    }
+
+    if let Some(max) = args.max_delta_abstract_words
+        && let Some(words) = total.metrics.get(Metric::AbstractWords)
+        && let Some(before) = baseline.metrics.get(Metric::AbstractWords)
+    {
+        let delta = words as i64 - before as i64;
+        if delta > max {
+            errors.push(format!(
+                "Abstract word count change exceeds maximum ({delta:+} > {max:+})"
+            ));
+        }
+    }
+
     if errors.is_empty() {
         Ok(())
     } else {
@@ -230,20 +1388,89 @@ pub fn check_limits(args: &Cli, total: &Count) -> Result<(), Vec<String>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::{Cli, CountMode, DisplayMode, OutputFormat};
+    use crate::cli::{
+        Cli, ColorChoice, CountMode, DisplayMode, ExclusionArgs, OutputFormat, PathStyle, RateUnit,
+    };
+    use crate::counter::BillingRegion;
+    use crate::preset::Preset;
 
     fn make_test_cli() -> Cli {
         Cli {
+            command: None,
             input: vec![],
+            schema: false,
+            config: None,
+            rev: None,
+            from_pdf: Vec::new(),
+            include_plain: Vec::new(),
+            exclude: Vec::new(),
+            entry_points: Vec::new(),
+            dedupe_shared: false,
             format: OutputFormat::Human,
             mode: CountMode::Both,
             output: None,
+            append: false,
+            json_errors: false,
+            no_daemon: false,
+            watch: false,
+            exec: None,
+            webhook: None,
+            bundle: None,
             display: DisplayMode::Auto,
-            exclude_imports: false,
+            color: ColorChoice::Never,
+            width: None,
+            path_style: PathStyle::Relative,
+            summary: false,
+            percent: false,
+            rate: None,
+            rate_unit: RateUnit::Word,
+            min_row_words: None,
+            top: None,
+            group_by_dir: false,
+            group_by_type: false,
+            with_metadata: false,
+            deterministic: false,
+            with_doc_metadata: false,
+            no_text: false,
+            exclusions: ExclusionArgs::default(),
+            note_marker: vec![],
+            find_duplicates: false,
+            audit: false,
+            explain: false,
+            metrics: vec![],
+            words_per_page: None,
+            billing_region: None,
+            latin_word_units: None,
+            terms: None,
+            font_fallback: false,
+            allow_missing_assets: false,
+            lenient_imports: false,
+            approximate_failed: false,
+            abstract_label: None,
             max_words: None,
             min_words: None,
             max_characters: None,
             min_characters: None,
+            max_abstract_words: None,
+            max_quote_percent: None,
+            min_references: None,
+            max_figures: None,
+            max_figure_density: None,
+            max_discouraged_terms: None,
+            keyword: Vec::new(),
+            word_lists: None,
+            spellcheck: None,
+            max_typos: None,
+            baseline: None,
+            baseline_report: None,
+            min_delta_words: None,
+            max_delta_words: None,
+            max_delta_abstract_words: None,
+            document_limits: false,
+            sarif: None,
+            pin_report: None,
+            verify_report: None,
+            pin_tolerance: 0.0,
         }
     }
 
@@ -253,6 +1480,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         assert!(check_limits(&args, &count).is_ok());
@@ -265,6 +1493,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         assert!(check_limits(&args, &count).is_ok());
@@ -277,6 +1506,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         let result = check_limits(&args, &count);
@@ -294,6 +1524,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         assert!(check_limits(&args, &count).is_ok());
@@ -306,6 +1537,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         let result = check_limits(&args, &count);
@@ -323,6 +1555,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         assert!(check_limits(&args, &count).is_ok());
@@ -335,6 +1568,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         let result = check_limits(&args, &count);
@@ -352,6 +1586,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         assert!(check_limits(&args, &count).is_ok());
@@ -364,6 +1599,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         let result = check_limits(&args, &count);
@@ -384,6 +1620,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         let result = check_limits(&args, &count);
@@ -402,6 +1639,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         // Exactly at the boundary should be OK
@@ -418,6 +1656,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
 
         let result = check_limits(&args, &count);
@@ -426,4 +1665,240 @@ mod tests {
         assert_eq!(errors.len(), 1);
         assert!(errors[0].contains("Character count exceeds maximum"));
     }
+
+    #[test]
+    fn test_apply_preset_fills_limits_and_exclusions() {
+        let mut args = make_test_cli();
+        args.exclusions.preset = Some(Preset::NatureLetter);
+        args.apply_preset();
+
+        assert_eq!(args.max_words, Some(1500));
+        assert!(args.exclusions.exclude_captions);
+        assert!(args.exclusions.exclude_footnotes);
+        assert!(args.exclusions.exclude_bibliography);
+    }
+
+    #[test]
+    fn test_apply_preset_keeps_explicit_limits() {
+        let mut args = make_test_cli();
+        args.exclusions.preset = Some(Preset::UkPhdThesis);
+        args.max_words = Some(80_000);
+        args.apply_preset();
+
+        assert_eq!(args.max_words, Some(80_000));
+        assert!(args.exclusions.exclude_bibliography);
+        assert!(!args.exclusions.exclude_captions);
+    }
+
+    #[test]
+    fn test_exclusions_apply_preset_without_limits() {
+        let exclusions = ExclusionArgs {
+            preset: Some(Preset::NatureLetter),
+            include_chrome: true,
+            ..ExclusionArgs::default()
+        };
+        let options = exclusions.count_options();
+
+        assert!(options.exclude_captions);
+        assert!(options.exclude_footnotes);
+        assert!(options.exclude_bibliography);
+        assert!(options.include_chrome);
+    }
+
+    #[test]
+    fn test_check_limits_abstract_exceeded() {
+        let mut args = make_test_cli();
+        args.max_abstract_words = Some(250);
+        let mut count = Count {
+            words: 5000,
+            characters: 30000,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::AbstractWords, 300);
+
+        let errors = check_limits(&args, &count).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Abstract word count exceeds maximum (300 > 250)"));
+    }
+
+    #[test]
+    fn test_check_limits_abstract_ok() {
+        let mut args = make_test_cli();
+        args.max_abstract_words = Some(250);
+        let mut count = Count {
+            words: 5000,
+            characters: 30000,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::AbstractWords, 200);
+
+        assert!(check_limits(&args, &count).is_ok());
+    }
+
+    #[test]
+    fn test_count_options_enable_abstract_metric() {
+        let mut args = make_test_cli();
+        assert!(args.count_options().metrics.is_empty());
+
+        args.max_abstract_words = Some(250);
+        assert_eq!(args.count_options().metrics, vec![Metric::AbstractWords]);
+    }
+
+    #[test]
+    fn test_count_options_enable_manuscript_pages() {
+        let mut args = make_test_cli();
+        args.words_per_page = Some(300);
+        let options = args.count_options();
+
+        assert_eq!(options.metrics, vec![Metric::ManuscriptPages]);
+        assert_eq!(options.words_per_page, Some(300));
+    }
+
+    #[test]
+    fn test_count_options_enable_billable_units() {
+        let mut args = make_test_cli();
+        args.rate = Some(0.08);
+        assert!(args.count_options().metrics.is_empty());
+
+        args.rate_unit = RateUnit::Unit;
+        let options = args.count_options();
+        assert_eq!(options.metrics, vec![Metric::BillableUnits]);
+        assert_eq!(options.billing_region, BillingRegion::Zh);
+    }
+
+    #[test]
+    fn test_check_limits_quote_percent() {
+        let mut args = make_test_cli();
+        args.max_quote_percent = Some(10.0);
+        let mut count = Count {
+            words: 200,
+            characters: 1000,
+            ..Count::default()
+        };
+
+        count.metrics.set(Metric::QuotedWords, 20);
+        assert!(check_limits(&args, &count).is_ok());
+
+        count.metrics.set(Metric::QuotedWords, 30);
+        let errors = check_limits(&args, &count).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("15.0% > 10%"));
+    }
+
+    #[test]
+    fn test_check_limits_min_references() {
+        let mut args = make_test_cli();
+        args.min_references = Some(10);
+        assert_eq!(args.count_options().metrics, vec![Metric::References]);
+
+        let mut count = Count::default();
+        count.metrics.set(Metric::References, 8);
+        assert_eq!(
+            check_limits(&args, &count).unwrap_err(),
+            ["Reference count below minimum (8 < 10)"]
+        );
+    }
+
+    #[test]
+    fn test_check_limits_figures() {
+        let mut args = make_test_cli();
+        args.max_figures = Some(8);
+        args.max_figure_density = Some(2.0);
+        assert_eq!(args.count_options().metrics, vec![Metric::Figures]);
+
+        let mut count = Count {
+            words: 4000,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::Figures, 8);
+        assert!(check_limits(&args, &count).is_ok());
+
+        count.words = 3000;
+        count.metrics.set(Metric::Figures, 9);
+        assert_eq!(
+            check_limits(&args, &count).unwrap_err(),
+            [
+                "Figure count exceeds maximum (9 > 8)",
+                "Figures per 1000 words exceed maximum (3.0 > 2)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_options_enable_density_base() {
+        let mut args = make_test_cli();
+        args.metrics = vec![Metric::CitationDensity];
+        assert_eq!(
+            args.count_options().metrics,
+            vec![Metric::CitationDensity, Metric::Citations]
+        );
+    }
+
+    #[test]
+    fn test_check_delta() {
+        let mut args = make_test_cli();
+        args.min_delta_words = Some(200);
+        args.max_delta_words = Some(1000);
+        let baseline = Count {
+            words: 5000,
+            ..Count::default()
+        };
+        let count = |words| Count {
+            words,
+            ..Count::default()
+        };
+
+        assert!(check_delta(&args, &count(5500), &baseline).is_ok());
+        assert_eq!(
+            check_delta(&args, &count(5050), &baseline).unwrap_err(),
+            ["Word count change below minimum (+50 < +200)"]
+        );
+        assert_eq!(
+            check_delta(&args, &count(6200), &baseline).unwrap_err(),
+            ["Word count change exceeds maximum (+1200 > +1000)"]
+        );
+    }
+
+    #[test]
+    fn test_check_delta_abstract() {
+        let mut args = make_test_cli();
+        args.max_delta_abstract_words = Some(0);
+        let mut baseline = Count::default();
+        let mut total = Count::default();
+        total.metrics.set(Metric::AbstractWords, 260);
+
+        // Without the abstract in the baseline, there is nothing to compare.
+        assert!(check_delta(&args, &total, &baseline).is_ok());
+
+        baseline.metrics.set(Metric::AbstractWords, 250);
+        assert_eq!(
+            check_delta(&args, &total, &baseline).unwrap_err(),
+            ["Abstract word count change exceeds maximum (+10 > +0)"]
+        );
+    }
+
+    #[test]
+    fn test_failed_imports() {
+        let main = FileId::new(None, VirtualPath::new("main.typ"));
+        let style = FileId::new(None, VirtualPath::new("style.typ"));
+        let span = |id| {
+            typst::syntax::Source::new(id, "#x".to_string())
+                .root()
+                .span()
+        };
+        let errors = [
+            SourceDiagnostic::error(span(style), "unknown variable: x"),
+            SourceDiagnostic::error(span(style), "expected expression"),
+        ];
+        assert_eq!(
+            failed_imports(main, &errors),
+            Some(vec![(
+                style,
+                "unknown variable: x, expected expression".to_string()
+            )])
+        );
+
+        let errors = [SourceDiagnostic::error(span(main), "unknown variable: x")];
+        assert_eq!(failed_imports(main, &errors), None);
+    }
 }
@@ -0,0 +1,381 @@
+//! Checking grammar and style with an external checker.
+//!
+//! `typst-count lint --via languagetool-server URL` sends the counted text
+//! to a LanguageTool server, e.g. one running locally or the public API at
+//! `https://api.languagetool.org`, and reports its findings at the place in
+//! the Typst sources they come from. The checker sees the same prose that
+//! is counted, so markup, code, and excluded content don't cause false
+//! alarms.
+
+use crate::counter::{SourceLocation, TextChunk};
+use crate::http;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Characters of text sent to the checker per request, the limit of the
+/// public LanguageTool API.
+const MAX_REQUEST_CHARS: usize = 20_000;
+
+/// Replacements shown per issue.
+const MAX_REPLACEMENTS: usize = 3;
+
+/// An external checker the text is sent to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checker {
+    /// A server with the LanguageTool HTTP API, at its base URL
+    LanguageToolServer(String),
+}
+
+impl Checker {
+    /// Parses the checker named with `--via`, followed by its URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checker is unknown.
+    pub fn from_via(name: &str, url: &str) -> Result<Self> {
+        match name {
+            "languagetool-server" => Ok(Self::LanguageToolServer(url.to_string())),
+            _ => bail!("Unknown checker `{name}`; supported: languagetool-server"),
+        }
+    }
+}
+
+/// A problem found by the checker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    /// Document the text was counted in
+    pub file: String,
+    /// Where the flagged text is written, if it comes from a source file
+    pub location: Option<SourceLocation>,
+    /// Explanation of the problem
+    pub message: String,
+    /// Identifier of the rule that found it, e.g. `MORFOLOGIK_RULE_EN_US`
+    pub rule: String,
+    /// The flagged text
+    pub text: String,
+    /// Suggested replacements, best first
+    pub replacements: Vec<String>,
+}
+
+/// Checks the counted text of a document.
+///
+/// `root` is the directory the chunks' source locations are relative to.
+/// Each issue is located at the flagged text in the source where it can be
+/// found there, and at the start of its element otherwise, e.g. if the text
+/// is split by markup.
+///
+/// # Errors
+///
+/// Returns an error if the checker cannot be reached, fails, or responds
+/// with something else than its results.
+pub fn check(
+    checker: &Checker,
+    language: &str,
+    file: &str,
+    root: &Path,
+    chunks: &[TextChunk],
+) -> Result<Vec<Issue>> {
+    let Checker::LanguageToolServer(url) = checker;
+    let mut issues = Vec::new();
+    for batch in batches(chunks) {
+        let (text, starts) = join_chunks(batch);
+        let response = post(url, language, &text)?;
+        issues.extend(issues_from_response(&response, batch, &starts, file, root)?);
+    }
+    // Show the files relative to the working directory, like the document.
+    for location in issues
+        .iter_mut()
+        .filter_map(|issue| issue.location.as_mut())
+    {
+        if !location.file.starts_with('@') {
+            location.file = root.join(&location.file).display().to_string();
+        }
+    }
+    Ok(issues)
+}
+
+/// Splits chunks into batches small enough for one request.
+fn batches(chunks: &[TextChunk]) -> Vec<&[TextChunk]> {
+    let mut batches = Vec::new();
+    let (mut start, mut chars) = (0, 0);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let len = chunk.text.chars().count() + 2;
+        if i > start && chars + len > MAX_REQUEST_CHARS {
+            batches.push(&chunks[start..i]);
+            (start, chars) = (i, 0);
+        }
+        chars += len;
+    }
+    if start < chunks.len() {
+        batches.push(&chunks[start..]);
+    }
+    batches
+}
+
+/// Joins chunks into paragraphs of one text.
+///
+/// Also returns where each chunk starts in the text, in UTF-16 code units
+/// as LanguageTool counts offsets.
+fn join_chunks(chunks: &[TextChunk]) -> (String, Vec<usize>) {
+    let mut text = String::new();
+    let mut starts = Vec::new();
+    let mut units = 0;
+    for chunk in chunks {
+        if !text.is_empty() {
+            text.push_str("\n\n");
+            units += 2;
+        }
+        starts.push(units);
+        text.push_str(&chunk.text);
+        units += chunk.text.encode_utf16().count();
+    }
+    (text, starts)
+}
+
+/// Sends text to the `/v2/check` endpoint of a LanguageTool server.
+fn post(url: &str, language: &str, text: &str) -> Result<Value> {
+    let endpoint = if url.trim_end_matches('/').ends_with("/v2/check") {
+        url.to_string()
+    } else {
+        format!("{}/v2/check", url.trim_end_matches('/'))
+    };
+    let response = http::agent()?
+        .post(&endpoint)
+        .send_form(&[("language", language), ("text", text)]);
+    let body = match response {
+        Ok(response) => response
+            .into_string()
+            .with_context(|| format!("Failed to read the response of {endpoint}"))?,
+        Err(e) => bail!(
+            "Failed to check the text with {endpoint}: {}",
+            http::describe(e)
+        ),
+    };
+    serde_json::from_str(&body).with_context(|| format!("Invalid response from {endpoint}"))
+}
+
+/// Reads the issues from a LanguageTool response for a batch of chunks.
+fn issues_from_response(
+    response: &Value,
+    chunks: &[TextChunk],
+    starts: &[usize],
+    file: &str,
+    root: &Path,
+) -> Result<Vec<Issue>> {
+    let matches = response["matches"]
+        .as_array()
+        .context("The response of the checker has no matches")?;
+    let mut issues = Vec::new();
+    for found in matches {
+        let (Some(offset), Some(length)) = (found["offset"].as_u64(), found["length"].as_u64())
+        else {
+            continue;
+        };
+        let (offset, length) = (offset as usize, length as usize);
+        let index = starts.partition_point(|&start| start <= offset).max(1) - 1;
+        let chunk = &chunks[index];
+        let start = utf16_index(&chunk.text, offset - starts[index]);
+        let end = utf16_index(&chunk.text[start..], length) + start;
+        let text = &chunk.text[start..end];
+        let location = chunk.location.as_ref().map(|location| {
+            let nth = word_positions(&chunk.text, text)
+                .iter()
+                .filter(|&&position| position < start)
+                .count();
+            locate_in_source(root, location, text, nth).unwrap_or_else(|| location.clone())
+        });
+        issues.push(Issue {
+            file: file.to_string(),
+            location,
+            message: found["message"].as_str().unwrap_or_default().to_string(),
+            rule: found["rule"]["id"].as_str().unwrap_or_default().to_string(),
+            text: text.to_string(),
+            replacements: found["replacements"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|replacement| replacement["value"].as_str())
+                .take(MAX_REPLACEMENTS)
+                .map(str::to_string)
+                .collect(),
+        });
+    }
+    Ok(issues)
+}
+
+/// Returns the byte index of a text after a number of UTF-16 code units.
+fn utf16_index(text: &str, units: usize) -> usize {
+    let mut counted = 0;
+    for (index, c) in text.char_indices() {
+        if counted >= units {
+            return index;
+        }
+        counted += c.len_utf16();
+    }
+    text.len()
+}
+
+/// Finds the `nth` occurrence of a text in the source after the start of
+/// its element.
+pub(crate) fn locate_in_source(
+    root: &Path,
+    element: &SourceLocation,
+    text: &str,
+    nth: usize,
+) -> Option<SourceLocation> {
+    if text.is_empty() || element.file.starts_with('@') {
+        return None;
+    }
+    let source = fs::read_to_string(root.join(&element.file)).ok()?;
+    let line_start: usize = source
+        .split_inclusive('\n')
+        .take(element.line.checked_sub(1)?)
+        .map(str::len)
+        .sum();
+    let start = line_start
+        + source
+            .get(line_start..)?
+            .chars()
+            .take(element.column.checked_sub(1)?)
+            .map(char::len_utf8)
+            .sum::<usize>();
+    let offset = *word_positions(source.get(start..)?, text).get(nth)?;
+    let before = &source[start..start + offset];
+    Some(match before.rfind('\n') {
+        Some(newline) => SourceLocation {
+            file: element.file.clone(),
+            line: element.line + before.matches('\n').count(),
+            column: before[newline + 1..].chars().count() + 1,
+        },
+        None => SourceLocation {
+            file: element.file.clone(),
+            line: element.line,
+            column: element.column + before.chars().count(),
+        },
+    })
+}
+
+/// Returns the byte positions where a text occurs in another one, where it
+/// doesn't start or end in the middle of a word.
+fn word_positions(haystack: &str, needle: &str) -> Vec<usize> {
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    let bounded_start = is_word(needle.chars().next());
+    let bounded_end = is_word(needle.chars().next_back());
+    let mut positions = Vec::new();
+    if needle.is_empty() {
+        return positions;
+    }
+    let mut from = 0;
+    while let Some(offset) = haystack[from..].find(needle) {
+        let position = from + offset;
+        let end = position + needle.len();
+        let inside_word = (bounded_start && is_word(haystack[..position].chars().next_back()))
+            || (bounded_end && is_word(haystack[end..].chars().next()));
+        if !inside_word {
+            positions.push(position);
+        }
+        from = position
+            + haystack[position..]
+                .chars()
+                .next()
+                .map_or(1, char::len_utf8);
+    }
+    positions
+}
+
+/// Formats issues, one per line, prefixed with their location.
+///
+/// Issues without a source location are prefixed with their document
+/// instead.
+#[must_use]
+pub fn format_issues(issues: &[Issue]) -> String {
+    if issues.is_empty() {
+        return "No issues found\n".to_string();
+    }
+    let mut output = String::new();
+    for issue in issues {
+        let location = match &issue.location {
+            Some(location) => location.to_string(),
+            None => issue.file.clone(),
+        };
+        write!(
+            output,
+            "{location}: {} \"{}\" [{}]",
+            issue.message, issue.text, issue.rule
+        )
+        .unwrap();
+        if !issue.replacements.is_empty() {
+            write!(output, " → {}", issue.replacements.join(", ")).unwrap();
+        }
+        output.push('\n');
+    }
+    let plural = if issues.len() == 1 { "" } else { "s" };
+    writeln!(output, "\n{} issue{plural}", issues.len()).unwrap();
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_issues_from_response() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("main.typ"),
+            "= Intro\n\nThis is is a *test*.\nIt works, it it does.\n",
+        )
+        .unwrap();
+        let chunk = |text: &str, line| TextChunk {
+            text: text.to_string(),
+            element: "par",
+            heading_level: None,
+            location: Some(SourceLocation {
+                file: "main.typ".to_string(),
+                line,
+                column: 1,
+            }),
+        };
+        let chunks = [
+            chunk("Intro", 1),
+            chunk("This is is a test. It works, it it does.", 3),
+        ];
+        let (text, starts) = join_chunks(&chunks);
+        assert_eq!(starts, [0, 7]);
+        assert_eq!(&text[12..17], "is is");
+
+        let issue = |offset, length, rule| {
+            json!({
+                "message": "Possible typo: you repeated a word",
+                "offset": offset,
+                "length": length,
+                "replacements": [{ "value": "is" }],
+                "rule": { "id": rule },
+            })
+        };
+        let response = json!({ "matches": [
+            issue(12, 5, "ENGLISH_WORD_REPEAT_RULE"),
+            issue(36, 5, "ENGLISH_WORD_REPEAT_RULE"),
+        ] });
+        let issues =
+            issues_from_response(&response, &chunks, &starts, "main.typ", dir.path()).unwrap();
+        assert_eq!(issues[0].text, "is is");
+        assert_eq!(issues[1].text, "it it");
+        let locations: Vec<String> = issues
+            .iter()
+            .map(|issue| issue.location.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(locations, ["main.typ:3:6", "main.typ:4:11"]);
+
+        assert_eq!(
+            format_issues(&issues[..1]),
+            "main.typ:3:6: Possible typo: you repeated a word \"is is\" \
+             [ENGLISH_WORD_REPEAT_RULE] → is\n\n1 issue\n"
+        );
+        assert_eq!(format_issues(&[]), "No issues found\n");
+    }
+}
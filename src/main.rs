@@ -3,17 +3,37 @@
 //! This binary provides a CLI tool for counting words and characters in Typst documents.
 //! It handles argument parsing, file processing, output formatting, and limit checking.
 
-use anyhow::{Context, Result};
-use clap::Parser;
-use std::fs::File;
+use anyhow::{Context, Result, bail};
+use clap::{CommandFactory, Parser};
+use std::fs::{self, File, OpenOptions};
 use std::io::{self, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process;
-use typst_count::{check_limits, cli, output, process_files};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use typst_count::config::{Config, Group};
+use typst_count::counter::{AuthorCount, Count};
+#[cfg(unix)]
+use typst_count::daemon;
+use typst_count::metadata::Metadata;
+use typst_count::report::{self, read_report};
+use typst_count::sarif::Violation;
+use typst_count::terms::{self, Terms};
+use typst_count::wordlists::WordFilter;
+use typst_count::{
+    Deduplicated, analysis, audit_document, baseline_count, certificate, check_delta,
+    check_document_limits, check_keywords, check_limits, check_spelling, cli,
+    compile_document_with, count_at_milestones, count_document_authors, count_document_sections,
+    count_history, count_inputs, count_with_dependencies, dedupe_shared, discovery, document_info,
+    exec, extract_document_chunks, extract_document_text, extract_input_text, lint,
+    load_run_config, output, progress, revision, sarif, spelling, stream_document, upload, watch,
+    webhook,
+};
 
 /// Writes formatted output to a file or stdout.
 ///
-/// If an output path is provided, writes to that file. Otherwise, writes to stdout.
+/// If an output path is provided, writes to that file, or uploads the content
+/// if the path is an `s3://` or `https://` URL. Otherwise, writes to stdout.
 ///
 /// # Arguments
 ///
@@ -25,8 +45,13 @@ use typst_count::{check_limits, cli, output, process_files};
 /// Returns an error if:
 /// - The output file cannot be created
 /// - Writing to the file or stdout fails
+/// - The upload fails
 fn write_output(content: &str, output_path: Option<&Path>) -> Result<()> {
-    if let Some(path) = output_path {
+    if let Some(url) = output_path.and_then(Path::to_str)
+        && upload::is_remote(url)
+    {
+        upload::put(url, content)?;
+    } else if let Some(path) = output_path {
         let mut file = File::create(path)
             .with_context(|| format!("Failed to create output file: {}", path.display()))?;
         file.write_all(content.as_bytes())
@@ -38,6 +63,582 @@ fn write_output(content: &str, output_path: Option<&Path>) -> Result<()> {
     Ok(())
 }
 
+/// Appends CSV rows to a progress log file.
+///
+/// The header row of `content` is only written if the file is new or empty,
+/// so repeated runs build up a single table.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or written, or is remote.
+fn append_output(content: &str, path: &Path) -> Result<()> {
+    if path.to_str().is_some_and(upload::is_remote) {
+        bail!("--append needs a local output file");
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open output file: {}", path.display()))?;
+    let is_empty = file.metadata()?.len() == 0;
+    let rows = if is_empty {
+        content
+    } else {
+        content.split_once('\n').map_or("", |(_, rows)| rows)
+    };
+    file.write_all(rows.as_bytes())
+        .with_context(|| format!("Failed to write to output file: {}", path.display()))
+}
+
+/// Runs a subcommand and returns its output and whether the goal it checks,
+/// if any, was met.
+///
+/// # Errors
+///
+/// Returns an error if any document fails to compile or any report cannot
+/// be read.
+fn run_command(command: &cli::Command) -> Result<(String, bool)> {
+    let mut passed = true;
+    let output: Result<String> = match command {
+        cli::Command::Acronyms(args) => {
+            let options = args.count_options();
+            let mut texts = Vec::new();
+            for path in &args.input {
+                texts.extend(extract_document_text(path, &options)?);
+            }
+            Ok(analysis::format_acronyms(&analysis::find_acronyms(&texts)))
+        }
+        cli::Command::Outline(args) => {
+            let options = args.count_options();
+            let mut output = String::new();
+            for (i, path) in args.input.iter().enumerate() {
+                let sections = count_document_sections(path, &options)?;
+                if args.input.len() > 1 {
+                    if i > 0 {
+                        output.push('\n');
+                    }
+                    output.push_str(&format!("{}:\n", path.display()));
+                }
+                output.push_str(&analysis::format_outline(&sections));
+            }
+            Ok(output)
+        }
+        cli::Command::Suggest(args) => {
+            let options = args.document.count_options();
+            let mut sections = Vec::new();
+            for path in &args.document.input {
+                for section in count_document_sections(path, &options)? {
+                    sections.push((path.display().to_string(), section));
+                }
+            }
+            let total = sections.iter().map(|(_, section)| section.words).sum();
+            let cuts = analysis::suggest_cuts(&sections, args.target);
+            Ok(analysis::format_cuts(&cuts, total, args.target))
+        }
+        cli::Command::Authors(args) => {
+            let options = args.count_options();
+            let mut authors: Vec<AuthorCount> = Vec::new();
+            for path in &args.input {
+                for count in count_document_authors(path, &options)? {
+                    match authors.iter_mut().find(|a| a.author == count.author) {
+                        Some(existing) => existing.words += count.words,
+                        None => authors.push(count),
+                    }
+                }
+            }
+            // Keep the unattributed words last when merging several documents.
+            authors.sort_by_key(|a| a.author.is_none());
+            Ok(analysis::format_authors(&authors))
+        }
+        cli::Command::Extract(args) => {
+            let options = args.document.count_options();
+            let mut output = String::new();
+            for path in &args.document.input {
+                let chunks = extract_document_chunks(path, &options)?;
+                output.push_str(&analysis::format_chunks(&chunks, args.locations));
+            }
+            Ok(output)
+        }
+        cli::Command::Kwic(args) => {
+            let options = args.document.count_options();
+            let mut concordance = analysis::Concordance::new(&args.term, args.context);
+            for path in &args.document.input {
+                let file = path.display().to_string();
+                stream_document(path, &options, |chunk| concordance.add(&file, &chunk))?;
+            }
+            Ok(analysis::format_kwic(&concordance.occurrences))
+        }
+        cli::Command::Export(args) => {
+            let options = args.document.count_options();
+            let mut output = String::new();
+            for path in &args.document.input {
+                let chunks = extract_document_chunks(path, &options)?;
+                if !output.is_empty() {
+                    output.push('\n');
+                }
+                output.push_str(&analysis::format_export(&chunks, args.format));
+            }
+            Ok(output)
+        }
+        cli::Command::Lint(args) => {
+            let checker = lint::Checker::from_via(&args.via[0], &args.via[1])?;
+            let options = args.document.count_options();
+            let mut issues = Vec::new();
+            for path in &args.document.input {
+                let chunks = extract_document_chunks(path, &options)?;
+                let root = path.parent().unwrap_or(Path::new(""));
+                let file = path.display().to_string();
+                issues.extend(lint::check(&checker, &args.language, &file, root, &chunks)?);
+            }
+            passed = issues.is_empty();
+            Ok(lint::format_issues(&issues))
+        }
+        cli::Command::Frequency(args) => {
+            let options = args.document.count_options();
+            let mut texts = Vec::new();
+            for path in &args.document.input {
+                texts.extend(extract_document_text(path, &options)?);
+            }
+            let filter = match Config::load(args.config.as_deref())? {
+                Some(config) => config.word_filter(args.word_lists.as_deref())?,
+                None if args.word_lists.is_some() => {
+                    bail!("No {} found", typst_count::config::CONFIG_FILE)
+                }
+                None => WordFilter::default(),
+            };
+            let phrases =
+                analysis::count_phrases(&texts, args.ngrams.clone(), args.min_occurrences, &filter);
+            Ok(analysis::format_phrases(&phrases, args.top))
+        }
+        cli::Command::Trend(args) => {
+            let options = args.document.count_options();
+            let points = count_history(&args.document.input, args.since.as_deref(), &options)?;
+            if args.sparkline {
+                Ok(analysis::format_sparkline(&points))
+            } else {
+                Ok(analysis::format_trend(&points))
+            }
+        }
+        cli::Command::Milestones(args) => {
+            let options = args.document.count_options();
+            let config = Config::load(args.config.as_deref())?
+                .with_context(|| format!("No {} found", typst_count::config::CONFIG_FILE))?;
+            let today = args
+                .today
+                .unwrap_or_else(|| chrono::Local::now().date_naive());
+            let mut words = 0;
+            for path in &args.document.input {
+                words += compile_document_with(path, &options)?.words;
+            }
+            // Outside a git repository, past milestones are judged by the
+            // current count alone.
+            let at_deadline =
+                count_at_milestones(&args.document.input, &config.milestones, today, &options)
+                    .unwrap_or_else(|_| vec![None; config.milestones.len()]);
+            let milestones: Vec<_> = config
+                .milestones
+                .into_iter()
+                .zip(at_deadline)
+                .map(|(milestone, count)| (milestone, count.map(|count| count.words)))
+                .collect();
+            Ok(analysis::format_milestones(&milestones, words, today))
+        }
+        cli::Command::Daily(args) => {
+            let options = args.document.count_options();
+            let today = chrono::Local::now().date_naive();
+            let entries = progress::read_log(&args.log)?;
+            let baseline = progress::last_before(&entries, today)
+                .with_context(|| format!("No run before today in {}", args.log.display()))?;
+            let mut words = 0;
+            for path in &args.document.input {
+                words += compile_document_with(path, &options)?.words;
+            }
+            passed = words >= baseline.words + args.goal;
+            Ok(analysis::format_daily(baseline, words, args.goal))
+        }
+        cli::Command::Sprint(args) => {
+            let options = args.document.count_options();
+            let count = || -> Result<(usize, Vec<PathBuf>)> {
+                let mut words = 0;
+                let mut dependencies = Vec::new();
+                for path in &args.document.input {
+                    let (count, paths) = count_with_dependencies(path, &options)?;
+                    words += count.words;
+                    dependencies.extend(paths);
+                }
+                Ok((words, dependencies))
+            };
+            let (start, mut dependencies) = count()?;
+            let length = Duration::from_secs(args.minutes * 60);
+            let interval = Duration::from_secs(args.interval.max(1));
+            let began = Instant::now();
+            eprintln!(
+                "Sprint started at {start} words, {} minutes to go",
+                args.minutes
+            );
+
+            let mut words = start;
+            let mut modified = modification_times(&dependencies);
+            while let Some(left) = length.checked_sub(began.elapsed()) {
+                thread::sleep(interval.min(left));
+                let latest = modification_times(&dependencies);
+                if latest == modified {
+                    continue;
+                }
+                modified = latest;
+                // The documents may not compile while they are being edited,
+                // so keep the last count and the files it was compiled from
+                // until they do again.
+                match count() {
+                    Ok((now, paths)) => {
+                        words = now;
+                        dependencies = paths;
+                        modified = modification_times(&dependencies);
+                        eprintln!(
+                            "{:>3} min: {words} words ({:+})",
+                            began.elapsed().as_secs() / 60,
+                            words as i64 - start as i64
+                        );
+                    }
+                    Err(e) => eprintln!("Warning: {e}"),
+                }
+            }
+            Ok(analysis::format_sprint(start, words, args.minutes))
+        }
+        cli::Command::Daemon(args) => {
+            #[cfg(unix)]
+            {
+                let socket = args.socket.clone().unwrap_or_else(daemon::socket_path);
+                daemon::serve(&socket)?;
+                Ok(String::new())
+            }
+            #[cfg(not(unix))]
+            {
+                let _ = args;
+                anyhow::bail!("The daemon is only available on Unix")
+            }
+        }
+        cli::Command::Certify(args) => {
+            let options = args.document.count_options();
+            let statement = certificate::statement(&args.document.input, &options)?;
+            certificate::sign(statement, args.secret_key.as_deref())
+        }
+        cli::Command::VerifyCert(args) => {
+            let text = fs::read_to_string(&args.certificate).with_context(|| {
+                format!("Failed to read certificate: {}", args.certificate.display())
+            })?;
+            let verification =
+                certificate::verify(&text, args.public_key.as_deref(), !args.hashes_only)?;
+            passed = verification.holds();
+            Ok(certificate::format_verification(&verification))
+        }
+        cli::Command::Report(args) => match &args.command {
+            None => {
+                let summary = &args.summary;
+                let path = summary.report.as_deref().context("No report given")?;
+                let after = read_report(path)?;
+                let before = summary.baseline.as_deref().map(read_report).transpose()?;
+                match summary.format {
+                    Some(cli::ReportFormat::PrComment) | None => Ok(report::format_pr_comment(
+                        before.as_ref(),
+                        &after,
+                        &summary.limits(),
+                    )),
+                }
+            }
+            Some(cli::ReportCommand::Merge(args)) => {
+                let results = args
+                    .reports
+                    .iter()
+                    .map(|path| read_report(path).map(|count| (path.display().to_string(), count)))
+                    .collect::<Result<Vec<_>>>()?;
+                let formatter = output::OutputFormatter::new(args.format, args.mode);
+                Ok(formatter.format_output(&results, args.display))
+            }
+        },
+    };
+    Ok((output?, passed))
+}
+
+/// Returns the modification times of files, or `None` for files that can't
+/// be read, e.g. because they were deleted.
+fn modification_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
+
+/// Finds sentences repeated across the input files and formats them.
+///
+/// # Errors
+///
+/// Returns an error if any document fails to compile.
+fn report_duplicates(args: &cli::Cli) -> Result<String> {
+    let options = args.count_options();
+    let documents = args
+        .input
+        .iter()
+        .map(|path| {
+            extract_document_text(path, &options).map(|texts| (path.display().to_string(), texts))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(analysis::format_duplicates(
+        &analysis::find_duplicate_sentences(&documents),
+    ))
+}
+
+/// Counts the discouraged term variants in each counted file and formats
+/// them.
+///
+/// # Errors
+///
+/// Returns an error if any file cannot be read or fails to compile.
+fn report_terms(args: &cli::Cli, terms: &Terms, files: &[PathBuf]) -> Result<String> {
+    let options = args.count_options();
+    let usage = files
+        .iter()
+        .map(|path| {
+            let texts = extract_input_text(args, path, &options)?;
+            Ok((path.display().to_string(), terms.usage(&texts)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(terms::format_usage(&usage))
+}
+
+/// Audits the counting of each input file and formats the findings.
+///
+/// # Errors
+///
+/// Returns an error if any document fails to compile.
+fn report_audit(args: &cli::Cli) -> Result<String> {
+    let options = args.count_options();
+    let audits = args
+        .input
+        .iter()
+        .map(|path| audit_document(path, &options).map(|audit| (path.display().to_string(), audit)))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(analysis::format_audit(&audits))
+}
+
+/// Writes the count of every file as a JSON report with the version of the
+/// Typst compiler, for `--verify-report`.
+///
+/// # Errors
+///
+/// Returns an error if the report cannot be written.
+fn pin_report(results: &[(String, Count)], path: &Path) -> Result<()> {
+    let mut metadata = Metadata::collect(Path::new("."));
+    metadata.timestamp = None;
+    let options = output::FormatOptions {
+        metadata: Some(metadata),
+        ..output::FormatOptions::default()
+    };
+    let formatter = output::OutputFormatter::new(cli::OutputFormat::Json, cli::CountMode::Both)
+        .with_options(options);
+    write_output(
+        &formatter.format_output(results, cli::DisplayMode::Detailed),
+        Some(path),
+    )
+}
+
+/// Writes the artifacts of a run into the `--bundle` directory.
+///
+/// The JSON report carries the run metadata and summary, the text of each
+/// file is written to `text/` under the file's path with `.txt` appended
+/// (unless `--no-text` is given), and `diagnostics.log` lists the
+/// violations, one per line.
+///
+/// # Errors
+///
+/// Returns an error if a file's text cannot be extracted or the bundle
+/// cannot be written.
+fn write_bundle(
+    args: &cli::Cli,
+    dir: &Path,
+    results: &[(String, Count)],
+    files: &[PathBuf],
+    violations: &[Violation],
+) -> Result<()> {
+    write_diagnostics(dir, violations.iter().map(|violation| &violation.message))?;
+    let options = output::FormatOptions {
+        metadata: Some(Metadata::collect(Path::new("."))),
+        summary: Some(output::Summary {
+            files: files.len(),
+            words: output::calculate_total(results).words,
+            violations: violations.len(),
+            errors: results
+                .iter()
+                .filter(|(_, count)| count.approximate)
+                .count(),
+        }),
+        ..output::FormatOptions::default()
+    };
+    let formatter =
+        output::OutputFormatter::new(cli::OutputFormat::Json, args.mode).with_options(options);
+    write_output(
+        &formatter.format_output(results, cli::DisplayMode::Detailed),
+        Some(&dir.join("report.json")),
+    )?;
+    if args.no_text {
+        return Ok(());
+    }
+
+    let count_options = args.count_options();
+    for path in files {
+        let text = extract_input_text(args, path, &count_options)?.join("\n");
+        // Keep the file's path below `text/`, without its root or `..`.
+        let relative: PathBuf = path
+            .components()
+            .filter(|component| matches!(component, std::path::Component::Normal(_)))
+            .collect();
+        let mut target = dir.join("text").join(relative).into_os_string();
+        target.push(".txt");
+        let target = PathBuf::from(target);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        write_output(&text, Some(&target))?;
+    }
+    Ok(())
+}
+
+/// Writes the diagnostics of a run to `diagnostics.log` in a bundle
+/// directory, one per line.
+///
+/// # Errors
+///
+/// Returns an error if the directory or file cannot be written.
+fn write_diagnostics<T: std::fmt::Display>(
+    dir: &Path,
+    diagnostics: impl IntoIterator<Item = T>,
+) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let log: String = diagnostics
+        .into_iter()
+        .map(|diagnostic| format!("Error: {diagnostic}\n"))
+        .collect();
+    write_output(&log, Some(&dir.join("diagnostics.log")))
+}
+
+/// Lists the rows and sections contributing the most words, to explain a
+/// violated limit.
+///
+/// # Errors
+///
+/// Returns an error if any document fails to compile.
+fn report_contributors(
+    args: &cli::Cli,
+    results: &[(String, Count)],
+    files: &[PathBuf],
+) -> Result<String> {
+    let options = args.count_options();
+    let mut sections = Vec::new();
+    for path in files {
+        for section in count_document_sections(path, &options)? {
+            sections.push((path.display().to_string(), section));
+        }
+    }
+    Ok(analysis::format_contributors(results, &sections))
+}
+
+/// Returns the output file requested for a subcommand, if any.
+fn command_output(command: &cli::Command) -> Option<&Path> {
+    match command {
+        cli::Command::Acronyms(args)
+        | cli::Command::Outline(args)
+        | cli::Command::Authors(args) => args.output.as_deref(),
+        cli::Command::Suggest(args) => args.document.output.as_deref(),
+        cli::Command::Extract(args) => args.document.output.as_deref(),
+        cli::Command::Kwic(args) => args.document.output.as_deref(),
+        cli::Command::Frequency(args) => args.document.output.as_deref(),
+        cli::Command::Export(args) => args.document.output.as_deref(),
+        cli::Command::Lint(args) => args.document.output.as_deref(),
+        cli::Command::Trend(args) => args.document.output.as_deref(),
+        cli::Command::Milestones(args) => args.document.output.as_deref(),
+        cli::Command::Daily(args) => args.document.output.as_deref(),
+        cli::Command::Sprint(args) => args.document.output.as_deref(),
+        cli::Command::Daemon(_) => None,
+        cli::Command::Certify(args) => args.document.output.as_deref(),
+        cli::Command::VerifyCert(args) => args.output.as_deref(),
+        cli::Command::Report(args) => match &args.command {
+            Some(cli::ReportCommand::Merge(args)) => args.output.as_deref(),
+            None => args.summary.output.as_deref(),
+        },
+    }
+}
+
+/// Checks each group's count against the group's own limits.
+///
+/// `results` holds one entry per group, named after the group. Violations
+/// are located at the files of the group.
+fn group_violations(
+    groups: &[Group],
+    base_dir: &Path,
+    results: &[(String, Count)],
+) -> Vec<Violation> {
+    groups
+        .iter()
+        .filter_map(|group| {
+            let (_, count) = results.iter().find(|(name, _)| *name == group.name)?;
+            let files = group.files(base_dir).unwrap_or_default();
+            Some(
+                group
+                    .check_limits(count)
+                    .into_iter()
+                    .map(move |message| Violation::in_files(message, &files)),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+/// Returns the files that limits on the total count apply to.
+///
+/// These are the input files, or the files of all groups if none were given.
+fn counted_files(args: &cli::Cli, config: &Config) -> Vec<PathBuf> {
+    if args.has_inputs() {
+        let input = discovery::expand_inputs(&args.input, &args.discovery_options())
+            .unwrap_or_else(|_| args.input.clone());
+        return [input.as_slice(), &args.from_pdf, &args.include_plain].concat();
+    }
+    config
+        .groups
+        .iter()
+        .flat_map(|group| group.files(&config.base_dir).unwrap_or_default())
+        .collect()
+}
+
+/// Reports an error and exits with status 2.
+///
+/// The error goes to stderr; with `--json-errors` it is also written to the
+/// output as a JSON error object, so the output is valid JSON either way.
+/// With `--bundle`, it is also written to the bundle's diagnostics log.
+fn fail(args: &cli::Cli, error: &anyhow::Error) -> ! {
+    eprintln!("Error: {error:?}");
+    if let Some(dir) = &args.bundle
+        && let Err(e) = write_diagnostics(dir, [format!("{error:?}")])
+    {
+        eprintln!("Error: {e:?}");
+    }
+    if args.json_errors {
+        let path = args
+            .command
+            .as_ref()
+            .map_or(args.output.as_deref(), command_output);
+        if let Err(e) = write_output(&output::format_error(error), path) {
+            eprintln!("Error: {e:?}");
+        }
+    }
+    process::exit(2);
+}
+
 /// Main entry point for the typst-count CLI tool.
 ///
 /// This function orchestrates the entire counting process:
@@ -53,28 +654,296 @@ fn write_output(content: &str, output_path: Option<&Path>) -> Result<()> {
 /// - `1`: Limit violation - counts exceed or fall below specified limits
 /// - `2`: Error - compilation failure or other error
 fn main() {
-    let args = cli::Cli::parse();
+    let mut args = cli::Cli::parse();
+    args.apply_preset();
 
-    let results = match process_files(&args) {
-        Ok(results) => results,
-        Err(e) => {
-            eprintln!("Error: {e:?}");
-            process::exit(2);
+    if args.schema {
+        print!("{}", output::SCHEMA);
+        process::exit(0);
+    }
+
+    if args.append && args.format != cli::OutputFormat::Csv {
+        cli::Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--append requires --format csv",
+            )
+            .exit();
+    }
+    if args.json_errors && args.format != cli::OutputFormat::Json {
+        cli::Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--json-errors requires --format json",
+            )
+            .exit();
+    }
+
+    if let Some(command) = &args.command {
+        let result = run_command(command).and_then(|(output, passed)| {
+            write_output(&output, command_output(command))?;
+            Ok(passed)
+        });
+        match result {
+            Ok(true) => process::exit(0),
+            Ok(false) => process::exit(1),
+            Err(e) => fail(&args, &e),
         }
+    }
+
+    let config = match load_run_config(&mut args) {
+        Ok(Some(config)) => config,
+        Ok(None) => cli::Cli::command()
+            .error(
+                clap::error::ErrorKind::MissingRequiredArgument,
+                "no input files given and no groups configured",
+            )
+            .exit(),
+        Err(e) => fail(&args, &e),
     };
 
-    let formatter = output::OutputFormatter::new(args.format, args.mode);
-    let output_text = formatter.format_output(&results, args.display);
+    if args.watch {
+        if !args.has_inputs() {
+            cli::Cli::command()
+                .error(
+                    clap::error::ErrorKind::MissingRequiredArgument,
+                    "--watch requires input files",
+                )
+                .exit();
+        }
+        if let Err(e) = watch::run(&args) {
+            fail(&args, &e);
+        }
+        process::exit(0);
+    }
 
-    if let Err(e) = write_output(&output_text, args.output.as_deref()) {
-        eprintln!("Error: {e:?}");
-        process::exit(2);
+    #[cfg(unix)]
+    let delegated = if args.no_daemon || !daemon::serves(&args) {
+        None
+    } else {
+        daemon::delegate(&std::env::args_os().collect::<Vec<_>>())
+    };
+    #[cfg(not(unix))]
+    let delegated = None;
+    let results = if args.dedupe_shared && config.groups.is_empty() {
+        dedupe_shared(&args)
+    } else {
+        delegated
+            .unwrap_or_else(|| count_inputs(&args, &config))
+            .map(|results| Deduplicated {
+                results,
+                ..Deduplicated::default()
+            })
+    };
+    let Deduplicated {
+        mut results,
+        shared_words,
+    } = match results {
+        Ok(results) => results,
+        Err(e) => fail(&args, &e),
+    };
+    if args.deterministic {
+        results.sort_by(|(a, _), (b, _)| a.cmp(b));
     }
 
     let total = output::calculate_total(&results);
-    if let Err(errors) = check_limits(&args, &total) {
-        for error in errors {
-            eprintln!("Error: {error}");
+    let files = counted_files(&args, &config);
+    let mut errors = check_limits(&args, &total).err().unwrap_or_default();
+    errors.extend(
+        config
+            .scores
+            .iter()
+            .flat_map(|score| score.check_limits(&total)),
+    );
+    match baseline_count(&args) {
+        Ok(Some(baseline)) => {
+            errors.extend(
+                check_delta(&args, &total, &baseline)
+                    .err()
+                    .unwrap_or_default(),
+            );
+        }
+        Ok(None) => {}
+        Err(e) => fail(&args, &e),
+    }
+    if let Some(path) = &args.verify_report {
+        match report::read_pinned(path) {
+            Ok(pinned) => {
+                errors.extend(report::count_shifts(&pinned, &results, args.pin_tolerance))
+            }
+            Err(e) => fail(&args, &e),
+        }
+    }
+    if !args.keyword.is_empty() {
+        let keywords = config
+            .word_filter(args.word_lists.as_deref())
+            .and_then(|filter| check_keywords(&args, &files, &total, &filter));
+        match keywords {
+            Ok(keywords) => errors.extend(keywords),
+            Err(e) => fail(&args, &e),
+        }
+    }
+    let typos = match &args.spellcheck {
+        Some(dictionary) => match check_spelling(&args, dictionary, &files) {
+            Ok(typos) => typos,
+            Err(e) => fail(&args, &e),
+        },
+        None => Vec::new(),
+    };
+    let mut violations: Vec<Violation> = errors
+        .into_iter()
+        .map(|message| Violation::in_files(message, &files))
+        .collect();
+    if let Some(max) = args.max_typos
+        && typos.len() > max
+    {
+        let message = format!("Typo count exceeds maximum ({} > {max})", typos.len());
+        let mut violation = Violation::in_files(message, &files);
+        let located: Vec<_> = typos
+            .iter()
+            .filter_map(|typo| typo.location.clone())
+            .collect();
+        if !located.is_empty() {
+            violation.locations = located;
+        }
+        violations.push(violation);
+    }
+    violations.extend(group_violations(&config.groups, &config.base_dir, &results));
+    if args.document_limits {
+        match check_document_limits(&args, &files) {
+            Ok(declared) => violations.extend(declared),
+            Err(e) => fail(&args, &e),
+        }
+    }
+
+    let mut options = args.format_options();
+    options.shared_words = shared_words;
+    for group in &config.groups {
+        options
+            .row_limits
+            .insert(group.name.clone(), group.limits());
+    }
+    if args.with_metadata {
+        let mut metadata = Metadata::collect(Path::new("."));
+        if args.deterministic {
+            metadata.timestamp = None;
+        }
+        if let Some(rev) = &args.rev {
+            metadata.commit = revision::resolve(Path::new("."), rev).ok();
+            metadata.branch = None;
+        }
+        options.metadata = Some(metadata);
+    }
+    if args.with_doc_metadata {
+        let count_options = args.count_options();
+        for path in &args.input {
+            match document_info(path, &count_options) {
+                Ok(info) => {
+                    options.documents.insert(path.display().to_string(), info);
+                }
+                Err(e) => fail(&args, &e),
+            }
+        }
+    }
+    if args.summary {
+        options.summary = Some(output::Summary {
+            files: files.len(),
+            words: total.words,
+            violations: violations.len(),
+            errors: results
+                .iter()
+                .filter(|(_, count)| count.approximate)
+                .count(),
+        });
+    }
+    if args.append {
+        options.timestamp = Some(chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+    }
+    let output_text = if args.append && config.private_progress {
+        let secret = match progress::secret(config.progress_secret.as_deref()) {
+            Ok(secret) => secret,
+            Err(e) => fail(&args, &e),
+        };
+        let options = output::FormatOptions {
+            timestamp: options.timestamp,
+            ..output::FormatOptions::default()
+        };
+        output::OutputFormatter::new(args.format, args.mode)
+            .with_options(options)
+            .format_output(&progress::private_rows(&results, &secret), args.display)
+    } else {
+        output::OutputFormatter::new(args.format, args.mode)
+            .with_options(options)
+            .format_output(&results, args.display)
+    };
+
+    let written = match args.output.as_deref() {
+        Some(path) if args.append => append_output(&output_text, path),
+        path => write_output(&output_text, path),
+    };
+    if let Err(e) = written {
+        fail(&args, &e)
+    }
+
+    if let Some(command) = &args.exec
+        && let Err(e) = exec::run(command, &args, &results)
+    {
+        fail(&args, &e)
+    }
+
+    if let Some(url) = &args.webhook
+        && let Err(e) = webhook::post(url, &args.json_report(&results))
+    {
+        fail(&args, &e)
+    }
+
+    if let Some(path) = &args.pin_report
+        && let Err(e) = pin_report(&results, path)
+    {
+        fail(&args, &e)
+    }
+
+    if args.audit {
+        match report_audit(&args) {
+            Ok(report) => eprint!("{report}"),
+            Err(e) => fail(&args, &e),
+        }
+    }
+
+    if args.find_duplicates {
+        match report_duplicates(&args) {
+            Ok(report) => eprint!("{report}"),
+            Err(e) => fail(&args, &e),
+        }
+    }
+
+    if let Some(terms) = &args.terms {
+        match report_terms(&args, terms, &files) {
+            Ok(report) => eprint!("{report}"),
+            Err(e) => fail(&args, &e),
+        }
+    }
+    eprint!("{}", spelling::format_typos(&typos));
+
+    if let Some(path) = &args.sarif
+        && let Err(e) = write_output(&sarif::format(&violations), Some(path))
+    {
+        fail(&args, &e)
+    }
+    if let Some(dir) = &args.bundle
+        && let Err(e) = write_bundle(&args, dir, &results, &files, &violations)
+    {
+        fail(&args, &e)
+    }
+    if !violations.is_empty() {
+        for violation in violations {
+            eprintln!("Error: {}", violation.message);
+        }
+        if args.explain {
+            match report_contributors(&args, &results, &files) {
+                Ok(report) => eprint!("\n{report}"),
+                Err(e) => fail(&args, &e),
+            }
         }
         process::exit(1);
     }
@@ -0,0 +1,139 @@
+//! Approximate counts from the source text of documents.
+//!
+//! A document that fails to compile has no layout to count. Rather than
+//! leaving it out of a report, its text can be estimated from the markup:
+//! text, headings, lists, and the content blocks passed to functions are
+//! kept, while code, equations, and comments are dropped. Included files
+//! are read in place. The estimate misses generated text such as
+//! numbering, and counts text a show rule would have hidden.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use typst::syntax::{SyntaxKind, SyntaxNode};
+
+/// Reads the text of a Typst document from its markup, following includes.
+///
+/// Includes with an absolute path are resolved relative to the document's
+/// directory, as the project root; included files that cannot be read are
+/// skipped.
+///
+/// # Errors
+///
+/// Returns an error if the document itself cannot be read.
+pub fn source_text(path: &Path) -> Result<String> {
+    let root = path.parent().unwrap_or(Path::new(""));
+    let mut text = String::new();
+    let mut visited = Vec::new();
+    read_file(root, path, &mut visited, &mut text)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    Ok(text)
+}
+
+/// Appends the markup text of a file, unless it was read before.
+fn read_file(
+    root: &Path,
+    path: &Path,
+    visited: &mut Vec<PathBuf>,
+    text: &mut String,
+) -> std::io::Result<()> {
+    let canonical = path.canonicalize()?;
+    if visited.contains(&canonical) {
+        return Ok(());
+    }
+    visited.push(canonical);
+    let source = fs::read_to_string(path)?;
+    let dir = path.parent().unwrap_or(root);
+    collect_text(&typst::syntax::parse(&source), root, dir, visited, text);
+    Ok(())
+}
+
+/// Appends the text of the markup in a syntax tree.
+///
+/// `dir` is the directory of the file the tree was parsed from, which
+/// relative includes are resolved against.
+fn collect_text(
+    node: &SyntaxNode,
+    root: &Path,
+    dir: &Path,
+    visited: &mut Vec<PathBuf>,
+    text: &mut String,
+) {
+    match node.kind() {
+        SyntaxKind::Text | SyntaxKind::SmartQuote | SyntaxKind::Link => {
+            text.push_str(node.text());
+        }
+        SyntaxKind::Space | SyntaxKind::Linebreak => text.push(' '),
+        SyntaxKind::Parbreak => text.push_str("\n\n"),
+        SyntaxKind::Escape => text.push_str(node.text().trim_start_matches('\\')),
+        SyntaxKind::Shorthand if node.text() == "~" => text.push(' '),
+        SyntaxKind::ModuleInclude => {
+            if let Some(target) = node
+                .children()
+                .find(|child| child.kind() == SyntaxKind::Str)
+            {
+                let target = target.text().trim_matches('"');
+                let target = match target.strip_prefix('/') {
+                    Some(absolute) => root.join(absolute),
+                    None => dir.join(target),
+                };
+                text.push_str("\n\n");
+                // Unreadable includes are as good as empty for an estimate.
+                let _ = read_file(root, &target, visited, text);
+                text.push_str("\n\n");
+            }
+        }
+        SyntaxKind::Equation
+        | SyntaxKind::ModuleImport
+        | SyntaxKind::LetBinding
+        | SyntaxKind::SetRule
+        | SyntaxKind::ShowRule => {}
+        _ => {
+            for child in node.children() {
+                collect_text(child, root, dir, visited, text);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_text() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("chapters")).unwrap();
+        fs::write(
+            dir.path().join("main.typ"),
+            "#import \"style.typ\": conf\n#set text(size: 11pt)\n\
+             = Intro\nSome *bold* text~here, $x + y$ // note\n\
+             #emph[emphasized words] #undefined\n\
+             #include \"chapters/one.typ\"\n#include \"missing.typ\"",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("chapters/one.typ"),
+            "- First item\n#include \"/main.typ\"",
+        )
+        .unwrap();
+
+        let text = source_text(&dir.path().join("main.typ")).unwrap();
+        let words: Vec<&str> = text.split_whitespace().collect();
+        assert_eq!(
+            words,
+            [
+                "Intro",
+                "Some",
+                "bold",
+                "text",
+                "here,",
+                "emphasized",
+                "words",
+                "First",
+                "item"
+            ]
+        );
+        assert!(source_text(&dir.path().join("absent.typ")).is_err());
+    }
+}
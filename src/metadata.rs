@@ -0,0 +1,134 @@
+//! Run metadata for self-describing machine output.
+//!
+//! Reports archived by CI are often looked at long after the run that
+//! produced them. This module collects when and from which revision a count
+//! was made, and which versions of typst-count and the Typst compiler made it.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Information about the run that produced a set of counts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Metadata {
+    /// Time of the run as an ISO 8601 timestamp in UTC, omitted from
+    /// deterministic output
+    pub timestamp: Option<String>,
+    /// Commit SHA of the checked out revision, if in a git repository
+    pub commit: Option<String>,
+    /// Name of the checked out branch, if in a git repository and not detached
+    pub branch: Option<String>,
+    /// Version of typst-count
+    pub version: String,
+    /// Version of the Typst compiler used to compile the documents
+    pub typst_version: String,
+}
+
+impl Metadata {
+    /// Collects metadata for a run in `dir`.
+    ///
+    /// Git information is looked up with the `git` command and left empty if
+    /// it is unavailable or `dir` is not inside a repository.
+    #[must_use]
+    pub fn collect(dir: &Path) -> Self {
+        let branch = git(dir, &["rev-parse", "--abbrev-ref", "HEAD"]).filter(|b| b != "HEAD");
+        Self {
+            timestamp: Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+            commit: git(dir, &["rev-parse", "HEAD"]),
+            branch,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            typst_version: crate::compiler_version(),
+        }
+    }
+
+    /// Returns the metadata as named fields, in output order.
+    ///
+    /// Unknown values are `None`. The timestamp is left out entirely when it
+    /// was removed for deterministic output.
+    #[must_use]
+    pub fn fields(&self) -> Vec<(&'static str, Option<&str>)> {
+        let mut fields = Vec::with_capacity(5);
+        if let Some(timestamp) = &self.timestamp {
+            fields.push(("timestamp", Some(timestamp.as_str())));
+        }
+        fields.extend([
+            ("commit", self.commit.as_deref()),
+            ("branch", self.branch.as_deref()),
+            ("version", Some(self.version.as_str())),
+            ("typst_version", Some(self.typst_version.as_str())),
+        ]);
+        fields
+    }
+}
+
+/// Title and authors a document declares with `#set document(..)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentInfo {
+    /// Title of the document, if set
+    pub title: Option<String>,
+    /// Authors of the document, in the order they are given
+    pub authors: Vec<String>,
+}
+
+impl DocumentInfo {
+    /// Returns the authors as a single `; `-separated value, or `None` if
+    /// there are none.
+    #[must_use]
+    pub fn joined_authors(&self) -> Option<String> {
+        (!self.authors.is_empty()).then(|| self.authors.join("; "))
+    }
+}
+
+/// Runs a git command in `dir` and returns its trimmed output on success.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    let text = String::from_utf8(output.stdout).ok()?;
+    let text = text.trim();
+    (output.status.success() && !text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_outside_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata = Metadata::collect(dir.path());
+
+        assert_eq!(metadata.commit, None);
+        assert_eq!(metadata.branch, None);
+        assert_eq!(metadata.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(metadata.typst_version, crate::compiler_version());
+        assert_eq!(metadata.typst_version.split('.').count(), 3);
+        assert!(metadata.timestamp.unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn test_fields_without_timestamp() {
+        let metadata = Metadata {
+            timestamp: None,
+            commit: None,
+            branch: None,
+            version: "0.1.0".to_string(),
+            typst_version: "0.14.0".to_string(),
+        };
+        let keys: Vec<_> = metadata.fields().into_iter().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["commit", "branch", "version", "typst_version"]);
+    }
+
+    #[test]
+    fn test_joined_authors() {
+        let mut info = DocumentInfo::default();
+        assert_eq!(info.joined_authors(), None);
+
+        info.authors = vec!["Ada Lovelace".to_string(), "Charles Babbage".to_string()];
+        assert_eq!(
+            info.joined_authors().as_deref(),
+            Some("Ada Lovelace; Charles Babbage")
+        );
+    }
+}
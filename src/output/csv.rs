@@ -4,8 +4,9 @@
 //! suitable for importing into spreadsheet applications and data analysis tools.
 
 use crate::cli::{CountMode, DisplayMode};
-use crate::counter::Count;
-use crate::output::calculate_total;
+use crate::counter::{Count, Metric};
+use crate::metadata::{DocumentInfo, Metadata};
+use crate::output::{FormatOptions, calculate_total, reported_metrics, share_of, visible_rows};
 use std::fmt::Write;
 
 /// Formats count results as CSV.
@@ -18,27 +19,144 @@ use std::fmt::Write;
 /// * `results` - Slice of file paths and their counts
 /// * `display` - Display mode controlling whether to show individual files or totals
 /// * `mode` - What columns to include (words/characters/both)
+/// * `options` - Optional columns and settings
 ///
 /// # Returns
 ///
 /// A CSV-formatted string with header row and data rows.
-pub fn format(results: &[(String, Count)], display: DisplayMode, mode: CountMode) -> String {
+pub fn format(
+    results: &[(String, Count)],
+    display: DisplayMode,
+    mode: CountMode,
+    options: &FormatOptions,
+) -> String {
     let mut output = String::new();
+    let total = calculate_total(results);
 
-    writeln!(output, "{}", format_header(mode)).unwrap();
+    write!(output, "{}", format_header(mode)).unwrap();
+    if options.percent {
+        output.push_str(",percent");
+    }
+    if options.rate.is_some() {
+        output.push_str(",cost");
+    }
+    let metrics = reported_metrics(results);
+    for metric in &metrics {
+        write!(output, ",{}", metric.key()).unwrap();
+    }
+    output.push('\n');
 
-    if display == DisplayMode::Total && results.len() > 1 {
-        let total = calculate_total(results);
-        write_row(&mut output, "total", &total, mode);
+    let rows = if display == DisplayMode::Total && results.len() > 1 {
+        write_row(
+            &mut output,
+            "total",
+            &total,
+            mode,
+            &metrics,
+            share(&total, &total, options),
+            cost(&total, options),
+        );
+        Vec::new()
     } else {
-        for (name, count) in results {
-            write_row(&mut output, name, count, mode);
+        let rows = visible_rows(results, options);
+        for (name, count) in &rows {
+            write_row(
+                &mut output,
+                name,
+                count,
+                mode,
+                &metrics,
+                share(count, &total, options),
+                cost(count, options),
+            );
         }
+        rows
+    };
+
+    if !options.documents.is_empty() {
+        output = with_documents(&output, &rows, options);
+    }
+    if let Some(metadata) = &options.metadata {
+        output = with_metadata(&output, metadata);
+    }
+    match &options.timestamp {
+        Some(timestamp) => with_timestamp(&output, timestamp),
+        None => output,
     }
+}
+
+/// Appends a column for each metadata field to every line of CSV output.
+///
+/// Unknown values are left empty.
+fn with_metadata(csv: &str, metadata: &Metadata) -> String {
+    let fields = metadata.fields();
+    let header: String = fields.iter().map(|(key, _)| format!(",{key}")).collect();
+    let values: String = fields
+        .iter()
+        .map(|(_, value)| format!(",{}", value.unwrap_or_default()))
+        .collect();
 
+    csv.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let extra = if i == 0 { &header } else { &values };
+            format!("{line}{extra}\n")
+        })
+        .collect()
+}
+
+/// Appends `title` and `authors` columns to every line of CSV output.
+///
+/// `rows` are the files of the data lines, in order; the columns are left
+/// empty for the total row and for documents without a title or authors.
+fn with_documents(csv: &str, rows: &[&(String, Count)], options: &FormatOptions) -> String {
+    let mut lines = csv.lines();
+    let mut output = format!("{},title,authors\n", lines.next().unwrap_or_default());
+    for (i, line) in lines.enumerate() {
+        let info = rows
+            .get(i)
+            .and_then(|(name, _)| options.documents.get(name));
+        let title = info.and_then(|info| info.title.clone()).unwrap_or_default();
+        let authors = info
+            .and_then(DocumentInfo::joined_authors)
+            .unwrap_or_default();
+        writeln!(output, "{line},{},{}", quote(&title), quote(&authors)).unwrap();
+    }
     output
 }
 
+/// Quotes a CSV field if it contains a separator, quote, or line break.
+fn quote(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Prepends a `timestamp` column to every line of CSV output.
+///
+/// Used for progress logs, where each run appends its rows to the same file.
+fn with_timestamp(csv: &str, timestamp: &str) -> String {
+    csv.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let first = if i == 0 { "timestamp" } else { timestamp };
+            format!("{first},{line}\n")
+        })
+        .collect()
+}
+
+/// Returns a count's share of the total words if the percent column is enabled.
+fn share(count: &Count, total: &Count, options: &FormatOptions) -> Option<f64> {
+    options.percent.then(|| share_of(count.words, total.words))
+}
+
+/// Returns the cost of a count if a rate is set.
+fn cost(count: &Count, options: &FormatOptions) -> Option<f64> {
+    options.rate.map(|rate| rate.cost(count))
+}
+
 /// Returns the CSV header row based on the counting mode.
 ///
 /// # Arguments
@@ -58,24 +176,53 @@ const fn format_header(mode: CountMode) -> &'static str {
 
 /// Writes a single data row to the CSV output.
 ///
+/// A column is appended for each of `metrics`, left empty if the metric
+/// wasn't computed for this count, e.g. for plain text files.
+///
 /// # Arguments
 ///
 /// * `output` - Mutable string to append the row to
 /// * `name` - File name for the first column
 /// * `count` - Count values to include in the row
 /// * `mode` - What columns to include (words/characters/both)
-fn write_row(output: &mut String, name: &str, count: &Count, mode: CountMode) {
+/// * `metrics` - Metrics reported in the extra columns
+/// * `share` - Percentage of the total words, if that column is enabled
+/// * `cost` - Cost of the count, if a rate is set
+fn write_row(
+    output: &mut String,
+    name: &str,
+    count: &Count,
+    mode: CountMode,
+    metrics: &[Metric],
+    share: Option<f64>,
+    cost: Option<f64>,
+) {
     let row = match mode {
         CountMode::Both => format!("{},{},{}", name, count.words, count.characters),
         CountMode::Words => format!("{},{}", name, count.words),
         CountMode::Characters => format!("{},{}", name, count.characters),
     };
-    writeln!(output, "{row}").unwrap();
+    write!(output, "{row}").unwrap();
+    if let Some(share) = share {
+        write!(output, ",{share:.1}").unwrap();
+    }
+    if let Some(cost) = cost {
+        write!(output, ",{cost:.2}").unwrap();
+    }
+    for &metric in metrics {
+        match count.metrics.get(metric) {
+            Some(value) => write!(output, ",{value}").unwrap(),
+            None => output.push(','),
+        }
+    }
+    output.push('\n');
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::RateUnit;
+    use crate::output::Rate;
 
     #[test]
     fn test_format_header_both() {
@@ -101,8 +248,17 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        write_row(&mut output, "test.typ", &count, CountMode::Both);
+        write_row(
+            &mut output,
+            "test.typ",
+            &count,
+            CountMode::Both,
+            &[],
+            None,
+            None,
+        );
         assert_eq!(output, "test.typ,100,500\n");
     }
 
@@ -112,8 +268,17 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        write_row(&mut output, "test.typ", &count, CountMode::Words);
+        write_row(
+            &mut output,
+            "test.typ",
+            &count,
+            CountMode::Words,
+            &[],
+            None,
+            None,
+        );
         assert_eq!(output, "test.typ,100\n");
     }
 
@@ -123,8 +288,17 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        write_row(&mut output, "test.typ", &count, CountMode::Characters);
+        write_row(
+            &mut output,
+            "test.typ",
+            &count,
+            CountMode::Characters,
+            &[],
+            None,
+            None,
+        );
         assert_eq!(output, "test.typ,500\n");
     }
 
@@ -135,9 +309,15 @@ mod tests {
             Count {
                 words: 100,
                 characters: 500,
+                ..Count::default()
             },
         )];
-        let output = format(&results, DisplayMode::Auto, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         assert_eq!(output, "file,words,characters\ntest.typ,100,500\n");
     }
 
@@ -149,6 +329,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -156,10 +337,16 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format(&results, DisplayMode::Auto, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         assert!(output.starts_with("file,words,characters\n"));
         assert!(output.contains("file1.typ,100,500\n"));
         assert!(output.contains("file2.typ,200,1000\n"));
@@ -173,6 +360,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -180,10 +368,16 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format(&results, DisplayMode::Total, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Total,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         assert_eq!(output, "file,words,characters\ntotal,300,1500\n");
     }
 
@@ -194,9 +388,15 @@ mod tests {
             Count {
                 words: 42,
                 characters: 200,
+                ..Count::default()
             },
         )];
-        let output = format(&results, DisplayMode::Auto, CountMode::Words);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Words,
+            &FormatOptions::default(),
+        );
         assert_eq!(output, "file,words\ntest.typ,42\n");
         assert!(!output.contains("characters"));
     }
@@ -208,9 +408,15 @@ mod tests {
             Count {
                 words: 42,
                 characters: 200,
+                ..Count::default()
             },
         )];
-        let output = format(&results, DisplayMode::Auto, CountMode::Characters);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Characters,
+            &FormatOptions::default(),
+        );
         assert_eq!(output, "file,characters\ntest.typ,200\n");
         assert!(!output.contains("words"));
     }
@@ -222,10 +428,226 @@ mod tests {
             Count {
                 words: 100,
                 characters: 500,
+                ..Count::default()
             },
         )];
         // Total mode with single file doesn't trigger total output (needs len > 1)
-        let output = format(&results, DisplayMode::Total, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Total,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         assert_eq!(output, "file,words,characters\ntest.typ,100,500\n");
     }
+
+    #[test]
+    fn test_format_with_metric_column() {
+        let mut count = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::AbstractWords, 20);
+        let results = vec![("test.typ".to_string(), count)];
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
+        assert_eq!(
+            output,
+            "file,words,characters,abstract_words\ntest.typ,100,500,20\n"
+        );
+    }
+
+    #[test]
+    fn test_format_with_missing_metrics() {
+        let mut document = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        document.metrics.set(Metric::AbstractWords, 20);
+        document.metrics.set(Metric::QuotedWords, 5);
+        let mut notes = Count {
+            words: 30,
+            characters: 150,
+            ..Count::default()
+        };
+        notes.metrics.set(Metric::QuotedWords, 3);
+        let results = vec![
+            ("a.typ".to_string(), document),
+            ("notes.md".to_string(), notes),
+        ];
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Words,
+            &FormatOptions::default(),
+        );
+        assert_eq!(
+            output,
+            "file,words,abstract_words,quoted_words
+a.typ,100,20,5
+notes.md,30,,3
+"
+        );
+    }
+
+    #[test]
+    fn test_format_with_cost_column() {
+        let results = vec![(
+            "test.typ".to_string(),
+            Count {
+                words: 100,
+                characters: 550,
+                ..Count::default()
+            },
+        )];
+        let options = FormatOptions {
+            rate: Some(Rate {
+                amount: 0.02,
+                unit: RateUnit::Character,
+            }),
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(output, "file,words,cost\ntest.typ,100,11.00\n");
+    }
+
+    #[test]
+    fn test_format_with_percent_column() {
+        let results = vec![
+            (
+                "file1.typ".to_string(),
+                Count {
+                    words: 100,
+                    characters: 500,
+                    ..Count::default()
+                },
+            ),
+            (
+                "file2.typ".to_string(),
+                Count {
+                    words: 300,
+                    characters: 1500,
+                    ..Count::default()
+                },
+            ),
+        ];
+        let options = FormatOptions {
+            percent: true,
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            "file,words,percent\nfile1.typ,100,25.0\nfile2.typ,300,75.0\n"
+        );
+    }
+
+    #[test]
+    fn test_format_with_row_filters() {
+        let results: Vec<_> = [("a.typ", 10), ("b.typ", 300), ("c.typ", 50)]
+            .into_iter()
+            .map(|(name, words)| {
+                (
+                    name.to_string(),
+                    Count {
+                        words,
+                        ..Count::default()
+                    },
+                )
+            })
+            .collect();
+        let options = FormatOptions {
+            percent: true,
+            top: Some(2),
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            "file,words,percent\nb.typ,300,83.3\nc.typ,50,13.9\n"
+        );
+    }
+
+    #[test]
+    fn test_format_with_timestamp() {
+        let results = vec![(
+            "test.typ".to_string(),
+            Count {
+                words: 100,
+                characters: 500,
+                ..Count::default()
+            },
+        )];
+        let options = FormatOptions {
+            timestamp: Some("2026-01-17 12:00:00".to_string()),
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Auto, CountMode::Both, &options);
+        assert_eq!(
+            output,
+            "timestamp,file,words,characters\n2026-01-17 12:00:00,test.typ,100,500\n"
+        );
+    }
+
+    #[test]
+    fn test_format_with_metadata() {
+        let results = vec![(
+            "test.typ".to_string(),
+            Count {
+                words: 100,
+                characters: 500,
+                ..Count::default()
+            },
+        )];
+        let options = FormatOptions {
+            metadata: Some(Metadata {
+                timestamp: Some("2026-01-17T12:00:00Z".to_string()),
+                commit: None,
+                branch: Some("main".to_string()),
+                version: "0.1.0".to_string(),
+                typst_version: "0.14.0".to_string(),
+            }),
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            concat!(
+                "file,words,timestamp,commit,branch,version,typst_version\n",
+                "test.typ,100,2026-01-17T12:00:00Z,,main,0.1.0,0.14.0\n"
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_with_documents() {
+        let count = Count {
+            words: 100,
+            ..Count::default()
+        };
+        let results = vec![("a.typ".to_string(), count), ("b.typ".to_string(), count)];
+        let mut options = FormatOptions::default();
+        options.documents.insert(
+            "a.typ".to_string(),
+            DocumentInfo {
+                title: Some("Results, Revisited".to_string()),
+                authors: vec!["Ada".to_string(), "Charles".to_string()],
+            },
+        );
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            concat!(
+                "file,words,title,authors\n",
+                "a.typ,100,\"Results, Revisited\",Ada; Charles\n",
+                "b.typ,100,,\n"
+            )
+        );
+    }
 }
@@ -0,0 +1,106 @@
+//! GitLab metrics report output formatting.
+//!
+//! GitLab CI shows the values of a `metrics` report artifact in the merge
+//! request widget, together with their change against the target branch.
+//! The report is a text file in the OpenMetrics format, one `name value`
+//! pair per line.
+
+use crate::cli::{CountMode, DisplayMode};
+use crate::counter::Count;
+use crate::output::{calculate_total, reported_metrics};
+use std::fmt::Write;
+
+/// Formats count results as a GitLab metrics report.
+///
+/// The totals are written as `words`, `characters`, and the key of each
+/// additional metric. With several files, the per-file values follow with
+/// the file as a label, e.g. `words{file="chapters/intro.typ"} 1200`, unless
+/// only totals are requested.
+///
+/// # Arguments
+///
+/// * `results` - Slice of file paths and their counts
+/// * `display` - Display mode controlling whether to include individual files
+/// * `mode` - Which of words and characters to include
+pub fn format(results: &[(String, Count)], display: DisplayMode, mode: CountMode) -> String {
+    let mut output = String::new();
+    let metrics = reported_metrics(results);
+    let total = calculate_total(results);
+    let per_file = results.len() > 1 && !matches!(display, DisplayMode::Total | DisplayMode::Quiet);
+
+    let mut write_count = |label: &str, count: &Count| {
+        if mode != CountMode::Characters {
+            writeln!(output, "words{label} {}", count.words).unwrap();
+        }
+        if mode != CountMode::Words {
+            writeln!(output, "characters{label} {}", count.characters).unwrap();
+        }
+        for &metric in &metrics {
+            if let Some(value) = count.metrics.get(metric) {
+                writeln!(output, "{}{label} {value}", metric.key()).unwrap();
+            }
+        }
+    };
+
+    write_count("", &total);
+    if per_file {
+        for (name, count) in results {
+            write_count(&format!("{{file=\"{}\"}}", escape_label(name)), count);
+        }
+    }
+    output
+}
+
+/// Escapes a label value for the OpenMetrics format.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::counter::Metric;
+
+    fn count(words: usize, characters: usize) -> Count {
+        Count {
+            words,
+            characters,
+            ..Count::default()
+        }
+    }
+
+    #[test]
+    fn test_format_single_file() {
+        let results = vec![("main.typ".to_string(), count(100, 500))];
+        assert_eq!(
+            format(&results, DisplayMode::Auto, CountMode::Both),
+            "words 100\ncharacters 500\n"
+        );
+    }
+
+    #[test]
+    fn test_format_multiple_files() {
+        let mut intro = count(100, 500);
+        intro.metrics.set(Metric::Links, 2);
+        let results = vec![
+            ("intro.typ".to_string(), intro),
+            ("dir/\"odd\".typ".to_string(), count(50, 250)),
+        ];
+
+        assert_eq!(
+            format(&results, DisplayMode::Auto, CountMode::Words),
+            "words 150\n\
+             links 2\n\
+             words{file=\"intro.typ\"} 100\n\
+             links{file=\"intro.typ\"} 2\n\
+             words{file=\"dir/\\\"odd\\\".typ\"} 50\n"
+        );
+        assert_eq!(
+            format(&results, DisplayMode::Total, CountMode::Characters),
+            "characters 750\nlinks 2\n"
+        );
+    }
+}
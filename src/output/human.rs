@@ -2,39 +2,73 @@
 //!
 //! This module provides functions to format count results in a human-readable
 //! table format with proper alignment and separators.
+//!
+//! Columns are aligned by the width text takes up in a terminal rather than
+//! its length, so names with combining marks or wide characters line up.
+//! Names containing right-to-left text, e.g. Arabic or Hebrew file names,
+//! are wrapped in bidi isolates so they don't reorder the counts next to
+//! them.
 
-use crate::cli::{CountMode, DisplayMode};
-use crate::counter::Count;
-use crate::output::calculate_total;
+use crate::cli::{CountMode, DisplayMode, PathStyle};
+use crate::counter::{Count, Metric};
+use crate::output::{
+    FormatOptions, Grouping, calculate_total, reported_metrics, row_label, share_of, visible_rows,
+};
+use crate::report::{LimitStatus, Limits};
 use std::fmt::Write;
+use unicode_bidi::{BidiClass, bidi_class};
 
 /// Formats count results in human-readable format.
 ///
 /// Produces nicely formatted output with proper alignment, either as a simple
 /// count display for single files or as a table with breakdown for multiple files.
+/// With a summary in the options, a final line states the verdict of the run.
 ///
 /// # Arguments
 ///
 /// * `results` - Slice of file paths and their counts
 /// * `display` - Display mode controlling verbosity
 /// * `mode` - What to count and display (words/characters/both)
+/// * `options` - Optional table columns and settings
 ///
 /// # Returns
 ///
 /// A formatted string ready for display to the user.
-pub fn format(results: &[(String, Count)], display: DisplayMode, mode: CountMode) -> String {
+pub fn format(
+    results: &[(String, Count)],
+    display: DisplayMode,
+    mode: CountMode,
+    options: &FormatOptions,
+) -> String {
     let show_breakdown = match display {
         DisplayMode::Auto => results.len() > 1,
         DisplayMode::Detailed => true,
         DisplayMode::Total | DisplayMode::Quiet => false,
     };
 
-    if show_breakdown {
-        format_table(results, display == DisplayMode::Quiet, mode)
+    let mut output = if show_breakdown {
+        format_table(results, display == DisplayMode::Quiet, mode, options)
     } else {
         let total = calculate_total(results);
-        format_single(&total, display == DisplayMode::Quiet, mode)
+        let mut output = format_single(&total, display == DisplayMode::Quiet, mode);
+        if let Some(rate) = &options.rate {
+            let cost = rate.cost(&total);
+            if display == DisplayMode::Quiet {
+                output.pop();
+                writeln!(output, " {cost:.2}").unwrap();
+            } else {
+                writeln!(output, " {:<11} {cost:.2}", "Cost:").unwrap();
+            }
+        }
+        output
+    };
+    if display != DisplayMode::Quiet && results.iter().any(|(_, count)| count.approximate) {
+        writeln!(output, "\n{APPROXIMATE_NOTE}").unwrap();
+    }
+    if let Some(summary) = &options.summary {
+        writeln!(output, "\n{summary}").unwrap();
     }
+    output
 }
 
 /// Formats a single count result.
@@ -47,57 +81,147 @@ pub fn format(results: &[(String, Count)], display: DisplayMode, mode: CountMode
 /// * `quiet` - If true, omit labels and output only numbers
 /// * `mode` - What to display (words/characters/both)
 fn format_single(count: &Count, quiet: bool, mode: CountMode) -> String {
-    match (mode, quiet) {
+    let mut output = match (mode, quiet) {
         (CountMode::Both, false) => {
             format!(
                 " Words:      {}\n Characters: {}\n",
-                count.words, count.characters
+                number(count.words, count),
+                number(count.characters, count)
             )
         }
         (CountMode::Both, true) => format!("{} {}\n", count.words, count.characters),
-        (CountMode::Words, false) => format!(" Words:      {}\n", count.words),
+        (CountMode::Words, false) => format!(" Words:      {}\n", number(count.words, count)),
         (CountMode::Words, true) => format!("{}\n", count.words),
-        (CountMode::Characters, false) => format!(" Characters: {}\n", count.characters),
+        (CountMode::Characters, false) => {
+            format!(" Characters: {}\n", number(count.characters, count))
+        }
         (CountMode::Characters, true) => format!("{}\n", count.characters),
+    };
+
+    if quiet {
+        if !count.metrics.is_empty() {
+            output.pop();
+            for (_, value) in count.metrics.iter() {
+                write!(output, " {value}").unwrap();
+            }
+            output.push('\n');
+        }
+    } else {
+        for (metric, value) in count.metrics.iter() {
+            writeln!(output, " {:<11} {}", format!("{}:", metric.label()), value).unwrap();
+        }
     }
+
+    output
 }
 
 /// Formats multiple count results as a table.
 ///
 /// Creates a formatted table with columns for file names and counts,
-/// including a separator line and totals row.
+/// including a separator line and totals row. Rows hidden by the row
+/// filters in `options` still contribute to the total. When grouping by
+/// directory, each directory's files are followed by a subtotal row.
 ///
 /// # Arguments
 ///
 /// * `results` - Slice of file paths and their counts
 /// * `quiet` - If true, omit headers and separators
 /// * `mode` - What to display (words/characters/both)
-fn format_table(results: &[(String, Count)], quiet: bool, mode: CountMode) -> String {
+/// * `options` - Optional table columns and settings
+fn format_table(
+    results: &[(String, Count)],
+    quiet: bool,
+    mode: CountMode,
+    options: &FormatOptions,
+) -> String {
     let mut output = String::new();
-    let max_name_len = results.iter().map(|(n, _)| n.len()).max().unwrap_or(0);
+    let rows = visible_rows(results, options);
+    let metrics = reported_metrics(results);
+    let total = calculate_total(results);
+    let mut columns = format_separator(0, mode);
+    if options.percent {
+        columns += &"─".repeat(PERCENT_WIDTH + 1);
+    }
+    if options.rate.is_some() {
+        columns += &"─".repeat(COST_WIDTH + 1);
+    }
+    columns += &metric_separator(&metrics);
+    let name_limit = (options.path_style == PathStyle::Truncate).then(|| {
+        options
+            .width
+            .map_or(TRUNCATED_NAME_WIDTH, |width| {
+                width.saturating_sub(columns.chars().count())
+            })
+            .max(MIN_NAME_WIDTH)
+    });
+    let lines = table_lines(results, &rows, options, name_limit);
+    let max_name_len = lines
+        .iter()
+        .map(|(n, _, _)| display_width(n))
+        .max()
+        .unwrap_or(0);
     let name_width = max_name_len.max(4);
-
-    if !quiet {
-        writeln!(output, "{}", format_header(name_width, mode)).unwrap();
-        writeln!(output, "{}", format_separator(name_width, mode)).unwrap();
+    let separator = "─".repeat(name_width) + &columns;
+    let hidden = results.len() - rows.len();
+    if !quiet
+        && options
+            .width
+            .is_some_and(|width| separator.chars().count() > width)
+    {
+        return format_compact(&lines, hidden, &total, name_width, mode, options);
     }
 
-    for (name, count) in results {
+    if !quiet {
+        let share = if options.percent {
+            format!(" {:>PERCENT_WIDTH$}", "Share")
+        } else {
+            String::new()
+        };
+        let cost = if options.rate.is_some() {
+            format!(" {:>COST_WIDTH$}", "Cost")
+        } else {
+            String::new()
+        };
         writeln!(
             output,
-            "{}",
-            format_row(name, count, name_width, quiet, mode)
+            "{}{}{}{}",
+            format_header(name_width, mode),
+            share,
+            cost,
+            metric_header(&metrics)
         )
         .unwrap();
+        writeln!(output, "{separator}").unwrap();
+    }
+
+    for (name, count, limits) in &lines {
+        let row = format!(
+            "{}{}{}{}",
+            format_row(name, count, name_width, quiet, mode),
+            percent_cell(count, &total, options, quiet),
+            cost_cell(count, options, quiet),
+            metric_cells(count, &metrics, quiet)
+        );
+        writeln!(output, "{}", colored(row, count, *limits, options)).unwrap();
+    }
+
+    if hidden > 0 && !quiet {
+        writeln!(output, "{}", hidden_note(hidden)).unwrap();
     }
 
     if !quiet {
-        writeln!(output, "{}", format_separator(name_width, mode)).unwrap();
-        let total = calculate_total(results);
+        writeln!(output, "{separator}").unwrap();
+        let row = format!(
+            "{}{}{}{}",
+            format_row("Total", &total, name_width, false, mode),
+            percent_cell(&total, &total, options, false),
+            cost_cell(&total, options, false),
+            metric_cells(&total, &metrics, false)
+        );
         write!(
             output,
             "{}",
-            format_row("Total", &total, name_width, false, mode)
+            colored(row, &total, Some(&options.total_limits), options)
         )
         .unwrap();
     }
@@ -105,6 +229,164 @@ fn format_table(results: &[(String, Count)], quiet: bool, mode: CountMode) -> St
     output
 }
 
+/// Formats the table with only the name and one count column, for
+/// terminals too narrow for the full table.
+///
+/// The count column shows words, or characters if only characters are
+/// counted.
+fn format_compact(
+    lines: &[(String, Count, Option<&Limits>)],
+    hidden: usize,
+    total: &Count,
+    name_width: usize,
+    mode: CountMode,
+    options: &FormatOptions,
+) -> String {
+    let mode = match mode {
+        CountMode::Characters => CountMode::Characters,
+        CountMode::Both | CountMode::Words => CountMode::Words,
+    };
+    let separator = format_separator(name_width, mode);
+    let mut output = String::new();
+    writeln!(output, "{}", format_header(name_width, mode)).unwrap();
+    writeln!(output, "{separator}").unwrap();
+    for (name, count, limits) in lines {
+        let row = format_row(name, count, name_width, false, mode);
+        writeln!(output, "{}", colored(row, count, *limits, options)).unwrap();
+    }
+    if hidden > 0 {
+        writeln!(output, "{}", hidden_note(hidden)).unwrap();
+    }
+    writeln!(output, "{separator}").unwrap();
+    let row = format_row("Total", total, name_width, false, mode);
+    write!(
+        output,
+        "{}",
+        colored(row, total, Some(&options.total_limits), options)
+    )
+    .unwrap();
+    output
+}
+
+/// Returns the note on rows hidden by the row filters.
+fn hidden_note(hidden: usize) -> String {
+    let files = if hidden == 1 { "file" } else { "files" };
+    format!("({hidden} more {files} not shown)")
+}
+
+/// Width of the name column when truncating paths without a known width.
+const TRUNCATED_NAME_WIDTH: usize = 40;
+
+/// Narrowest name column paths are truncated to.
+const MIN_NAME_WIDTH: usize = 12;
+
+/// Returns the labelled rows of the table body, with the limits of each row.
+///
+/// Without grouping these are just the visible rows. With `group_by_dir` or
+/// `group_by_type`, the visible rows are arranged by directory or file type
+/// and each group gets a subtotal row covering all of its files, including
+/// hidden ones. Files are labelled by their document's title, if known.
+/// Labels are shortened to `name_limit` columns, if given.
+fn table_lines<'a>(
+    results: &[(String, Count)],
+    rows: &[&(String, Count)],
+    options: &'a FormatOptions,
+    name_limit: Option<usize>,
+) -> Vec<(String, Count, Option<&'a Limits>)> {
+    let label = |label: String| match name_limit {
+        Some(limit) => isolate(truncate_start(label, limit)),
+        None => isolate(label),
+    };
+    let line = |(name, count): &&(String, Count)| {
+        (
+            label(row_label(name, options)),
+            *count,
+            options.row_limits.get(name),
+        )
+    };
+    let Some(grouping) = Grouping::of(options) else {
+        return rows.iter().map(line).collect();
+    };
+
+    let mut lines = Vec::new();
+    for (key, subtotal) in grouping.groups(results) {
+        let start = lines.len();
+        lines.extend(
+            rows.iter()
+                .filter(|(name, _)| grouping.key(name) == key)
+                .map(line),
+        );
+        if lines.len() > start {
+            lines.push((label(grouping.subtotal_label(&key)), subtotal, None));
+        }
+    }
+    lines
+}
+
+/// Colors a table row green, yellow, or red by the status of its limits,
+/// if `color` is enabled.
+///
+/// Rows without limits are left as they are.
+fn colored(row: String, count: &Count, limits: Option<&Limits>, options: &FormatOptions) -> String {
+    if !options.color {
+        return row;
+    }
+    let code = match limits.and_then(|limits| limits.status(count)) {
+        None => return row,
+        Some(LimitStatus::Within) => 32,
+        Some(LimitStatus::Near) => 33,
+        Some(LimitStatus::Violated) => 31,
+    };
+    format!("\x1b[{code}m{row}\x1b[0m")
+}
+
+/// Width of the percent-of-total column, e.g. `100.0%`.
+const PERCENT_WIDTH: usize = 7;
+
+/// Formats the percent-of-total cell of a row, if enabled.
+///
+/// # Arguments
+///
+/// * `count` - Count of the row
+/// * `total` - Total count across all rows
+/// * `options` - Whether the column is enabled
+/// * `quiet` - If true, output the bare number without alignment
+fn percent_cell(count: &Count, total: &Count, options: &FormatOptions, quiet: bool) -> String {
+    if !options.percent {
+        return String::new();
+    }
+
+    let share = format!("{:.1}%", share_of(count.words, total.words));
+    if quiet {
+        format!(" {share}")
+    } else {
+        format!(" {share:>PERCENT_WIDTH$}")
+    }
+}
+
+/// Width of the cost column, e.g. `123456.78`.
+const COST_WIDTH: usize = 10;
+
+/// Formats the cost cell of a row, if a rate is set.
+///
+/// # Arguments
+///
+/// * `count` - Count of the row
+/// * `options` - The rate, if any
+/// * `quiet` - If true, output the bare number without alignment
+fn cost_cell(count: &Count, options: &FormatOptions, quiet: bool) -> String {
+    let Some(rate) = &options.rate else {
+        return String::new();
+    };
+
+    let cost = format!("{:.2}", rate.cost(count));
+    if quiet {
+        format!(" {cost}")
+    } else {
+        format!(" {cost:>COST_WIDTH$}")
+    }
+}
+
 /// Formats the table header row.
 ///
 /// # Arguments
@@ -150,6 +432,58 @@ fn format_separator(name_width: usize, mode: CountMode) -> String {
     "─".repeat(total_width)
 }
 
+/// Returns the width of the table column for a metric.
+fn metric_width(metric: Metric) -> usize {
+    metric.label().len().max(12)
+}
+
+/// Formats the header cells for additional metric columns.
+///
+/// # Arguments
+///
+/// * `metrics` - The metrics shown as extra columns
+fn metric_header(metrics: &[Metric]) -> String {
+    metrics
+        .iter()
+        .map(|&metric| format!(" {:>width$}", metric.label(), width = metric_width(metric)))
+        .collect()
+}
+
+/// Formats the separator for additional metric columns.
+///
+/// # Arguments
+///
+/// * `metrics` - The metrics shown as extra columns
+fn metric_separator(metrics: &[Metric]) -> String {
+    "─".repeat(metrics.iter().map(|&m| metric_width(m) + 1).sum())
+}
+
+/// Formats the additional metric cells of a row.
+///
+/// Metrics missing from the count are shown as `-`.
+///
+/// # Arguments
+///
+/// * `count` - Count whose metrics to display
+/// * `metrics` - The metrics shown as extra columns
+/// * `quiet` - If true, output bare numbers without alignment
+fn metric_cells(count: &Count, metrics: &[Metric], quiet: bool) -> String {
+    metrics
+        .iter()
+        .map(|&metric| {
+            let value = count
+                .metrics
+                .get(metric)
+                .map_or_else(|| "-".to_string(), |v| v.to_string());
+            if quiet {
+                format!(" {value}")
+            } else {
+                format!(" {:>width$}", value, width = metric_width(metric))
+            }
+        })
+        .collect()
+}
+
 /// Formats a single row in the table.
 ///
 /// # Arguments
@@ -173,40 +507,148 @@ fn format_row(
             CountMode::Characters => format!("{}", count.characters),
         }
     } else {
+        let name = pad(name, name_width);
+        let words = number(count.words, count);
+        let characters = number(count.characters, count);
         match mode {
-            CountMode::Both => {
-                format!(
-                    "{:<width$} {:>12} {:>12}",
-                    name,
-                    count.words,
-                    count.characters,
-                    width = name_width
-                )
-            }
-            CountMode::Words => {
-                format!("{:<width$} {:>12}", name, count.words, width = name_width)
-            }
-            CountMode::Characters => {
-                format!(
-                    "{:<width$} {:>12}",
-                    name,
-                    count.characters,
-                    width = name_width
-                )
-            }
+            CountMode::Both => format!("{name} {words:>12} {characters:>12}"),
+            CountMode::Words => format!("{name} {words:>12}"),
+            CountMode::Characters => format!("{name} {characters:>12}"),
         }
     }
 }
 
+/// Note explaining the mark of approximate counts.
+const APPROXIMATE_NOTE: &str =
+    "~ approximate: counted from the source text of a document that failed to compile";
+
+/// Formats a value of a count, marked with `~` if the count is approximate.
+fn number(value: usize, count: &Count) -> String {
+    if count.approximate {
+        format!("~{value}")
+    } else {
+        value.to_string()
+    }
+}
+
+/// Shortens text to at most `width` columns by replacing its start with an
+/// ellipsis, keeping the end of a path, which names the file.
+fn truncate_start(text: String, width: usize) -> String {
+    if display_width(&text) <= width {
+        return text;
+    }
+    let mut kept = 1;
+    let start = text
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| {
+            kept += display_width(c.encode_utf8(&mut [0; 4]));
+            kept <= width
+        })
+        .last()
+        .map_or(text.len(), |(index, _)| index);
+    format!("…{}", &text[start..])
+}
+
+/// Pads text with spaces to take up `width` columns in a terminal.
+fn pad(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
+
+/// Returns the number of columns text takes up in a terminal.
+///
+/// Combining marks and invisible formatting characters such as bidi
+/// isolates take up none, and East Asian wide and fullwidth characters take
+/// up two.
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match bidi_class(c) {
+            BidiClass::NSM
+            | BidiClass::BN
+            | BidiClass::LRE
+            | BidiClass::RLE
+            | BidiClass::LRO
+            | BidiClass::RLO
+            | BidiClass::PDF
+            | BidiClass::LRI
+            | BidiClass::RLI
+            | BidiClass::FSI
+            | BidiClass::PDI => 0,
+            _ if is_wide(c) => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Checks if a character is East Asian wide or fullwidth.
+const fn is_wide(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{115F}'
+        | '\u{2E80}'..='\u{303E}'
+        | '\u{3041}'..='\u{33FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{A000}'..='\u{A4CF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{FE30}'..='\u{FE4F}'
+        | '\u{FF00}'..='\u{FF60}'
+        | '\u{FFE0}'..='\u{FFE6}'
+        | '\u{1F300}'..='\u{1F64F}'
+        | '\u{1F900}'..='\u{1F9FF}'
+        | '\u{20000}'..='\u{3FFFD}')
+}
+
+/// Wraps text containing right-to-left characters in a first-strong bidi
+/// isolate, so the terminal lays it out apart from the rest of the row.
+fn isolate(text: String) -> String {
+    if text
+        .chars()
+        .any(|c| matches!(bidi_class(c), BidiClass::R | BidiClass::AL))
+    {
+        format!("\u{2068}{text}\u{2069}")
+    } else {
+        text
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::RateUnit;
+    use crate::output::Rate;
+
+    #[test]
+    fn test_format_with_summary() {
+        let count = Count {
+            words: 18342,
+            ..Count::default()
+        };
+        let options = FormatOptions {
+            summary: Some(crate::output::Summary {
+                files: 1,
+                words: 18342,
+                violations: 0,
+                errors: 0,
+            }),
+            ..FormatOptions::default()
+        };
+        let output = format(
+            &[("a.typ".to_string(), count)],
+            DisplayMode::Auto,
+            CountMode::Words,
+            &options,
+        );
+        assert!(output.ends_with("\n\n1 file, 18,342 words, 0 limit violations, 0 errors\n"));
+    }
 
     #[test]
     fn test_format_single_both() {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
         let output = format_single(&count, false, CountMode::Both);
         assert!(output.contains("100"));
@@ -220,6 +662,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
         let output = format_single(&count, false, CountMode::Words);
         assert!(output.contains("100"));
@@ -232,6 +675,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
         let output = format_single(&count, false, CountMode::Characters);
         assert!(!output.contains("100"));
@@ -244,6 +688,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
         let output = format_single(&count, true, CountMode::Both);
         assert_eq!(output, "100 500\n");
@@ -254,6 +699,7 @@ mod tests {
         let count = Count {
             words: 42,
             characters: 500,
+            ..Count::default()
         };
         let output = format_single(&count, true, CountMode::Words);
         assert_eq!(output, "42\n");
@@ -267,6 +713,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -274,10 +721,11 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format_table(&results, false, CountMode::Both);
+        let output = format_table(&results, false, CountMode::Both, &FormatOptions::default());
         assert!(output.contains("file1.typ"));
         assert!(output.contains("file2.typ"));
         assert!(output.contains("100"));
@@ -297,6 +745,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -304,10 +753,11 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format_table(&results, true, CountMode::Both);
+        let output = format_table(&results, true, CountMode::Both, &FormatOptions::default());
         assert!(!output.contains("File"));
         assert!(!output.contains("Total"));
         assert!(output.contains("100 500"));
@@ -347,6 +797,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
         let row = format_row("test.typ", &count, 10, false, CountMode::Both);
         assert!(row.contains("test.typ"));
@@ -359,6 +810,7 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
         let row = format_row("test.typ", &count, 10, true, CountMode::Both);
         assert_eq!(row, "100 500");
@@ -372,9 +824,15 @@ mod tests {
             Count {
                 words: 100,
                 characters: 500,
+                ..Count::default()
             },
         )];
-        let output = format(&results, DisplayMode::Auto, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         // Should use simple format for single file
         assert!(output.contains("100"));
         assert!(output.contains("500"));
@@ -389,6 +847,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -396,10 +855,16 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format(&results, DisplayMode::Auto, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         // Should use table format for multiple files
         assert!(output.contains("file1.typ"));
         assert!(output.contains("file2.typ"));
@@ -413,9 +878,15 @@ mod tests {
             Count {
                 words: 100,
                 characters: 500,
+                ..Count::default()
             },
         )];
-        let output = format(&results, DisplayMode::Detailed, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Detailed,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         // Should use table format even for single file
         assert!(output.contains("test.typ"));
         assert!(output.contains("Total"));
@@ -429,6 +900,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -436,10 +908,16 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format(&results, DisplayMode::Total, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Total,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         // Should show only total, no breakdown
         assert!(!output.contains("file1.typ"));
         assert!(!output.contains("file2.typ"));
@@ -455,6 +933,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -462,11 +941,361 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format(&results, DisplayMode::Quiet, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Quiet,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         // Should show only numbers, no labels
         assert_eq!(output.trim(), "300 1500");
     }
+
+    #[test]
+    fn test_format_single_with_metric() {
+        let mut count = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::AbstractWords, 20);
+
+        let output = format_single(&count, false, CountMode::Both);
+        assert!(output.ends_with(" Abstract words: 20\n"));
+
+        let quiet = format_single(&count, true, CountMode::Both);
+        assert_eq!(quiet, "100 500 20\n");
+    }
+
+    #[test]
+    fn test_format_table_with_metric_column() {
+        let mut count = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::AbstractWords, 20);
+        let results = vec![("file1.typ".to_string(), count)];
+
+        let output = format_table(&results, false, CountMode::Both, &FormatOptions::default());
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].ends_with("Abstract words"));
+        assert!(lines[2].ends_with(" 20"));
+        assert_eq!(lines[0].chars().count(), lines[1].chars().count());
+    }
+
+    #[test]
+    fn test_format_table_with_percent() {
+        let results = vec![
+            (
+                "file1.typ".to_string(),
+                Count {
+                    words: 100,
+                    characters: 500,
+                    ..Count::default()
+                },
+            ),
+            (
+                "file2.typ".to_string(),
+                Count {
+                    words: 300,
+                    characters: 1500,
+                    ..Count::default()
+                },
+            ),
+        ];
+        let options = FormatOptions {
+            percent: true,
+            ..FormatOptions::default()
+        };
+        let output = format_table(&results, false, CountMode::Both, &options);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert!(lines[0].ends_with("Share"));
+        assert!(lines[2].ends_with(" 25.0%"));
+        assert!(lines[3].ends_with(" 75.0%"));
+        assert!(lines[5].ends_with("100.0%"));
+        assert_eq!(lines[0].chars().count(), lines[1].chars().count());
+    }
+
+    #[test]
+    fn test_format_with_cost() {
+        let count = Count {
+            words: 1000,
+            characters: 6000,
+            ..Count::default()
+        };
+        let results = vec![
+            ("file1.typ".to_string(), count),
+            ("file2.typ".to_string(), count),
+        ];
+        let options = FormatOptions {
+            rate: Some(Rate {
+                amount: 0.12,
+                unit: RateUnit::Word,
+            }),
+            ..FormatOptions::default()
+        };
+
+        let output = format_table(&results, false, CountMode::Both, &options);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].ends_with("      Cost"));
+        assert!(lines[2].ends_with("    120.00"));
+        assert!(lines[5].ends_with("    240.00"));
+        assert_eq!(lines[0].chars().count(), lines[1].chars().count());
+
+        let single = format(&results[..1], DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(single, " Words:      1000\n Cost:       120.00\n");
+    }
+
+    #[test]
+    fn test_format_table_with_min_row_words() {
+        let results: Vec<_> = [("main.typ", 400), ("macros.typ", 2), ("intro.typ", 100)]
+            .into_iter()
+            .map(|(name, words)| {
+                (
+                    name.to_string(),
+                    Count {
+                        words,
+                        ..Count::default()
+                    },
+                )
+            })
+            .collect();
+        let options = FormatOptions {
+            min_row_words: Some(10),
+            ..FormatOptions::default()
+        };
+        let output = format_table(&results, false, CountMode::Words, &options);
+
+        assert!(!output.contains("macros.typ"));
+        assert!(output.contains("(1 more file not shown)"));
+        // The total still includes the hidden row
+        assert!(output.lines().last().unwrap().ends_with("502"));
+    }
+
+    #[test]
+    fn test_format_table_grouped_by_dir() {
+        let results: Vec<_> = [
+            ("chapters/intro.typ", 100),
+            ("appendix/a.typ", 50),
+            ("chapters/method.typ", 200),
+        ]
+        .into_iter()
+        .map(|(name, words)| {
+            (
+                name.to_string(),
+                Count {
+                    words,
+                    ..Count::default()
+                },
+            )
+        })
+        .collect();
+        let options = FormatOptions {
+            group_by_dir: true,
+            ..FormatOptions::default()
+        };
+        let output = format_table(&results, false, CountMode::Words, &options);
+        let names: Vec<&str> = output
+            .lines()
+            .skip(2)
+            .filter(|line| !line.starts_with('─'))
+            .map(|line| line.split("  ").next().unwrap().trim_end())
+            .collect();
+
+        assert_eq!(
+            names,
+            [
+                "chapters/intro.typ",
+                "chapters/method.typ",
+                "chapters/ subtotal",
+                "appendix/a.typ",
+                "appendix/ subtotal",
+                "Total"
+            ]
+        );
+        let subtotal = output
+            .lines()
+            .find(|line| line.starts_with("chapters/ subtotal"));
+        assert!(subtotal.unwrap().ends_with(" 300"));
+    }
+
+    #[test]
+    fn test_format_table_flags_shared() {
+        let count = Count {
+            words: 100,
+            ..Count::default()
+        };
+        let results = vec![("a.typ".to_string(), count), ("b.typ".to_string(), count)];
+        let mut options = FormatOptions::default();
+        options.shared_words.insert("b.typ".to_string(), 40);
+
+        let output = format_table(&results, false, CountMode::Words, &options);
+        assert!(output.contains("\na.typ "));
+        assert!(output.contains("\nb.typ (-40 shared) "));
+    }
+
+    #[test]
+    fn test_format_table_marks_approximate() {
+        let count = Count {
+            words: 100,
+            ..Count::default()
+        };
+        let approximate = Count {
+            approximate: true,
+            ..count
+        };
+        let results = vec![
+            ("a.typ".to_string(), count),
+            ("b.typ".to_string(), approximate),
+        ];
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Words,
+            &FormatOptions::default(),
+        );
+        assert!(output.contains("\na.typ          100\n"));
+        assert!(output.contains("\nb.typ         ~100\n"));
+        assert!(output.contains("\nTotal         ~200\n"));
+        assert!(output.ends_with(&format!("\n{APPROXIMATE_NOTE}\n")));
+    }
+
+    #[test]
+    fn test_format_table_colors_rows_by_limits() {
+        let count = |words| Count {
+            words,
+            ..Count::default()
+        };
+        let results = vec![
+            ("intro".to_string(), count(95)),
+            ("body".to_string(), count(500)),
+            ("notes".to_string(), count(10)),
+        ];
+        let limit = |max| Limits {
+            max_words: Some(max),
+            ..Limits::default()
+        };
+        let mut options = FormatOptions {
+            total_limits: limit(1000),
+            ..FormatOptions::default()
+        };
+        options.row_limits.insert("intro".to_string(), limit(100));
+        options.row_limits.insert("body".to_string(), limit(400));
+
+        let plain = format_table(&results, false, CountMode::Words, &options);
+        assert!(!plain.contains('\x1b'));
+
+        options.color = true;
+        let output = format_table(&results, false, CountMode::Words, &options);
+        assert!(output.contains("\x1b[33mintro "));
+        assert!(output.contains("\x1b[31mbody "));
+        assert!(output.contains("\nnotes "));
+        assert!(output.contains("\x1b[32mTotal "));
+    }
+
+    #[test]
+    fn test_format_table_compact_when_narrow() {
+        let count = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        let results = vec![("a.typ".to_string(), count), ("b.typ".to_string(), count)];
+        let mut options = FormatOptions {
+            percent: true,
+            width: Some(80),
+            ..FormatOptions::default()
+        };
+
+        let wide = format_table(&results, false, CountMode::Both, &options);
+        assert!(wide.contains("Characters"));
+        assert!(wide.contains("Share"));
+
+        options.width = Some(30);
+        let narrow = format_table(&results, false, CountMode::Both, &options);
+        let lines: Vec<&str> = narrow.lines().collect();
+        assert_eq!(lines[0], "File         Words");
+        assert_eq!(lines[2], "a.typ          100");
+        assert_eq!(lines[5], "Total          200");
+        assert!(lines.iter().all(|line| line.chars().count() <= 30));
+    }
+
+    #[test]
+    fn test_format_table_aligns_wide_and_rtl_names() {
+        let count = Count {
+            words: 100,
+            ..Count::default()
+        };
+        let results = vec![
+            ("مقدمة.typ".to_string(), count),
+            ("第一章.typ".to_string(), count),
+            ("cafe\u{301}.typ".to_string(), count),
+        ];
+        let output = format_table(&results, false, CountMode::Words, &FormatOptions::default());
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[2], "\u{2068}مقدمة.typ\u{2069}           100");
+        assert_eq!(lines[3], "第一章.typ          100");
+        assert_eq!(lines[4], "cafe\u{301}.typ            100");
+        assert!(lines[2..].iter().all(|line| display_width(line) == 23));
+    }
+
+    #[test]
+    fn test_format_table_path_styles() {
+        let count = Count {
+            words: 100,
+            ..Count::default()
+        };
+        let name = "chapters/part-one/introduction-and-motivation.typ";
+        let results = vec![(name.to_string(), count), ("notes.typ".to_string(), count)];
+        let table = |path_style, width| {
+            let options = FormatOptions {
+                path_style,
+                width,
+                ..FormatOptions::default()
+            };
+            format_table(&results, false, CountMode::Words, &options)
+        };
+
+        assert!(table(PathStyle::Full, None).contains(&format!("\n{name} ")));
+        assert!(table(PathStyle::Relative, None).contains(&format!("\n{name} ")));
+        assert!(table(PathStyle::Filename, None).contains("\nintroduction-and-motivation.typ "));
+        assert!(
+            table(PathStyle::Truncate, None)
+                .contains("\n…art-one/introduction-and-motivation.typ ")
+        );
+
+        let narrow = table(PathStyle::Truncate, Some(40));
+        assert!(narrow.contains("\n…duction-and-motivation.typ "));
+        assert!(narrow.lines().all(|line| display_width(line) <= 40));
+        assert_eq!(truncate_start("short.typ".to_string(), 12), "short.typ");
+    }
+
+    #[test]
+    fn test_format_table_labels_rows_by_title() {
+        let count = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        let results = vec![("a.typ".to_string(), count), ("b.typ".to_string(), count)];
+        let mut options = FormatOptions::default();
+        options.documents.insert(
+            "a.typ".to_string(),
+            crate::metadata::DocumentInfo {
+                title: Some("Introduction".to_string()),
+                authors: vec![],
+            },
+        );
+        let output = format_table(&results, false, CountMode::Both, &options);
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[2].starts_with("Introduction "));
+        assert!(lines[3].starts_with("b.typ "));
+    }
 }
@@ -5,28 +5,74 @@
 
 use crate::cli::{CountMode, DisplayMode};
 use crate::counter::Count;
-use crate::output::calculate_total;
+use crate::metadata::{DocumentInfo, Metadata};
+use crate::output::{FormatOptions, Grouping, Rate, Summary, calculate_total};
+use std::collections::HashMap;
+
+/// Version of the JSON output structure described by [`SCHEMA`].
+///
+/// Bumped whenever a field is renamed or removed, or the structure changes in
+/// a way that could break consumers. New optional fields don't bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema describing the output of this formatter.
+pub const SCHEMA: &str = include_str!("schema.json");
+
+/// Formats an error as a JSON error object, see `--json-errors`.
+///
+/// The object holds the error's message and the chain of its causes, e.g.
+/// `{"error":{"message":"Failed to compile: a.typ","causes":["..."]}}`.
+#[must_use]
+pub fn format_error(error: &anyhow::Error) -> String {
+    let causes: Vec<String> = error.chain().skip(1).map(ToString::to_string).collect();
+    format!(
+        "{}\n",
+        serde_json::json!({
+            "error": {
+                "message": error.to_string(),
+                "causes": causes,
+            }
+        })
+    )
+}
 
 /// Formats count results as JSON.
 ///
 /// Produces valid JSON output, either as a single object for one file
-/// or as an array of objects for multiple files.
+/// or as an array of objects for multiple files. When grouping by directory,
+/// the array holds one object per directory with its subtotal and files.
 ///
 /// # Arguments
 ///
 /// * `results` - Slice of file paths and their counts
 /// * `display` - Display mode controlling output structure
 /// * `mode` - What to include in the output (words/characters/both)
+/// * `options` - Optional settings; `rate`, `group_by_dir`, `metadata`,
+///   `documents`, and `summary` apply to JSON
 ///
 /// # Returns
 ///
 /// A JSON string representing the count results.
-pub fn format(results: &[(String, Count)], display: DisplayMode, mode: CountMode) -> String {
-    if results.len() == 1 || display == DisplayMode::Total {
+pub fn format(
+    results: &[(String, Count)],
+    display: DisplayMode,
+    mode: CountMode,
+    options: &FormatOptions,
+) -> String {
+    let metadata =
+        metadata_fields(options.metadata.as_ref()) + &summary_field(options.summary.as_ref());
+    let documents = &options.documents;
+    let rate = options.rate.as_ref();
+    if let [(name, count)] = results {
+        let fields = cost_field(rate, count) + &document_fields(documents, name) + &metadata;
+        format_single(count, mode, &fields)
+    } else if display == DisplayMode::Total {
         let total = calculate_total(results);
-        format_single(&total, mode)
+        format_single(&total, mode, &(cost_field(rate, &total) + &metadata))
+    } else if let Some(grouping) = Grouping::of(options) {
+        format_groups(results, grouping, mode, rate, documents, &metadata)
     } else {
-        format_array(results, mode)
+        format_array(results, mode, rate, documents, &metadata)
     }
 }
 
@@ -36,30 +82,117 @@ pub fn format(results: &[(String, Count)], display: DisplayMode, mode: CountMode
 ///
 /// * `count` - The count to format
 /// * `mode` - What fields to include (words/characters/both)
-fn format_single(count: &Count, mode: CountMode) -> String {
+/// * `metadata` - Pre-formatted metadata fields to append
+fn format_single(count: &Count, mode: CountMode, metadata: &str) -> String {
+    let metrics = metric_fields(count) + metadata;
     match mode {
         CountMode::Both => {
             format!(
-                r#"{{"words":{},"characters":{}}}"#,
-                count.words, count.characters
+                r#"{{"words":{},"characters":{}{}}}"#,
+                count.words, count.characters, metrics
             )
         }
-        CountMode::Words => format!(r#"{{"words":{}}}"#, count.words),
-        CountMode::Characters => format!(r#"{{"characters":{}}}"#, count.characters),
+        CountMode::Words => format!(r#"{{"words":{}{}}}"#, count.words, metrics),
+        CountMode::Characters => {
+            format!(r#"{{"characters":{}{}}}"#, count.characters, metrics)
+        }
     }
 }
 
+/// Formats the additional metrics of a count as JSON object fields,
+/// followed by `"approximate":true` for approximate counts.
+///
+/// Each field is preceded by a comma, so the result can be appended directly
+/// after the word and character fields. Returns an empty string if no
+/// metrics were computed and the count is exact.
+fn metric_fields(count: &Count) -> String {
+    let mut fields: String = count
+        .metrics
+        .iter()
+        .map(|(metric, value)| format!(r#","{}":{}"#, metric.key(), value))
+        .collect();
+    if count.approximate {
+        fields.push_str(r#","approximate":true"#);
+    }
+    fields
+}
+
+/// Formats run metadata as JSON object fields.
+///
+/// Like [`metric_fields`], each field is preceded by a comma. Unknown values
+/// are written as `null`. Returns an empty string if there is no metadata.
+fn metadata_fields(metadata: Option<&Metadata>) -> String {
+    metadata
+        .into_iter()
+        .flat_map(Metadata::fields)
+        .map(|(key, value)| match value {
+            Some(value) => format!(r#","{key}":"{value}""#),
+            None => format!(r#","{key}":null"#),
+        })
+        .collect()
+}
+
+/// Formats the verdict of the run as a `summary` object field.
+///
+/// Like [`metric_fields`], the field is preceded by a comma. It is added to
+/// every object like the run metadata, so consumers of an array can read it
+/// from any entry. Returns an empty string if there is no summary.
+fn summary_field(summary: Option<&Summary>) -> String {
+    summary
+        .map(|summary| {
+            format!(
+                r#","summary":{{"files":{},"words":{},"violations":{},"errors":{}}}"#,
+                summary.files, summary.words, summary.violations, summary.errors
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Formats the cost of a count at a rate as a JSON object field.
+///
+/// Like [`metric_fields`], the field is preceded by a comma. Returns an empty
+/// string if no rate is set.
+fn cost_field(rate: Option<&Rate>, count: &Count) -> String {
+    rate.map(|rate| format!(r#","cost":{:.2}"#, rate.cost(count)))
+        .unwrap_or_default()
+}
+
+/// Formats the title and authors of a document as JSON object fields.
+///
+/// Like [`metric_fields`], each field is preceded by a comma. Returns an
+/// empty string if nothing is known about the document.
+fn document_fields(documents: &HashMap<String, DocumentInfo>, name: &str) -> String {
+    let Some(info) = documents.get(name) else {
+        return String::new();
+    };
+    format!(
+        r#","title":{},"authors":{}"#,
+        serde_json::json!(info.title),
+        serde_json::json!(info.authors)
+    )
+}
+
 /// Formats multiple counts as a JSON array.
 ///
 /// # Arguments
 ///
 /// * `results` - Slice of file paths and their counts
 /// * `mode` - What fields to include in each object (words/characters/both)
-fn format_array(results: &[(String, Count)], mode: CountMode) -> String {
+/// * `rate` - Price for the cost field, if any
+/// * `documents` - Title and authors of the documents, by file name
+/// * `metadata` - Pre-formatted metadata fields to append to each object
+fn format_array(
+    results: &[(String, Count)],
+    mode: CountMode,
+    rate: Option<&Rate>,
+    documents: &HashMap<String, DocumentInfo>,
+    metadata: &str,
+) -> String {
     let mut output = String::from("[\n");
     for (i, (name, count)) in results.iter().enumerate() {
         let comma = if i < results.len() - 1 { "," } else { "" };
-        let entry = format_entry(name, count, mode, comma);
+        let fields = cost_field(rate, count) + &document_fields(documents, name) + metadata;
+        let entry = format_entry(name, count, mode, comma, &fields);
         output.push_str(&entry);
         output.push('\n');
     }
@@ -67,6 +200,57 @@ fn format_array(results: &[(String, Count)], mode: CountMode) -> String {
     output
 }
 
+/// Formats counts grouped by directory or file type as a JSON array.
+///
+/// Each group object holds the group's subtotal and a `files` array with
+/// the entries of its files.
+///
+/// # Arguments
+///
+/// * `results` - Slice of file paths and their counts
+/// * `grouping` - Whether to group by directory or by file type
+/// * `mode` - What fields to include in each object (words/characters/both)
+/// * `rate` - Price for the cost fields, if any
+/// * `documents` - Title and authors of the documents, by file name
+/// * `metadata` - Pre-formatted metadata fields to append to each object
+fn format_groups(
+    results: &[(String, Count)],
+    grouping: Grouping,
+    mode: CountMode,
+    rate: Option<&Rate>,
+    documents: &HashMap<String, DocumentInfo>,
+    metadata: &str,
+) -> String {
+    let groups = grouping.groups(results);
+    let mut output = String::from("[\n");
+    for (i, (key, subtotal)) in groups.iter().enumerate() {
+        let subtotal = format_single(subtotal, mode, &(cost_field(rate, subtotal) + metadata));
+        output.push_str(&format!(
+            "  {{\"{}\":\"{}\",{},\"files\":[\n",
+            grouping.field(),
+            key,
+            &subtotal[1..subtotal.len() - 1]
+        ));
+
+        let files: Vec<_> = results
+            .iter()
+            .filter(|(name, _)| grouping.key(name) == *key)
+            .collect();
+        for (j, (name, count)) in files.iter().enumerate() {
+            let comma = if j < files.len() - 1 { "," } else { "" };
+            output.push_str("  ");
+            let fields = cost_field(rate, count) + &document_fields(documents, name) + metadata;
+            output.push_str(&format_entry(name, count, mode, comma, &fields));
+            output.push('\n');
+        }
+
+        let comma = if i < groups.len() - 1 { "," } else { "" };
+        output.push_str(&format!("  ]}}{comma}\n"));
+    }
+    output.push(']');
+    output
+}
+
 /// Formats a single entry in a JSON array.
 ///
 /// # Arguments
@@ -75,24 +259,26 @@ fn format_array(results: &[(String, Count)], mode: CountMode) -> String {
 /// * `count` - Count values to include
 /// * `mode` - What fields to include (words/characters/both)
 /// * `comma` - Trailing comma for array formatting
-fn format_entry(name: &str, count: &Count, mode: CountMode, comma: &str) -> String {
+/// * `metadata` - Pre-formatted metadata fields to append
+fn format_entry(name: &str, count: &Count, mode: CountMode, comma: &str, metadata: &str) -> String {
+    let metrics = metric_fields(count) + metadata;
     match mode {
         CountMode::Both => {
             format!(
-                r#"  {{"file":"{}","words":{},"characters":{}}}{}"#,
-                name, count.words, count.characters, comma
+                r#"  {{"file":"{}","words":{},"characters":{}{}}}{}"#,
+                name, count.words, count.characters, metrics, comma
             )
         }
         CountMode::Words => {
             format!(
-                r#"  {{"file":"{}","words":{}}}{}"#,
-                name, count.words, comma
+                r#"  {{"file":"{}","words":{}{}}}{}"#,
+                name, count.words, metrics, comma
             )
         }
         CountMode::Characters => {
             format!(
-                r#"  {{"file":"{}","characters":{}}}{}"#,
-                name, count.characters, comma
+                r#"  {{"file":"{}","characters":{}{}}}{}"#,
+                name, count.characters, metrics, comma
             )
         }
     }
@@ -101,14 +287,17 @@ fn format_entry(name: &str, count: &Count, mode: CountMode, comma: &str) -> Stri
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::RateUnit;
+    use crate::counter::Metric;
 
     #[test]
     fn test_format_single_both() {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        let output = format_single(&count, CountMode::Both);
+        let output = format_single(&count, CountMode::Both, "");
         assert_eq!(output, r#"{"words":100,"characters":500}"#);
     }
 
@@ -117,8 +306,9 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        let output = format_single(&count, CountMode::Words);
+        let output = format_single(&count, CountMode::Words, "");
         assert_eq!(output, r#"{"words":100}"#);
     }
 
@@ -127,8 +317,9 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        let output = format_single(&count, CountMode::Characters);
+        let output = format_single(&count, CountMode::Characters, "");
         assert_eq!(output, r#"{"characters":500}"#);
     }
 
@@ -137,8 +328,9 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        let entry = format_entry("test.typ", &count, CountMode::Both, ",");
+        let entry = format_entry("test.typ", &count, CountMode::Both, ",", "");
         assert_eq!(
             entry,
             r#"  {"file":"test.typ","words":100,"characters":500},"#
@@ -150,8 +342,9 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        let entry = format_entry("test.typ", &count, CountMode::Both, "");
+        let entry = format_entry("test.typ", &count, CountMode::Both, "", "");
         assert_eq!(
             entry,
             r#"  {"file":"test.typ","words":100,"characters":500}"#
@@ -163,8 +356,9 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        let entry = format_entry("test.typ", &count, CountMode::Words, "");
+        let entry = format_entry("test.typ", &count, CountMode::Words, "", "");
         assert_eq!(entry, r#"  {"file":"test.typ","words":100}"#);
     }
 
@@ -173,8 +367,9 @@ mod tests {
         let count = Count {
             words: 100,
             characters: 500,
+            ..Count::default()
         };
-        let entry = format_entry("test.typ", &count, CountMode::Characters, "");
+        let entry = format_entry("test.typ", &count, CountMode::Characters, "", "");
         assert_eq!(entry, r#"  {"file":"test.typ","characters":500}"#);
     }
 
@@ -186,6 +381,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -193,10 +389,11 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format_array(&results, CountMode::Both);
+        let output = format_array(&results, CountMode::Both, None, &HashMap::new(), "");
         assert!(output.starts_with("[\n"));
         assert!(output.ends_with(']'));
         assert!(output.contains(r#""file":"file1.typ""#));
@@ -214,9 +411,15 @@ mod tests {
             Count {
                 words: 100,
                 characters: 500,
+                ..Count::default()
             },
         )];
-        let output = format(&results, DisplayMode::Auto, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         assert_eq!(output, r#"{"words":100,"characters":500}"#);
     }
 
@@ -228,6 +431,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -235,10 +439,16 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format(&results, DisplayMode::Auto, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         assert!(output.starts_with("[\n"));
         assert!(output.contains(r#""file":"file1.typ""#));
         assert!(output.contains(r#""file":"file2.typ""#));
@@ -252,6 +462,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -259,10 +470,16 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
         ];
-        let output = format(&results, DisplayMode::Total, CountMode::Both);
+        let output = format(
+            &results,
+            DisplayMode::Total,
+            CountMode::Both,
+            &FormatOptions::default(),
+        );
         // Should show only total as single object
         assert_eq!(output, r#"{"words":300,"characters":1500}"#);
     }
@@ -274,9 +491,15 @@ mod tests {
             Count {
                 words: 42,
                 characters: 200,
+                ..Count::default()
             },
         )];
-        let output = format(&results, DisplayMode::Auto, CountMode::Words);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Words,
+            &FormatOptions::default(),
+        );
         assert_eq!(output, r#"{"words":42}"#);
         assert!(!output.contains("characters"));
     }
@@ -288,10 +511,263 @@ mod tests {
             Count {
                 words: 42,
                 characters: 200,
+                ..Count::default()
             },
         )];
-        let output = format(&results, DisplayMode::Auto, CountMode::Characters);
+        let output = format(
+            &results,
+            DisplayMode::Auto,
+            CountMode::Characters,
+            &FormatOptions::default(),
+        );
         assert_eq!(output, r#"{"characters":200}"#);
         assert!(!output.contains("words"));
     }
+
+    #[test]
+    fn test_format_with_cost() {
+        let count = Count {
+            words: 1000,
+            characters: 6000,
+            ..Count::default()
+        };
+        let options = FormatOptions {
+            rate: Some(Rate {
+                amount: 0.12,
+                unit: RateUnit::Word,
+            }),
+            ..FormatOptions::default()
+        };
+
+        let single = [("test.typ".to_string(), count)];
+        let output = format(&single, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(output, r#"{"words":1000,"cost":120.00}"#);
+
+        let results = [("a.typ".to_string(), count), ("b.typ".to_string(), count)];
+        let output = format(&results, DisplayMode::Total, CountMode::Words, &options);
+        assert_eq!(output, r#"{"words":2000,"cost":240.00}"#);
+    }
+
+    #[test]
+    fn test_format_single_with_metric() {
+        let mut count = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::AbstractWords, 20);
+        let output = format_single(&count, CountMode::Both, "");
+        assert_eq!(
+            output,
+            r#"{"words":100,"characters":500,"abstract_words":20}"#
+        );
+    }
+
+    #[test]
+    fn test_format_entry_with_metric() {
+        let mut count = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        count.metrics.set(Metric::AbstractWords, 20);
+        let entry = format_entry("test.typ", &count, CountMode::Words, "", "");
+        assert_eq!(
+            entry,
+            r#"  {"file":"test.typ","words":100,"abstract_words":20}"#
+        );
+    }
+
+    #[test]
+    fn test_format_grouped_by_dir() {
+        let results = vec![
+            (
+                "chapters/intro.typ".to_string(),
+                Count {
+                    words: 100,
+                    characters: 500,
+                    ..Count::default()
+                },
+            ),
+            (
+                "chapters/method.typ".to_string(),
+                Count {
+                    words: 200,
+                    characters: 1000,
+                    ..Count::default()
+                },
+            ),
+            (
+                "appendix/a.typ".to_string(),
+                Count {
+                    words: 50,
+                    characters: 250,
+                    ..Count::default()
+                },
+            ),
+        ];
+        let options = FormatOptions {
+            group_by_dir: true,
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            concat!(
+                "[\n",
+                "  {\"directory\":\"chapters\",\"words\":300,\"files\":[\n",
+                "    {\"file\":\"chapters/intro.typ\",\"words\":100},\n",
+                "    {\"file\":\"chapters/method.typ\",\"words\":200}\n",
+                "  ]},\n",
+                "  {\"directory\":\"appendix\",\"words\":50,\"files\":[\n",
+                "    {\"file\":\"appendix/a.typ\",\"words\":50}\n",
+                "  ]}\n",
+                "]"
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_grouped_by_file_type() {
+        let count = Count {
+            words: 100,
+            ..Count::default()
+        };
+        let results = vec![
+            ("docs/main.typ".to_string(), count),
+            ("docs/notes.md".to_string(), count),
+        ];
+        let options = FormatOptions {
+            group_by_type: true,
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            concat!(
+                "[\n",
+                "  {\"file_type\":\"typ\",\"words\":100,\"files\":[\n",
+                "    {\"file\":\"docs/main.typ\",\"words\":100}\n",
+                "  ]},\n",
+                "  {\"file_type\":\"md\",\"words\":100,\"files\":[\n",
+                "    {\"file\":\"docs/notes.md\",\"words\":100}\n",
+                "  ]}\n",
+                "]"
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_with_metadata() {
+        let results = vec![(
+            "test.typ".to_string(),
+            Count {
+                words: 100,
+                characters: 500,
+                ..Count::default()
+            },
+        )];
+        let options = FormatOptions {
+            metadata: Some(Metadata {
+                timestamp: Some("2026-01-17T12:00:00Z".to_string()),
+                commit: Some("abc123".to_string()),
+                branch: None,
+                version: "0.1.0".to_string(),
+                typst_version: "0.14.0".to_string(),
+            }),
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            concat!(
+                r#"{"words":100,"timestamp":"2026-01-17T12:00:00Z","commit":"abc123","#,
+                r#""branch":null,"version":"0.1.0","typst_version":"0.14.0"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_schema_covers_fields() {
+        assert!(SCHEMA.contains(&format!("report-v{SCHEMA_VERSION}.json")));
+        for metric in Metric::ALL {
+            assert!(
+                SCHEMA.contains(&format!(r#""{}":"#, metric.key())),
+                "{metric:?}"
+            );
+        }
+        for key in [
+            "file",
+            "directory",
+            "file_type",
+            "files",
+            "timestamp",
+            "typst_version",
+            "cost",
+            "error",
+            "causes",
+            "summary",
+            "violations",
+        ] {
+            assert!(SCHEMA.contains(&format!(r#""{key}":"#)), "{key}");
+        }
+    }
+
+    #[test]
+    fn test_format_with_summary() {
+        let results = vec![("a.typ".to_string(), Count::default()); 2];
+        let options = FormatOptions {
+            summary: Some(Summary {
+                files: 2,
+                words: 18342,
+                violations: 1,
+                errors: 0,
+            }),
+            ..FormatOptions::default()
+        };
+        let output = format(&results, DisplayMode::Total, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            r#"{"words":0,"summary":{"files":2,"words":18342,"violations":1,"errors":0}}"#
+        );
+    }
+
+    #[test]
+    fn test_format_error() {
+        let error = anyhow::anyhow!("file not found").context("Failed to read file: \"a.typ\"");
+        let output = format_error(&error);
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(value["error"]["message"], "Failed to read file: \"a.typ\"");
+        assert_eq!(
+            value["error"]["causes"],
+            serde_json::json!(["file not found"])
+        );
+    }
+
+    #[test]
+    fn test_format_with_documents() {
+        let count = Count {
+            words: 100,
+            ..Count::default()
+        };
+        let results = vec![("a.typ".to_string(), count), ("b.typ".to_string(), count)];
+        let mut options = FormatOptions::default();
+        options.documents.insert(
+            "a.typ".to_string(),
+            DocumentInfo {
+                title: Some("A \"Quoted\" Title".to_string()),
+                authors: vec!["Ada".to_string()],
+            },
+        );
+        let output = format(&results, DisplayMode::Auto, CountMode::Words, &options);
+        assert_eq!(
+            output,
+            concat!(
+                "[\n",
+                "  {\"file\":\"a.typ\",\"words\":100,\"title\":\"A \\\"Quoted\\\" Title\",\"authors\":[\"Ada\"]},\n",
+                "  {\"file\":\"b.typ\",\"words\":100}\n",
+                "]"
+            )
+        );
+    }
 }
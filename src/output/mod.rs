@@ -1,15 +1,24 @@
 //! Output formatting for word and character count results.
 //!
 //! This module provides formatters for displaying count results in various formats
-//! including human-readable tables, JSON, and CSV. It handles different display modes
-//! and counting modes to present the data appropriately.
+//! including human-readable tables, JSON, CSV, and GitLab metrics reports. It handles
+//! different display modes and counting modes to present the data appropriately.
 
 mod csv;
+mod gitlab;
 mod human;
 mod json;
 
-use crate::cli::{CountMode, DisplayMode, OutputFormat};
-use crate::counter::Count;
+pub use json::{SCHEMA, SCHEMA_VERSION, format_error};
+
+use crate::cli::{CountMode, DisplayMode, OutputFormat, PathStyle, RateUnit};
+use crate::counter::{Count, Metric};
+use crate::metadata::{DocumentInfo, Metadata};
+use crate::report::Limits;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
 
 /// Formatter for outputting count results in various formats.
 ///
@@ -24,7 +33,7 @@ use crate::counter::Count;
 /// use typst_count::counter::Count;
 ///
 /// let formatter = OutputFormatter::new(OutputFormat::Human, CountMode::Both);
-/// let results = vec![("document.typ".to_string(), Count { words: 100, characters: 500 })];
+/// let results = vec![("document.typ".to_string(), Count { words: 100, characters: 500, ..Count::default() })];
 /// let output = formatter.format_output(&results, DisplayMode::Auto);
 /// println!("{}", output);
 /// ```
@@ -33,6 +42,122 @@ pub struct OutputFormatter {
     format: OutputFormat,
     /// What to count and display (words/characters/both)
     mode: CountMode,
+    /// Optional columns and presentation settings
+    options: FormatOptions,
+}
+
+/// Optional columns and presentation settings for the formatters.
+///
+/// Not every format supports every option; unsupported options are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    /// Show each file's share of the total word count (human table and CSV)
+    pub percent: bool,
+    /// Price for a cost estimate column (human table, JSON, and CSV)
+    pub rate: Option<Rate>,
+    /// Hide rows with fewer words than this (human table and CSV)
+    pub min_row_words: Option<usize>,
+    /// Show only this many rows with the most words (human table and CSV)
+    pub top: Option<usize>,
+    /// Aggregate files by directory with subtotals (human table and JSON)
+    pub group_by_dir: bool,
+    /// Aggregate files by file type with subtotals (human table and JSON)
+    pub group_by_type: bool,
+    /// Timestamp written as a leading column of every row (CSV)
+    pub timestamp: Option<String>,
+    /// Run metadata added to every object or row (JSON and CSV)
+    pub metadata: Option<Metadata>,
+    /// Title and authors of the documents, by file name (human table, JSON,
+    /// and CSV)
+    pub documents: HashMap<String, DocumentInfo>,
+    /// Words of shared files left out of a file's count because an earlier
+    /// file counts them, by file name (human table)
+    pub shared_words: HashMap<String, usize>,
+    /// Color rows by the status of their limits (human table)
+    pub color: bool,
+    /// Limits of individual rows, such as configured groups, by row name
+    /// (human table)
+    pub row_limits: HashMap<String, Limits>,
+    /// Limits of the total (human table)
+    pub total_limits: Limits,
+    /// How file paths are shown (human table)
+    pub path_style: PathStyle,
+    /// Width available for the table, in columns; tables wider than this
+    /// are shown with only the name and word columns (human table)
+    pub width: Option<usize>,
+    /// Verdict of the run, shown as a final line (human output and JSON)
+    pub summary: Option<Summary>,
+}
+
+/// The verdict of a run at a glance, e.g.
+/// `2 files, 18,342 words, 1 limit violation, 0 errors`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of files counted
+    pub files: usize,
+    /// Total word count of the files
+    pub words: usize,
+    /// Number of limits the counts violate
+    pub violations: usize,
+    /// Number of files that failed to compile and were counted
+    /// approximately; other errors stop the run before a summary is written
+    pub errors: usize,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: usize, singular: &str, plural: &str| {
+            format!(
+                "{} {}",
+                group_digits(n),
+                if n == 1 { singular } else { plural }
+            )
+        };
+        write!(
+            f,
+            "{}, {}, {}, {}",
+            plural(self.files, "file", "files"),
+            plural(self.words, "word", "words"),
+            plural(self.violations, "limit violation", "limit violations"),
+            plural(self.errors, "error", "errors"),
+        )
+    }
+}
+
+/// Writes a number with commas between groups of three digits, e.g. `18,342`.
+fn group_digits(n: usize) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// A price per word or character, for estimating the cost of translating or
+/// editing the counted text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rate {
+    /// Price per unit, in any currency
+    pub amount: f64,
+    /// Whether the price is per word, character, or billable unit
+    pub unit: RateUnit,
+}
+
+impl Rate {
+    /// Returns the cost of a count at this rate.
+    #[must_use]
+    pub fn cost(&self, count: &Count) -> f64 {
+        let units = match self.unit {
+            RateUnit::Word => count.words,
+            RateUnit::Character => count.characters,
+            RateUnit::Unit => count.metrics.get(Metric::BillableUnits).unwrap_or(0),
+        };
+        units as f64 * self.amount
+    }
 }
 
 impl OutputFormatter {
@@ -52,8 +177,29 @@ impl OutputFormatter {
     /// let formatter = OutputFormatter::new(OutputFormat::Human, CountMode::Both);
     /// ```
     #[must_use]
-    pub const fn new(format: OutputFormat, mode: CountMode) -> Self {
-        Self { format, mode }
+    pub fn new(format: OutputFormat, mode: CountMode) -> Self {
+        Self {
+            format,
+            mode,
+            options: FormatOptions::default(),
+        }
+    }
+
+    /// Sets optional columns and presentation settings.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use typst_count::output::{FormatOptions, OutputFormatter};
+    /// use typst_count::cli::{OutputFormat, CountMode};
+    ///
+    /// let options = FormatOptions { percent: true, ..FormatOptions::default() };
+    /// let formatter = OutputFormatter::new(OutputFormat::Csv, CountMode::Words).with_options(options);
+    /// ```
+    #[must_use]
+    pub fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
     }
 
     /// Formats count results according to the configured format and mode.
@@ -79,25 +225,26 @@ impl OutputFormatter {
     ///
     /// let formatter = OutputFormatter::new(OutputFormat::Json, CountMode::Words);
     /// let results = vec![
-    ///     ("doc1.typ".to_string(), Count { words: 100, characters: 500 }),
-    ///     ("doc2.typ".to_string(), Count { words: 200, characters: 1000 }),
+    ///     ("doc1.typ".to_string(), Count { words: 100, characters: 500, ..Count::default() }),
+    ///     ("doc2.typ".to_string(), Count { words: 200, characters: 1000, ..Count::default() }),
     /// ];
     /// let output = formatter.format_output(&results, DisplayMode::Detailed);
     /// ```
     #[must_use]
     pub fn format_output(&self, results: &[(String, Count)], display: DisplayMode) -> String {
         match self.format {
-            OutputFormat::Human => human::format(results, display, self.mode),
-            OutputFormat::Json => json::format(results, display, self.mode),
-            OutputFormat::Csv => csv::format(results, display, self.mode),
+            OutputFormat::Human => human::format(results, display, self.mode, &self.options),
+            OutputFormat::Json => json::format(results, display, self.mode, &self.options),
+            OutputFormat::Csv => csv::format(results, display, self.mode, &self.options),
+            OutputFormat::GitlabMetrics => gitlab::format(results, display, self.mode),
         }
     }
 }
 
 /// Calculates the total word and character count across multiple files.
 ///
-/// Sums up all word counts and character counts from the provided results
-/// to produce aggregate totals.
+/// Sums up all word counts, character counts, and additional metrics from
+/// the provided results to produce aggregate totals.
 ///
 /// # Arguments
 ///
@@ -114,8 +261,8 @@ impl OutputFormatter {
 /// use typst_count::counter::Count;
 ///
 /// let results = vec![
-///     ("doc1.typ".to_string(), Count { words: 100, characters: 500 }),
-///     ("doc2.typ".to_string(), Count { words: 200, characters: 1000 }),
+///     ("doc1.typ".to_string(), Count { words: 100, characters: 500, ..Count::default() }),
+///     ("doc2.typ".to_string(), Count { words: 200, characters: 1000, ..Count::default() }),
 /// ];
 /// let total = calculate_total(&results);
 /// assert_eq!(total.words, 300);
@@ -123,16 +270,196 @@ impl OutputFormatter {
 /// ```
 #[must_use]
 pub fn calculate_total(results: &[(String, Count)]) -> Count {
-    Count {
-        words: results.iter().map(|(_, c)| c.words).sum(),
-        characters: results.iter().map(|(_, c)| c.characters).sum(),
+    results
+        .iter()
+        .fold(Count::default(), |total, (_, count)| total + *count)
+}
+
+/// Returns `part` as a percentage of `total`, or 0 if `total` is zero.
+fn share_of(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+/// Returns the rows to show after applying the row filters.
+///
+/// Rows below `min_row_words` are dropped first. With `top` set, the remaining
+/// rows are ordered by word count (largest first) and truncated; otherwise
+/// they keep their input order. Totals are always computed from all results,
+/// not just the rows returned here.
+fn visible_rows<'a>(
+    results: &'a [(String, Count)],
+    options: &FormatOptions,
+) -> Vec<&'a (String, Count)> {
+    let mut rows: Vec<_> = results
+        .iter()
+        .filter(|(_, count)| options.min_row_words.is_none_or(|min| count.words >= min))
+        .collect();
+
+    if let Some(top) = options.top {
+        rows.sort_by_key(|(_, count)| Reverse(count.words));
+        rows.truncate(top);
+    }
+
+    rows
+}
+
+/// Returns the label of a result: the document's title if known, otherwise
+/// its path in the `path_style`, flagged if shared content was left out of
+/// its count.
+fn row_label(name: &str, options: &FormatOptions) -> String {
+    let label = match options
+        .documents
+        .get(name)
+        .and_then(|info| info.title.as_deref())
+    {
+        Some(title) => title.to_string(),
+        None => display_path(name, options.path_style),
+    };
+    match options.shared_words.get(name) {
+        Some(words) => format!("{label} (-{words} shared)"),
+        None => label,
+    }
+}
+
+/// Returns how a result's file path is shown in the given style.
+///
+/// Names that aren't paths, such as those of configured groups, are left
+/// as they are. Truncation to the table's width happens when the table is
+/// laid out.
+fn display_path(name: &str, style: PathStyle) -> String {
+    let path = Path::new(name);
+    match style {
+        PathStyle::Full => name.to_string(),
+        PathStyle::Relative | PathStyle::Truncate => std::env::current_dir()
+            .ok()
+            .and_then(|dir| path.strip_prefix(dir).ok().map(|p| p.display().to_string()))
+            .unwrap_or_else(|| name.to_string()),
+        PathStyle::Filename => path.file_name().map_or_else(
+            || name.to_string(),
+            |file| file.to_string_lossy().to_string(),
+        ),
+    }
+}
+
+/// Returns the directory of a result's file, or `.` for files without one.
+fn directory_of(name: &str) -> String {
+    match Path::new(name).parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.display().to_string(),
+        _ => ".".to_string(),
+    }
+}
+
+/// Returns the file type of a result's file: its lowercase extension, with
+/// `markdown` as `md`, or `other` for files without one.
+fn file_type_of(name: &str) -> String {
+    match Path::new(name).extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("markdown") => "md".to_string(),
+        Some(ext) => ext.to_string_lossy().to_lowercase(),
+        None => "other".to_string(),
     }
 }
 
+/// How results are aggregated into subtotals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grouping {
+    /// One subtotal per directory
+    Directory,
+    /// One subtotal per file type, e.g. for projects mixing Typst and
+    /// Markdown files
+    FileType,
+}
+
+impl Grouping {
+    /// Returns the grouping selected in `options`, if any.
+    const fn of(options: &FormatOptions) -> Option<Self> {
+        if options.group_by_dir {
+            Some(Self::Directory)
+        } else if options.group_by_type {
+            Some(Self::FileType)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the group a result's file belongs to.
+    fn key(self, name: &str) -> String {
+        match self {
+            Self::Directory => directory_of(name),
+            Self::FileType => file_type_of(name),
+        }
+    }
+
+    /// Returns the JSON field naming a group.
+    const fn field(self) -> &'static str {
+        match self {
+            Self::Directory => "directory",
+            Self::FileType => "file_type",
+        }
+    }
+
+    /// Returns the label of a group's subtotal row in the table.
+    fn subtotal_label(self, key: &str) -> String {
+        match self {
+            Self::Directory => format!("{key}/ subtotal"),
+            Self::FileType => format!("*.{key} subtotal"),
+        }
+    }
+
+    /// Aggregates results per group.
+    ///
+    /// Groups are returned in the order they first appear in `results`,
+    /// each with the sum of its files' counts.
+    fn groups(self, results: &[(String, Count)]) -> Vec<(String, Count)> {
+        let mut groups: Vec<(String, Count)> = Vec::new();
+        for (name, count) in results {
+            let key = self.key(name);
+            match groups.iter_mut().find(|(existing, _)| *existing == key) {
+                Some((_, subtotal)) => *subtotal += *count,
+                None => groups.push((key, *count)),
+            }
+        }
+        groups
+    }
+}
+
+/// Returns the additional metrics present in any of the results.
+///
+/// Used to decide which extra columns or fields to output.
+fn reported_metrics(results: &[(String, Count)]) -> Vec<Metric> {
+    Metric::ALL
+        .into_iter()
+        .filter(|&metric| {
+            results
+                .iter()
+                .any(|(_, count)| count.metrics.get(metric).is_some())
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_summary_display() {
+        let summary = Summary {
+            files: 2,
+            words: 18342,
+            violations: 1,
+            errors: 0,
+        };
+        assert_eq!(
+            summary.to_string(),
+            "2 files, 18,342 words, 1 limit violation, 0 errors"
+        );
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1_234_567), "1,234,567");
+    }
+
     #[test]
     fn test_calculate_total_single_file() {
         let results = vec![(
@@ -140,6 +467,7 @@ mod tests {
             Count {
                 words: 100,
                 characters: 500,
+                ..Count::default()
             },
         )];
 
@@ -156,6 +484,7 @@ mod tests {
                 Count {
                     words: 100,
                     characters: 500,
+                    ..Count::default()
                 },
             ),
             (
@@ -163,6 +492,7 @@ mod tests {
                 Count {
                     words: 200,
                     characters: 1000,
+                    ..Count::default()
                 },
             ),
             (
@@ -170,6 +500,7 @@ mod tests {
                 Count {
                     words: 50,
                     characters: 250,
+                    ..Count::default()
                 },
             ),
         ];
@@ -196,6 +527,7 @@ mod tests {
                 Count {
                     words: 0,
                     characters: 0,
+                    ..Count::default()
                 },
             ),
             (
@@ -203,6 +535,7 @@ mod tests {
                 Count {
                     words: 0,
                     characters: 0,
+                    ..Count::default()
                 },
             ),
         ];
@@ -227,6 +560,7 @@ mod tests {
             Count {
                 words: 42,
                 characters: 200,
+                ..Count::default()
             },
         )];
 
@@ -234,4 +568,137 @@ mod tests {
         assert!(output.contains("42"));
         assert!(output.contains("200"));
     }
+
+    #[test]
+    fn test_calculate_total_sums_metrics() {
+        let mut first = Count {
+            words: 100,
+            characters: 500,
+            ..Count::default()
+        };
+        first.metrics.set(Metric::AbstractWords, 20);
+        let second = Count {
+            words: 50,
+            characters: 250,
+            ..Count::default()
+        };
+        let results = vec![
+            ("file1.typ".to_string(), first),
+            ("file2.typ".to_string(), second),
+        ];
+
+        let total = calculate_total(&results);
+        assert_eq!(total.metrics.get(Metric::AbstractWords), Some(20));
+        assert_eq!(reported_metrics(&results), vec![Metric::AbstractWords]);
+    }
+
+    #[test]
+    fn test_visible_rows() {
+        let results: Vec<_> = [("a.typ", 10), ("b.typ", 300), ("c.typ", 50), ("d.typ", 200)]
+            .into_iter()
+            .map(|(name, words)| {
+                (
+                    name.to_string(),
+                    Count {
+                        words,
+                        ..Count::default()
+                    },
+                )
+            })
+            .collect();
+        let names = |options: &FormatOptions| -> Vec<String> {
+            visible_rows(&results, options)
+                .into_iter()
+                .map(|(name, _)| name.clone())
+                .collect()
+        };
+
+        assert_eq!(names(&FormatOptions::default()).len(), 4);
+
+        let min = FormatOptions {
+            min_row_words: Some(50),
+            ..FormatOptions::default()
+        };
+        assert_eq!(names(&min), ["b.typ", "c.typ", "d.typ"]);
+
+        let top = FormatOptions {
+            top: Some(2),
+            ..FormatOptions::default()
+        };
+        assert_eq!(names(&top), ["b.typ", "d.typ"]);
+
+        let both = FormatOptions {
+            min_row_words: Some(250),
+            top: Some(2),
+            ..FormatOptions::default()
+        };
+        assert_eq!(names(&both), ["b.typ"]);
+    }
+
+    #[test]
+    fn test_group_by_dir() {
+        let results: Vec<_> = [
+            ("chapters/intro.typ", 100),
+            ("main.typ", 10),
+            ("chapters/method.typ", 200),
+            ("appendix/a.typ", 50),
+        ]
+        .into_iter()
+        .map(|(name, words)| {
+            (
+                name.to_string(),
+                Count {
+                    words,
+                    ..Count::default()
+                },
+            )
+        })
+        .collect();
+
+        let groups: Vec<_> = Grouping::Directory
+            .groups(&results)
+            .into_iter()
+            .map(|(dir, count)| (dir, count.words))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("chapters".to_string(), 300),
+                (".".to_string(), 10),
+                ("appendix".to_string(), 50)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_by_file_type() {
+        let count = Count {
+            words: 10,
+            ..Count::default()
+        };
+        let results: Vec<_> = [
+            "docs/a.typ",
+            "notes/Plan.MD",
+            "b.typ",
+            "c.markdown",
+            "LICENSE",
+        ]
+        .into_iter()
+        .map(|name| (name.to_string(), count))
+        .collect();
+
+        let groups: Vec<_> = Grouping::FileType
+            .groups(&results)
+            .into_iter()
+            .map(|(file_type, count)| (file_type, count.words))
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("typ".to_string(), 20),
+                ("md".to_string(), 20),
+                ("other".to_string(), 10)
+            ]
+        );
+    }
 }
@@ -0,0 +1,81 @@
+//! Reading the text of exported PDFs.
+//!
+//! Sometimes only the artifact is at hand, e.g. a submitted PDF whose word
+//! count should match the one declared for it. Its text is extracted with
+//! the `pdftotext` command from Poppler and counted like the text of a
+//! document. Unlike counting the sources, everything on the pages is
+//! counted, including headers, footers, and page numbers.
+
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+use std::process::Command;
+
+/// Extracts the text of a PDF.
+///
+/// Words hyphenated at the end of a line are joined again, so they count
+/// once as in the source.
+///
+/// # Errors
+///
+/// Returns an error if `pdftotext` is not installed or cannot read the file.
+pub fn extract_text(path: &Path) -> Result<String> {
+    let output = Command::new("pdftotext")
+        .args(["-enc", "UTF-8"])
+        .arg(path)
+        .arg("-")
+        .output()
+        .context("Failed to run pdftotext; install Poppler to count PDFs")?;
+    if !output.status.success() {
+        bail!(
+            "pdftotext failed for {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let text = String::from_utf8(output.stdout).context("pdftotext returned invalid UTF-8")?;
+    Ok(join_hyphenated(&text))
+}
+
+/// Joins words split by a hyphen at the end of a line.
+///
+/// Only hyphens following a letter and followed by a lowercase letter on the
+/// next line are removed, so dashes and compounds split before a capitalized
+/// word are kept.
+fn join_hyphenated(text: &str) -> String {
+    let mut joined = String::with_capacity(text.len());
+    for line in text.lines() {
+        let mut end = joined.chars().rev();
+        let hyphenated = end.next() == Some('-') && end.next().is_some_and(char::is_alphabetic);
+        let continues = line
+            .trim_start()
+            .chars()
+            .next()
+            .is_some_and(char::is_lowercase);
+        if hyphenated && continues {
+            joined.pop();
+            joined.push_str(line.trim_start());
+        } else {
+            if !joined.is_empty() {
+                joined.push('\n');
+            }
+            joined.push_str(line);
+        }
+    }
+    joined.push('\n');
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_hyphenated() {
+        let text =
+            "Counts of hyphen-\nated words and\nGerman-\nAmerican ties --\nnot dashes.\n\x0c";
+        assert_eq!(
+            join_hyphenated(text),
+            "Counts of hyphenated words and\nGerman-\nAmerican ties --\nnot dashes.\n\x0c\n"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Reading plain text and Markdown companion files.
+//!
+//! Writing projects often keep notes, outlines, or a README next to the
+//! Typst sources. Their text is counted with the same word rules as
+//! documents, so a whole project can be tracked with one tool. Markdown
+//! syntax is removed first, so only the text a reader would see is counted.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Reads the text of a plain text or Markdown file.
+///
+/// Files with the extension `md` or `markdown` are read as Markdown, all
+/// others as plain text.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read as UTF-8 text.
+pub fn read_text(path: &Path) -> Result<String> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read file: {}", path.display()))?;
+    Ok(file_text(path, text))
+}
+
+/// Returns the text of a plain text or Markdown file read from elsewhere,
+/// e.g. from a git revision.
+///
+/// `path` is the file's path, whose extension decides whether the contents
+/// are read as Markdown, as in [`read_text`].
+#[must_use]
+pub fn file_text(path: &Path, contents: String) -> String {
+    let markdown = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"));
+    if markdown {
+        strip_markdown(&contents)
+    } else {
+        contents
+    }
+}
+
+/// Removes Markdown syntax from text.
+///
+/// Heading, quote, and list markers, code fences, emphasis, inline code
+/// marks, and the targets of links and images are removed. Link and image
+/// texts are kept.
+fn strip_markdown(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    for line in text.lines() {
+        let mut line = line.trim_start();
+        if line.starts_with("```") || line.starts_with("~~~") {
+            continue;
+        }
+        loop {
+            let rest = line.trim_start_matches('#');
+            let rest = if rest.len() < line.len() && (rest.is_empty() || rest.starts_with(' ')) {
+                rest
+            } else {
+                ["> ", "- ", "* ", "+ "]
+                    .iter()
+                    .find_map(|marker| line.strip_prefix(marker))
+                    .or_else(|| strip_ordered_marker(line))
+                    .unwrap_or(line)
+            };
+            if rest.len() == line.len() {
+                break;
+            }
+            line = rest.trim_start();
+        }
+        strip_inline(line, &mut stripped);
+        stripped.push('\n');
+    }
+    stripped
+}
+
+/// Strips the marker of an ordered list item such as `1. ` or `2) `.
+fn strip_ordered_marker(line: &str) -> Option<&str> {
+    let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+    if rest.len() == line.len() {
+        return None;
+    }
+    rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") "))
+}
+
+/// Appends a line without its inline Markdown syntax.
+fn strip_inline(line: &str, stripped: &mut String) {
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        // `[text](target)` and `![alt](target)` keep only their text.
+        let link = rest.strip_prefix('!').unwrap_or(rest);
+        if let Some(inner) = link.strip_prefix('[')
+            && let Some((text, after)) = inner.split_once("](")
+            && let Some((_, after)) = after.split_once(')')
+        {
+            strip_inline(text, stripped);
+            rest = after;
+            continue;
+        }
+        if !matches!(c, '*' | '`') {
+            stripped.push(c);
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_markdown() {
+        let markdown = "\
+# Notes
+
+- Read *the* [related work](https://example.com/papers)
+  1. Check `count` against ![the chart](chart.png)
+> ## Quote
+
+```
+code
+```
+Keep C# and 5 - 3 as they are.
+";
+        assert_eq!(
+            strip_markdown(markdown),
+            "Notes\n\nRead the related work\nCheck count against the chart\nQuote\n\ncode\n\
+             Keep C# and 5 - 3 as they are.\n"
+        );
+    }
+
+    #[test]
+    fn test_read_text() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("notes.md"), "## *Idea*").unwrap();
+        fs::write(dir.path().join("notes.txt"), "## *Idea*").unwrap();
+
+        assert_eq!(read_text(&dir.path().join("notes.md")).unwrap(), "Idea\n");
+        assert_eq!(
+            read_text(&dir.path().join("notes.txt")).unwrap(),
+            "## *Idea*"
+        );
+        assert!(read_text(&dir.path().join("missing.md")).is_err());
+    }
+}
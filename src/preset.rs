@@ -0,0 +1,114 @@
+//! Counting profiles for common journals, conferences, and degree programmes.
+//!
+//! Venues differ in what they consider part of the word count: some exclude
+//! figure captions, most exclude the reference list, and a few exclude notes.
+//! This module keeps those rules as data so users can select a venue by name
+//! instead of reverse-engineering its submission guidelines.
+
+use clap::ValueEnum;
+
+/// A named bundle of counting rules and default limits.
+///
+/// Selected with `--preset`. Explicit command-line limits always take
+/// precedence over the defaults provided by a preset.
+#[derive(Clone, Copy, ValueEnum, PartialEq, Eq, Debug)]
+pub enum Preset {
+    /// IEEE conference papers.
+    ///
+    /// References are not counted; venues limit pages rather than words.
+    IeeeConference,
+    /// ACM journals and conference proceedings.
+    ///
+    /// References are not counted; venues limit pages rather than words.
+    Acm,
+    /// Nature letters.
+    ///
+    /// Roughly 1,500 words of main text, excluding references, figure
+    /// legends, and notes.
+    NatureLetter,
+    /// UK doctoral thesis.
+    ///
+    /// A 100,000 word ceiling (common across UK universities), excluding
+    /// the bibliography.
+    UkPhdThesis,
+}
+
+/// The counting rules and limits associated with a [`Preset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    /// Whether figure and table captions are excluded from the count
+    pub exclude_captions: bool,
+    /// Whether footnotes are excluded from the count
+    pub exclude_footnotes: bool,
+    /// Whether the bibliography is excluded from the count
+    pub exclude_bibliography: bool,
+    /// Default maximum word count
+    pub max_words: Option<usize>,
+    /// Default minimum word count
+    pub min_words: Option<usize>,
+}
+
+impl Preset {
+    /// Returns the counting rules and default limits for this preset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use typst_count::preset::Preset;
+    ///
+    /// let profile = Preset::NatureLetter.profile();
+    /// assert!(profile.exclude_captions);
+    /// assert_eq!(profile.max_words, Some(1500));
+    /// ```
+    #[must_use]
+    pub const fn profile(self) -> Profile {
+        match self {
+            Self::IeeeConference | Self::Acm => Profile {
+                exclude_captions: false,
+                exclude_footnotes: false,
+                exclude_bibliography: true,
+                max_words: None,
+                min_words: None,
+            },
+            Self::NatureLetter => Profile {
+                exclude_captions: true,
+                exclude_footnotes: true,
+                exclude_bibliography: true,
+                max_words: Some(1500),
+                min_words: None,
+            },
+            Self::UkPhdThesis => Profile {
+                exclude_captions: false,
+                exclude_footnotes: false,
+                exclude_bibliography: true,
+                max_words: Some(100_000),
+                min_words: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_preset_excludes_bibliography() {
+        for preset in Preset::value_variants() {
+            assert!(preset.profile().exclude_bibliography, "{preset:?}");
+        }
+    }
+
+    #[test]
+    fn test_preset_names() {
+        let names: Vec<_> = Preset::value_variants()
+            .iter()
+            .filter_map(|p| p.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            ["ieee-conference", "acm", "nature-letter", "uk-phd-thesis"]
+        );
+    }
+}
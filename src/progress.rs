@@ -0,0 +1,217 @@
+//! Reading progress logs.
+//!
+//! Running typst-count with `--format csv --append` adds a timestamped row
+//! per file to a log on every run. The log records how the count developed
+//! from day to day, e.g. to check a daily writing goal against the count at
+//! the end of the previous day.
+//!
+//! The log is the only history typst-count keeps. It holds the names of the
+//! files and their numbers, never text extracted from them. For manuscripts
+//! whose file names are confidential as well, the configuration file can
+//! turn on private logs:
+//!
+//! ```toml
+//! [progress]
+//! private = true
+//! ```
+//!
+//! Rows of private logs name each file by a hash of its name, and hold only
+//! its total words and characters, without further metrics or run metadata.
+//! The names are hashed with HMAC-SHA256 keyed with a secret passphrase, so
+//! they can't be recovered by hashing likely file names. The passphrase is
+//! read from `TYPST_COUNT_PROGRESS_SECRET`, or from `secret` under
+//! `[progress]`.
+
+use crate::counter::Count;
+use anyhow::{Context, Result, bail};
+use chrono::NaiveDate;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// Environment variable holding the secret file names in private logs are
+/// hashed with.
+pub const SECRET_VAR: &str = "TYPST_COUNT_PROGRESS_SECRET";
+
+/// A run recorded in a progress log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Time of the run as `YYYY-MM-DD HH:MM:SS`
+    pub timestamp: String,
+    /// Total word count of all files counted in the run
+    pub words: usize,
+}
+
+impl LogEntry {
+    /// Returns the day of the run, or `None` if the timestamp doesn't start
+    /// with a date.
+    #[must_use]
+    pub fn date(&self) -> Option<NaiveDate> {
+        let date = self.timestamp.get(..10)?;
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+    }
+}
+
+/// Hex digits of the hashed file names in private logs.
+const HASH_LEN: usize = 16;
+
+/// Returns the secret file names in private logs are hashed with.
+///
+/// The [`SECRET_VAR`] environment variable takes precedence over the secret
+/// of the configuration file, `configured`.
+///
+/// # Errors
+///
+/// Returns an error if neither is set.
+pub fn secret(configured: Option<&str>) -> Result<Vec<u8>> {
+    match (env::var_os(SECRET_VAR), configured) {
+        (Some(secret), _) => Ok(secret.into_encoded_bytes()),
+        (None, Some(secret)) => Ok(secret.as_bytes().to_vec()),
+        (None, None) => bail!(
+            "Private progress logs need a secret: set {SECRET_VAR} or `secret` under `[progress]`"
+        ),
+    }
+}
+
+/// Returns the rows of a private log: each file named by a hash of its
+/// name keyed with `secret`, with only its words and characters.
+///
+/// The same name always hashes the same with the same secret, so runs can
+/// still be compared file by file.
+#[must_use]
+pub fn private_rows(results: &[(String, Count)], secret: &[u8]) -> Vec<(String, Count)> {
+    results
+        .iter()
+        .map(|(name, count)| {
+            let mut mac =
+                Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+            mac.update(name.as_bytes());
+            let mut hash = format!("{:x}", mac.finalize().into_bytes());
+            hash.truncate(HASH_LEN);
+            let count = Count {
+                words: count.words,
+                characters: count.characters,
+                ..Count::default()
+            };
+            (hash, count)
+        })
+        .collect()
+}
+
+/// Reads a progress log from a file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a progress log, see
+/// [`parse_log`].
+pub fn read_log(path: &Path) -> Result<Vec<LogEntry>> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read progress log: {}", path.display()))?;
+    parse_log(&text).with_context(|| format!("Invalid progress log: {}", path.display()))
+}
+
+/// Parses a progress log into its runs, oldest first.
+///
+/// Rows sharing a timestamp belong to the same run, and their word counts
+/// are added up.
+///
+/// # Errors
+///
+/// Returns an error if the log has no `timestamp` or `words` column, or a
+/// word count is not a number.
+pub fn parse_log(text: &str) -> Result<Vec<LogEntry>> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<&str> = lines.next().unwrap_or_default().split(',').collect();
+    let column = |name: &str| header.iter().position(|column| *column == name);
+    let (Some(timestamp), Some(words)) = (column("timestamp"), column("words")) else {
+        bail!("Missing `timestamp` or `words` column; write the log with --append");
+    };
+
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').collect();
+        let (Some(time), Some(count)) = (fields.get(timestamp), fields.get(words)) else {
+            bail!("Incomplete row: {line}");
+        };
+        let count: usize = count
+            .parse()
+            .with_context(|| format!("Invalid word count: {count}"))?;
+        match entries.last_mut() {
+            Some(entry) if entry.timestamp == *time => entry.words += count,
+            _ => entries.push(LogEntry {
+                timestamp: (*time).to_string(),
+                words: count,
+            }),
+        }
+    }
+    Ok(entries)
+}
+
+/// Returns the last run recorded before `date`.
+#[must_use]
+pub fn last_before(entries: &[LogEntry], date: NaiveDate) -> Option<&LogEntry> {
+    entries
+        .iter()
+        .rev()
+        .find(|entry| entry.date().is_some_and(|day| day < date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let log = "\
+timestamp,file,words,characters
+2026-03-01 21:00:00,ch1.typ,1000,6000
+2026-03-01 21:00:00,ch2.typ,500,3000
+2026-03-02 21:00:00,ch1.typ,1200,7000
+2026-03-02 21:00:00,ch2.typ,700,4000
+2026-03-03 09:00:00,ch1.typ,1300,7500
+2026-03-03 09:00:00,ch2.typ,700,4000
+";
+        let entries = parse_log(log).unwrap();
+        let words: Vec<usize> = entries.iter().map(|entry| entry.words).collect();
+        assert_eq!(words, [1500, 1900, 2000]);
+
+        let today = NaiveDate::from_ymd_opt(2026, 3, 3).unwrap();
+        assert_eq!(
+            last_before(&entries, today).unwrap().timestamp,
+            "2026-03-02 21:00:00"
+        );
+        assert!(last_before(&entries, NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()).is_none());
+    }
+
+    #[test]
+    fn test_private_rows() {
+        let mut count = Count {
+            words: 100,
+            characters: 600,
+            ..Count::default()
+        };
+        count.metrics.set(crate::counter::Metric::Figures, 2);
+        let results = [("thesis.typ".to_string(), count)];
+        let rows = private_rows(&results, b"passphrase");
+
+        assert_eq!(rows[0].0, "b8bf13d7b44f7532");
+        assert_eq!(private_rows(&results, b"other")[0].0, "eb91dfda7cfd6f0f");
+        assert_eq!(rows[0].1.words, 100);
+        assert_eq!(rows[0].1.characters, 600);
+        assert!(
+            rows[0]
+                .1
+                .metrics
+                .get(crate::counter::Metric::Figures)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_parse_log_requires_columns() {
+        assert!(parse_log("file,words\nch1.typ,100\n").is_err());
+        assert!(parse_log("timestamp,file,words\n2026-03-01 21:00:00,ch1.typ,many\n").is_err());
+    }
+}
@@ -0,0 +1,503 @@
+//! Reading previously generated JSON reports.
+//!
+//! Reports produced with `--format json` can be fed back into typst-count,
+//! e.g. to combine the results of parallel CI shards or of several
+//! repositories into one aggregated report, or to summarize the change
+//! between two reports in a pull request comment. Pinned reports record the
+//! count of every file, so counts can be verified after a compiler upgrade.
+
+use crate::counter::{Count, Metric};
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Marker identifying pull request comments written by typst-count.
+///
+/// Bots can search for it to update their previous comment instead of
+/// posting a new one on every push.
+pub const COMMENT_MARKER: &str = "<!-- typst-count -->";
+
+/// Word and character limits whose status is shown in a pull request comment
+/// or by the color of a table row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum allowed word count
+    pub max_words: Option<usize>,
+    /// Minimum required word count
+    pub min_words: Option<usize>,
+    /// Maximum allowed character count
+    pub max_characters: Option<usize>,
+    /// Minimum required character count
+    pub min_characters: Option<usize>,
+}
+
+/// How a count compares to its limits, see [`Limits::status`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitStatus {
+    /// The count is well within all limits.
+    Within,
+    /// The count is within its limits but has used up most of a maximum.
+    Near,
+    /// The count violates a limit.
+    Violated,
+}
+
+impl Limits {
+    /// Share of a maximum from which a count is considered near it.
+    pub const NEAR_SHARE: f64 = 0.9;
+
+    /// Returns the status of a count with respect to these limits, or `None`
+    /// if there are no limits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use typst_count::counter::Count;
+    /// use typst_count::report::{LimitStatus, Limits};
+    ///
+    /// let limits = Limits { max_words: Some(100), ..Limits::default() };
+    /// let count = Count { words: 95, ..Count::default() };
+    /// assert_eq!(limits.status(&count), Some(LimitStatus::Near));
+    /// ```
+    #[must_use]
+    pub fn status(&self, count: &Count) -> Option<LimitStatus> {
+        let bounds = [
+            (count.words, self.min_words, self.max_words),
+            (count.characters, self.min_characters, self.max_characters),
+        ];
+        if bounds
+            .iter()
+            .all(|(_, min, max)| min.is_none() && max.is_none())
+        {
+            return None;
+        }
+
+        let mut status = LimitStatus::Within;
+        for (value, min, max) in bounds {
+            if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+                return Some(LimitStatus::Violated);
+            }
+            if max.is_some_and(|max| value as f64 >= max as f64 * Self::NEAR_SHARE) {
+                status = LimitStatus::Near;
+            }
+        }
+        Some(status)
+    }
+}
+
+/// Reads a JSON report and returns its total count.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a typst-count
+/// JSON report.
+pub fn read_report(path: &Path) -> Result<Count> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read report: {}", path.display()))?;
+    parse_report(&text).with_context(|| format!("Invalid report: {}", path.display()))
+}
+
+/// Parses a JSON report and returns its total count.
+///
+/// Accepts every structure described by the output schema: a single object,
+/// an array of file entries, or an array of directory entries. Metrics are
+/// summed like the counts; unknown fields such as run metadata are ignored.
+///
+/// # Errors
+///
+/// Returns an error if the text is not valid JSON or does not have the
+/// structure of a report.
+///
+/// # Examples
+///
+/// ```
+/// use typst_count::report::parse_report;
+///
+/// let count = parse_report(r#"[{"file":"a.typ","words":10},{"file":"b.typ","words":5}]"#)?;
+/// assert_eq!(count.words, 15);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn parse_report(text: &str) -> Result<Count> {
+    match serde_json::from_str(text)? {
+        Value::Object(object) => parse_counts(&object),
+        Value::Array(entries) => entries.iter().try_fold(Count::default(), |total, entry| {
+            let Value::Object(object) = entry else {
+                bail!("Report entries must be objects");
+            };
+            Ok(total + parse_counts(object)?)
+        }),
+        _ => bail!("Report must be an object or an array"),
+    }
+}
+
+/// Reads the counts and metrics of a single report object.
+pub(crate) fn parse_counts(object: &Map<String, Value>) -> Result<Count> {
+    let field = |key: &str| -> Result<Option<usize>> {
+        object
+            .get(key)
+            .map(|value| {
+                value
+                    .as_u64()
+                    .and_then(|n| usize::try_from(n).ok())
+                    .with_context(|| format!("`{key}` must be a non-negative integer"))
+            })
+            .transpose()
+    };
+
+    if !object.contains_key("words") && !object.contains_key("characters") {
+        bail!("Report entries must contain `words` or `characters`");
+    }
+
+    let mut count = Count {
+        words: field("words")?.unwrap_or_default(),
+        characters: field("characters")?.unwrap_or_default(),
+        approximate: object.get("approximate") == Some(&Value::Bool(true)),
+        ..Count::default()
+    };
+    for metric in Metric::ALL {
+        if let Some(value) = field(metric.key())? {
+            count.metrics.set(metric, value);
+        }
+    }
+    Ok(count)
+}
+
+/// Per-file counts recorded with `--pin-report`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PinnedReport {
+    /// Version of the Typst compiler that produced the counts, if recorded
+    pub typst_version: Option<String>,
+    /// Counts of the files; the name is missing if the report was written
+    /// for a single file
+    pub files: Vec<(Option<String>, Count)>,
+}
+
+/// Reads a pinned report.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a JSON report of
+/// single files, see [`parse_pinned`].
+pub fn read_pinned(path: &Path) -> Result<PinnedReport> {
+    let text = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read pinned report: {}", path.display()))?;
+    parse_pinned(&text).with_context(|| format!("Invalid pinned report: {}", path.display()))
+}
+
+/// Parses a pinned report: a JSON report with an object per file, or a
+/// single object for a single file.
+///
+/// # Errors
+///
+/// Returns an error if the text is not valid JSON or does not have the
+/// structure of a report. Reports grouped by directory are not accepted.
+pub fn parse_pinned(text: &str) -> Result<PinnedReport> {
+    let entries = match serde_json::from_str(text)? {
+        Value::Object(object) => vec![object],
+        Value::Array(entries) => entries
+            .into_iter()
+            .map(|entry| match entry {
+                Value::Object(object) if !object.contains_key("files") => Ok(object),
+                _ => bail!("Report entries must be objects of single files"),
+            })
+            .collect::<Result<_>>()?,
+        _ => bail!("Report must be an object or an array"),
+    };
+
+    let typst_version = entries
+        .iter()
+        .find_map(|entry| entry.get("typst_version")?.as_str())
+        .map(ToString::to_string);
+    let files = entries
+        .iter()
+        .map(|entry| {
+            let name = entry
+                .get("file")
+                .and_then(Value::as_str)
+                .map(ToString::to_string);
+            Ok((name, parse_counts(entry)?))
+        })
+        .collect::<Result<_>>()?;
+    Ok(PinnedReport {
+        typst_version,
+        files,
+    })
+}
+
+/// Compares counts with a pinned report and describes the files whose word
+/// or character count shifted by more than `tolerance` percent.
+///
+/// Files are matched by name; a report of a single file matches a single
+/// result whatever its name. Files without a pinned count are reported as
+/// well.
+#[must_use]
+pub fn count_shifts(
+    pinned: &PinnedReport,
+    results: &[(String, Count)],
+    tolerance: f64,
+) -> Vec<String> {
+    let version = pinned
+        .typst_version
+        .as_ref()
+        .map(|version| format!(" (Typst {version})"))
+        .unwrap_or_default();
+    let mut shifts = Vec::new();
+    for (name, count) in results {
+        let before = match pinned.files.as_slice() {
+            [(None, before)] if results.len() == 1 => Some(before),
+            files => files
+                .iter()
+                .find(|(file, _)| file.as_deref() == Some(name.as_str()))
+                .map(|(_, before)| before),
+        };
+        let Some(before) = before else {
+            shifts.push(format!("No pinned count for {name}"));
+            continue;
+        };
+
+        for (unit, old, new) in [
+            ("words", before.words, count.words),
+            ("characters", before.characters, count.characters),
+        ] {
+            let change = new as f64 - old as f64;
+            let percent = if old == 0 {
+                if new == 0 { 0.0 } else { 100.0 }
+            } else {
+                change * 100.0 / old as f64
+            };
+            if percent.abs() > tolerance {
+                shifts.push(format!(
+                    "Count of {name} shifted since the pinned report{version}: \
+                     {unit} {old} → {new} ({percent:+.1}%)"
+                ));
+            }
+        }
+    }
+    shifts
+}
+
+/// Formats a Markdown summary of a change for a pull request comment.
+///
+/// The summary is a table with the counts before and after the change, the
+/// difference, and the status of any limits, followed by an overall verdict
+/// if limits were given.
+/// Without a baseline, only the counts after the change are shown. The
+/// comment starts with [`COMMENT_MARKER`], so a bot can keep a single sticky
+/// comment up to date.
+#[must_use]
+pub fn format_pr_comment(before: Option<&Count>, after: &Count, limits: &Limits) -> String {
+    let mut rows = vec![
+        (
+            "Words",
+            before.map(|count| count.words),
+            after.words,
+            limit_status(after.words, limits.min_words, limits.max_words),
+        ),
+        (
+            "Characters",
+            before.map(|count| count.characters),
+            after.characters,
+            limit_status(
+                after.characters,
+                limits.min_characters,
+                limits.max_characters,
+            ),
+        ),
+    ];
+    for (metric, value) in after.metrics.iter() {
+        let previous = before.and_then(|count| count.metrics.get(metric));
+        rows.push((metric.label(), previous, value, None));
+    }
+
+    let mut output = String::new();
+    writeln!(output, "{COMMENT_MARKER}").unwrap();
+    writeln!(output, "### Word count\n").unwrap();
+    writeln!(output, "| | Before | After | Change | Limit |").unwrap();
+    writeln!(output, "|:--|--:|--:|--:|:--|").unwrap();
+    let mut checked = 0;
+    let mut violations = 0;
+    for (label, previous, value, status) in rows {
+        let (previous, change) = match previous {
+            Some(previous) => (
+                previous.to_string(),
+                format!("{:+}", value as i64 - previous as i64),
+            ),
+            None => ("—".to_string(), "—".to_string()),
+        };
+        checked += usize::from(status.is_some());
+        let status = match status {
+            Some((true, limit)) => format!("✅ {limit}"),
+            Some((false, limit)) => {
+                violations += 1;
+                format!("❌ {limit}")
+            }
+            None => "—".to_string(),
+        };
+        writeln!(
+            output,
+            "| {label} | {previous} | {value} | {change} | {status} |"
+        )
+        .unwrap();
+    }
+
+    if checked == 0 {
+        return output;
+    }
+    writeln!(output).unwrap();
+    match violations {
+        0 => writeln!(output, "All limits are met.").unwrap(),
+        1 => writeln!(output, "**1 limit is violated.**").unwrap(),
+        n => writeln!(output, "**{n} limits are violated.**").unwrap(),
+    }
+    output
+}
+
+/// Checks a value against optional bounds.
+///
+/// Returns whether the value is within the bounds and a description of the
+/// bounds, or `None` if there are no bounds.
+fn limit_status(value: usize, min: Option<usize>, max: Option<usize>) -> Option<(bool, String)> {
+    let ok = min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max);
+    let limit = match (min, max) {
+        (Some(min), Some(max)) => format!("{min}–{max}"),
+        (Some(min), None) => format!("min {min}"),
+        (None, Some(max)) => format!("max {max}"),
+        (None, None) => return None,
+    };
+    Some((ok, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_single_report() {
+        let count = parse_report(
+            r#"{"words":100,"characters":500,"links":3,"timestamp":"2026-01-17T12:00:00Z"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(count.words, 100);
+        assert_eq!(count.characters, 500);
+        assert_eq!(count.metrics.get(Metric::Links), Some(3));
+    }
+
+    #[test]
+    fn test_parse_grouped_report() {
+        let count = parse_report(
+            r#"[
+              {"directory":"chapters","words":300,"files":[
+                {"file":"chapters/a.typ","words":100},
+                {"file":"chapters/b.typ","words":200}
+              ]},
+              {"directory":".","words":50,"files":[{"file":"main.typ","words":50}]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(count.words, 350);
+        assert_eq!(count.characters, 0);
+    }
+
+    #[test]
+    fn test_parse_invalid_report() {
+        assert!(parse_report("not json").is_err());
+        assert!(parse_report("42").is_err());
+        assert!(parse_report(r#"[{"file":"a.typ"}]"#).is_err());
+        assert!(parse_report(r#"{"words":-1}"#).is_err());
+    }
+
+    #[test]
+    fn test_count_shifts() {
+        let pinned = parse_pinned(
+            r#"[
+              {"file":"a.typ","words":1000,"characters":6000,"typst_version":"0.13.1"},
+              {"file":"b.typ","words":500,"characters":3000,"typst_version":"0.13.1"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(pinned.typst_version.as_deref(), Some("0.13.1"));
+
+        let count = |words, characters| Count {
+            words,
+            characters,
+            ..Count::default()
+        };
+        let results = [
+            ("a.typ".to_string(), count(1010, 6000)),
+            ("b.typ".to_string(), count(500, 3000)),
+            ("c.typ".to_string(), count(10, 60)),
+        ];
+
+        assert_eq!(
+            count_shifts(&pinned, &results, 0.0),
+            [
+                "Count of a.typ shifted since the pinned report (Typst 0.13.1): \
+                 words 1000 → 1010 (+1.0%)",
+                "No pinned count for c.typ",
+            ]
+        );
+        assert_eq!(
+            count_shifts(&pinned, &results[..2], 1.0),
+            Vec::<String>::new()
+        );
+
+        let single = parse_pinned(r#"{"words":10,"characters":60}"#).unwrap();
+        assert!(count_shifts(&single, &results[2..], 0.0).is_empty());
+        assert!(parse_pinned(r#"[{"directory":".","words":1,"files":[]}]"#).is_err());
+    }
+
+    #[test]
+    fn test_format_pr_comment() {
+        let before = parse_report(r#"{"words":5000,"characters":30000}"#).unwrap();
+        let after = parse_report(r#"{"words":5200,"characters":31000}"#).unwrap();
+        let limits = Limits {
+            max_words: Some(5100),
+            min_characters: Some(20000),
+            ..Limits::default()
+        };
+        let comment = format_pr_comment(Some(&before), &after, &limits);
+        let lines: Vec<&str> = comment.lines().collect();
+
+        assert_eq!(lines[0], COMMENT_MARKER);
+        assert_eq!(lines[5], "| Words | 5000 | 5200 | +200 | ❌ max 5100 |");
+        assert_eq!(
+            lines[6],
+            "| Characters | 30000 | 31000 | +1000 | ✅ min 20000 |"
+        );
+        assert_eq!(lines[8], "**1 limit is violated.**");
+    }
+
+    #[test]
+    fn test_limit_status() {
+        let limits = Limits {
+            max_words: Some(100),
+            min_characters: Some(50),
+            ..Limits::default()
+        };
+        let count = |words, characters| Count {
+            words,
+            characters,
+            ..Count::default()
+        };
+
+        assert_eq!(Limits::default().status(&count(10, 60)), None);
+        assert_eq!(limits.status(&count(10, 60)), Some(LimitStatus::Within));
+        assert_eq!(limits.status(&count(90, 600)), Some(LimitStatus::Near));
+        assert_eq!(limits.status(&count(101, 600)), Some(LimitStatus::Violated));
+        assert_eq!(limits.status(&count(10, 40)), Some(LimitStatus::Violated));
+    }
+
+    #[test]
+    fn test_format_pr_comment_without_baseline() {
+        let after = parse_report(r#"{"words":120,"characters":600,"links":2}"#).unwrap();
+        let comment = format_pr_comment(None, &after, &Limits::default());
+
+        assert!(comment.contains("| Words | — | 120 | — | — |"));
+        assert!(comment.contains("| Links | — | 2 | — | — |"));
+        assert!(!comment.contains("limit"));
+    }
+}
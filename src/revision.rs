@@ -0,0 +1,367 @@
+//! Reading documents from a git revision.
+//!
+//! Historical counts are computed from the files of a commit as stored in
+//! the repository. The files are read with the `git` command and compiled in
+//! an in-memory world, so nothing is checked out and the working tree is
+//! left untouched.
+
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use typst::syntax::VirtualPath;
+
+/// The files of a document's directory at a git revision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Virtual path of the main document
+    pub main: VirtualPath,
+    /// Contents of every file in the document's directory and below, keyed
+    /// by their path relative to that directory
+    pub files: HashMap<VirtualPath, Vec<u8>>,
+}
+
+/// A commit in the history of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// Full SHA of the commit
+    pub sha: String,
+    /// Commit date as `YYYY-MM-DD`
+    pub date: String,
+    /// First line of the commit message
+    pub summary: String,
+}
+
+/// Reads the document at `path` and the files next to it as of `rev`.
+///
+/// As when compiling from disk, the document's directory is the root of the
+/// project, so the snapshot contains that directory and everything below
+/// it. `rev` can be anything git accepts as a commit, e.g. a branch, a tag,
+/// or a commit SHA.
+///
+/// # Errors
+///
+/// Returns an error if the document's directory is not inside a git
+/// repository, the revision doesn't exist, or the document doesn't exist at
+/// the revision.
+pub fn read_snapshot(path: &Path, rev: &str) -> Result<Snapshot> {
+    let name = path
+        .file_name()
+        .with_context(|| format!("Input file has no filename: {}", path.display()))?;
+    let dir = directory(path);
+
+    let commit = resolve(dir, rev)?;
+    let prefix = text(git(dir, &["rev-parse", "--show-prefix"], None)?)?;
+    let listing = git(dir, &["ls-tree", "-r", "-z", "--full-tree", &commit], None)?;
+
+    let mut paths = Vec::new();
+    let mut objects = String::new();
+    for (full_path, object) in blobs(&listing)? {
+        if let Some(relative) = full_path.strip_prefix(prefix.as_str()) {
+            paths.push(VirtualPath::new(relative));
+            objects.push_str(object);
+            objects.push('\n');
+        }
+    }
+
+    let contents = read_objects(&git(dir, &["cat-file", "--batch"], Some(objects))?)?;
+    let files: HashMap<_, _> = paths.into_iter().zip(contents).collect();
+
+    let main = VirtualPath::new(name);
+    if !files.contains_key(&main) {
+        bail!("{} does not exist at revision {rev}", path.display());
+    }
+    Ok(Snapshot { main, files })
+}
+
+/// Checks whether the document at `path` exists at `rev`.
+///
+/// # Errors
+///
+/// Returns an error if the document's directory is not inside a git
+/// repository or the revision doesn't exist.
+pub fn contains(path: &Path, rev: &str) -> Result<bool> {
+    let name = path
+        .file_name()
+        .with_context(|| format!("Input file has no filename: {}", path.display()))?;
+    let dir = directory(path);
+    let commit = resolve(dir, rev)?;
+    let prefix = text(git(dir, &["rev-parse", "--show-prefix"], None)?)?;
+    let object = format!("{commit}:{prefix}{}", name.to_string_lossy());
+    Ok(git(dir, &["cat-file", "-e", &object], None).is_ok())
+}
+
+/// Reads a single file as of `rev`.
+///
+/// # Errors
+///
+/// Returns an error if the file's directory is not inside a git repository,
+/// the revision doesn't exist, or the file doesn't exist at the revision.
+pub fn read_file(path: &Path, rev: &str) -> Result<Vec<u8>> {
+    let name = path
+        .file_name()
+        .with_context(|| format!("Input file has no filename: {}", path.display()))?;
+    let dir = directory(path);
+    let commit = resolve(dir, rev)?;
+    let object = format!("{commit}:./{}", name.to_string_lossy());
+    git(dir, &["cat-file", "blob", &object], None)
+        .with_context(|| format!("{} does not exist at revision {rev}", path.display()))
+}
+
+/// Lists the files below a directory as of `rev`, relative to it.
+///
+/// # Errors
+///
+/// Returns an error if the directory is not inside a git repository or the
+/// revision doesn't exist.
+pub fn list_files(dir: &Path, rev: &str) -> Result<Vec<PathBuf>> {
+    let commit = resolve(dir, rev)?;
+    // Without `--full-tree`, the listing is limited to the current directory
+    // and paths are relative to it.
+    let listing = git(dir, &["ls-tree", "-r", "-z", &commit], None)?;
+    Ok(blobs(&listing)?
+        .into_iter()
+        .map(|(path, _)| PathBuf::from(path))
+        .collect())
+}
+
+/// Lists the commits that changed the given documents, oldest first.
+///
+/// A commit counts as a change if it touched anything in the directory of
+/// one of the documents, since included files and assets affect the count
+/// as well. `since` restricts the history to commits after a date in any
+/// form `git log --since` accepts, e.g. `3 months ago` or `2026-01-01`.
+///
+/// # Errors
+///
+/// Returns an error if a document's directory doesn't exist or is not
+/// inside the same git repository as the others.
+pub fn history(paths: &[PathBuf], since: Option<&str>) -> Result<Vec<Commit>> {
+    let dirs = paths
+        .iter()
+        .map(|path| {
+            directory(path)
+                .canonicalize()
+                .with_context(|| format!("Failed to find directory of {}", path.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let Some(first) = dirs.first() else {
+        return Ok(Vec::new());
+    };
+
+    let since = since.map(|since| format!("--since={since}"));
+    let dirs: Vec<_> = dirs.iter().map(|dir| dir.to_string_lossy()).collect();
+    let mut args = vec!["log", "--reverse", "--format=%H%x09%cs%x09%s"];
+    args.extend(since.as_deref());
+    args.push("--");
+    args.extend(dirs.iter().map(|dir| &**dir));
+
+    let log = text(git(first, &args, None)?)?;
+    log.lines()
+        .map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let (Some(sha), Some(date), summary) = (fields.next(), fields.next(), fields.next())
+            else {
+                bail!("Unexpected git log line: {line}");
+            };
+            Ok(Commit {
+                sha: sha.to_string(),
+                date: date.to_string(),
+                summary: summary.unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Resolves a revision to the SHA of its commit.
+///
+/// # Errors
+///
+/// Returns an error if `dir` is not inside a git repository or the revision
+/// doesn't name a commit.
+pub fn resolve(dir: &Path, rev: &str) -> Result<String> {
+    let spec = format!("{rev}^{{commit}}");
+    git(dir, &["rev-parse", "--verify", "--quiet", &spec], None)
+        .and_then(text)
+        .with_context(|| format!("Unknown git revision: {rev}"))
+}
+
+/// Returns the paths and objects of the files in the output of
+/// `git ls-tree -z`.
+///
+/// Entries have the form `<mode> <type> <object>\t<path>`; entries other
+/// than files, e.g. submodules, are skipped.
+fn blobs(listing: &[u8]) -> Result<Vec<(&str, &str)>> {
+    let mut blobs = Vec::new();
+    for entry in listing.split(|&byte| byte == 0).filter(|e| !e.is_empty()) {
+        let entry = std::str::from_utf8(entry).context("Non UTF-8 path in git tree")?;
+        let Some((info, path)) = entry.split_once('\t') else {
+            bail!("Unexpected git tree entry: {entry}");
+        };
+        let mut info = info.split(' ');
+        if let (Some(_), Some("blob"), Some(object)) = (info.next(), info.next(), info.next()) {
+            blobs.push((path, object));
+        }
+    }
+    Ok(blobs)
+}
+
+/// Splits the output of `git cat-file --batch` into the object contents.
+///
+/// Each object is written as `<object> <type> <size>\n<contents>\n`.
+fn read_objects(mut output: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut contents = Vec::new();
+    while !output.is_empty() {
+        let header_end = output
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .context("Truncated git object header")?;
+        let header = std::str::from_utf8(&output[..header_end])?;
+        let size: usize = header
+            .rsplit(' ')
+            .next()
+            .and_then(|size| size.parse().ok())
+            .with_context(|| format!("Unexpected git object header: {header}"))?;
+        let start = header_end + 1;
+        let data = output
+            .get(start..start + size)
+            .context("Truncated git object")?;
+        contents.push(data.to_vec());
+        output = output.get(start + size + 1..).unwrap_or_default();
+    }
+    Ok(contents)
+}
+
+/// Runs a git command in `dir`, writing `input` to its standard input, and
+/// returns its standard output.
+fn git(dir: &Path, args: &[&str], input: Option<String>) -> Result<Vec<u8>> {
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run git")?;
+
+    // Write on a separate thread, so git never blocks on a full output pipe
+    // while we are still writing its input.
+    let mut stdin = child.stdin.take().context("Failed to open git's input")?;
+    let writer = thread::spawn(move || stdin.write_all(input.unwrap_or_default().as_bytes()));
+    let output = child.wait_with_output().context("Failed to run git")?;
+    let written = writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("Failed to write git's input"))?;
+
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    written.context("Failed to write git's input")?;
+    Ok(output.stdout)
+}
+
+/// Returns the directory containing a document.
+fn directory(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+/// Converts command output into a trimmed string.
+fn text(output: Vec<u8>) -> Result<String> {
+    Ok(String::from_utf8(output)?.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Runs a git command for setting up a test repository.
+    fn run(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_read_snapshot() {
+        let repo = tempfile::tempdir().unwrap();
+        let docs = repo.path().join("docs");
+        fs::create_dir_all(docs.join("chapters")).unwrap();
+        fs::write(docs.join("main.typ"), "#include \"chapters/one.typ\"").unwrap();
+        fs::write(docs.join("chapters/one.typ"), "Old text.").unwrap();
+        fs::write(repo.path().join("README.md"), "Outside").unwrap();
+        run(repo.path(), &["init", "-q"]);
+        run(repo.path(), &["add", "."]);
+        run(repo.path(), &["commit", "-q", "-m", "Draft"]);
+        run(repo.path(), &["tag", "draft"]);
+        fs::write(docs.join("chapters/one.typ"), "New text.").unwrap();
+
+        let snapshot = read_snapshot(&docs.join("main.typ"), "draft").unwrap();
+        assert_eq!(snapshot.main, VirtualPath::new("main.typ"));
+        assert_eq!(snapshot.files.len(), 2);
+        assert_eq!(
+            snapshot.files[&VirtualPath::new("chapters/one.typ")],
+            b"Old text."
+        );
+
+        assert_eq!(
+            read_file(&docs.join("chapters/one.typ"), "draft").unwrap(),
+            b"Old text."
+        );
+        assert!(read_file(&docs.join("missing.typ"), "draft").is_err());
+        assert_eq!(
+            list_files(&docs, "draft").unwrap(),
+            [PathBuf::from("chapters/one.typ"), PathBuf::from("main.typ")]
+        );
+
+        assert!(contains(&docs.join("main.typ"), "draft").unwrap());
+        assert!(!contains(&docs.join("missing.typ"), "draft").unwrap());
+        assert!(read_snapshot(&docs.join("missing.typ"), "draft").is_err());
+        assert!(read_snapshot(&docs.join("main.typ"), "no-such-rev").is_err());
+    }
+
+    #[test]
+    fn test_history() {
+        let repo = tempfile::tempdir().unwrap();
+        let docs = repo.path().join("docs");
+        fs::create_dir(&docs).unwrap();
+        run(repo.path(), &["init", "-q"]);
+        for (file, message) in [("docs/main.typ", "Start thesis"), ("notes.txt", "Notes")] {
+            fs::write(repo.path().join(file), message).unwrap();
+            run(repo.path(), &["add", "."]);
+            run(repo.path(), &["commit", "-q", "-m", message]);
+        }
+        fs::write(docs.join("intro.typ"), "Intro").unwrap();
+        run(repo.path(), &["add", "."]);
+        run(repo.path(), &["commit", "-q", "-m", "Add intro\n\nDetails"]);
+
+        let commits = history(&[docs.join("main.typ")], None).unwrap();
+        let summaries: Vec<_> = commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, ["Start thesis", "Add intro"]);
+        assert_eq!(commits[0].sha.len(), 40);
+        assert_eq!(commits[0].date.len(), 10);
+
+        let since = history(&[docs.join("main.typ")], Some("2037-01-01")).unwrap();
+        assert!(since.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_outside_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(resolve(dir.path(), "HEAD").is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! SARIF output for limit violations.
+//!
+//! The Static Analysis Results Interchange Format is understood by code
+//! scanning tools such as GitHub's, which show each result inline at its
+//! location in the repository. Writing limit violations as SARIF surfaces a
+//! chapter exceeding its word budget next to the chapter itself.
+
+use crate::counter::SourceLocation;
+use serde_json::{Value, json};
+use std::path::Path;
+
+/// Version of the SARIF specification of the output.
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// Identifier of the rule that all limit violations are reported under.
+pub const RULE_ID: &str = "count-limit";
+
+/// A violated limit and where in the sources it applies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Description of the violation, e.g. "Word count exceeds maximum (120 > 100)"
+    pub message: String,
+    /// Source locations the limit applies to, e.g. the counted files or the
+    /// heading of a section
+    pub locations: Vec<SourceLocation>,
+}
+
+impl Violation {
+    /// Creates a violation that applies to whole files.
+    ///
+    /// Each file is located at its beginning.
+    #[must_use]
+    pub fn in_files<P: AsRef<Path>>(message: String, files: &[P]) -> Self {
+        let locations = files
+            .iter()
+            .map(|file| SourceLocation {
+                file: file.as_ref().display().to_string(),
+                line: 1,
+                column: 1,
+            })
+            .collect();
+        Self { message, locations }
+    }
+}
+
+/// Formats limit violations as a SARIF log.
+///
+/// The log has a single run of typst-count with one result per violation.
+/// Paths are written as given, so they should be relative to the repository
+/// root for code scanning to link them to the sources.
+#[must_use]
+pub fn format(violations: &[Violation]) -> String {
+    let results: Vec<Value> = violations
+        .iter()
+        .map(|violation| {
+            let locations: Vec<Value> = violation
+                .locations
+                .iter()
+                .map(|location| {
+                    json!({
+                        "physicalLocation": {
+                            "artifactLocation": {
+                                "uri": location.file.replace('\\', "/"),
+                                "uriBaseId": "%SRCROOT%",
+                            },
+                            "region": {
+                                "startLine": location.line,
+                                "startColumn": location.column,
+                            },
+                        },
+                    })
+                })
+                .collect();
+            json!({
+                "ruleId": RULE_ID,
+                "level": "error",
+                "message": { "text": violation.message },
+                "locations": locations,
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "typst-count",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": [{
+                        "id": RULE_ID,
+                        "name": "CountLimit",
+                        "shortDescription": {
+                            "text": "Word or character count outside the configured limits",
+                        },
+                    }],
+                },
+            },
+            "results": results,
+        }],
+    });
+    let mut output = serde_json::to_string_pretty(&log).unwrap_or_default();
+    output.push('\n');
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_violations() {
+        let violations = [
+            Violation::in_files(
+                "Word count exceeds maximum (120 > 100)".to_string(),
+                &["chapters/intro.typ"],
+            ),
+            Violation {
+                message: "Section exceeds its budget".to_string(),
+                locations: vec![SourceLocation {
+                    file: "chapters/results.typ".to_string(),
+                    line: 12,
+                    column: 1,
+                }],
+            },
+        ];
+        let log: Value = serde_json::from_str(&format(&violations)).unwrap();
+        let results = &log["runs"][0]["results"];
+
+        assert_eq!(log["version"], SARIF_VERSION);
+        assert_eq!(results.as_array().unwrap().len(), 2);
+        assert_eq!(results[0]["ruleId"], RULE_ID);
+        assert_eq!(
+            results[0]["message"]["text"],
+            "Word count exceeds maximum (120 > 100)"
+        );
+        let location = &results[1]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "chapters/results.typ");
+        assert_eq!(location["region"]["startLine"], 12);
+    }
+
+    #[test]
+    fn test_format_without_violations() {
+        let log: Value = serde_json::from_str(&format(&[])).unwrap();
+        assert_eq!(log["runs"][0]["results"], json!([]));
+    }
+}
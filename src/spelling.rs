@@ -0,0 +1,209 @@
+//! Spell-checking the counted text.
+//!
+//! `--spellcheck DICT` checks the text that is counted with `hunspell`,
+//! which reads Hunspell dictionaries as well as most MySpell and OpenOffice
+//! ones, and lists the misspelled words at their place in the sources.
+//! Together with `--max-typos`, one CI job can gate both the length and the
+//! spelling of a document.
+
+use crate::counter::{SourceLocation, TextChunk};
+use crate::lint::locate_in_source;
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Suggestions shown per typo.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// A word the dictionary doesn't know.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Typo {
+    /// Document the word was counted in
+    pub file: String,
+    /// Where the word is written, if it comes from a source file
+    pub location: Option<SourceLocation>,
+    /// The misspelled word
+    pub word: String,
+    /// Suggested corrections, best first
+    pub suggestions: Vec<String>,
+}
+
+/// Spell-checks the counted text of a document.
+///
+/// `dictionary` is passed to `hunspell -d`, e.g. `en_US` or the path of a
+/// `.dic` file without its extension. `root` is the directory the chunks'
+/// source locations are relative to.
+///
+/// # Errors
+///
+/// Returns an error if `hunspell` is not installed or fails, e.g. because
+/// the dictionary cannot be found.
+pub fn check(dictionary: &str, file: &str, root: &Path, chunks: &[TextChunk]) -> Result<Vec<Typo>> {
+    // `!` turns on terse mode, which only reports misspelled words, and `^`
+    // keeps lines from being read as commands.
+    let mut input = String::from("!\n");
+    for chunk in chunks {
+        let text = chunk.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        writeln!(input, "^{text}").unwrap();
+    }
+
+    let mut child = Command::new("hunspell")
+        .args(["-a", "-i", "utf-8", "-d", dictionary])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run hunspell; install hunspell to use --spellcheck")?;
+    let mut stdin = child.stdin.take().context("Failed to start hunspell")?;
+    // Write from another thread, so hunspell doesn't block on a full pipe.
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output()?;
+    writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("Failed to send the text to hunspell"))??;
+    if !output.status.success() {
+        bail!(
+            "Failed to spell-check {file}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let mut typos = typos_from_output(&String::from_utf8_lossy(&output.stdout), chunks, file, root);
+    // Show the files relative to the working directory, like the document.
+    for location in typos.iter_mut().filter_map(|typo| typo.location.as_mut()) {
+        if !location.file.starts_with('@') {
+            location.file = root.join(&location.file).display().to_string();
+        }
+    }
+    Ok(typos)
+}
+
+/// Reads the misspelled words from the output of `hunspell -a`.
+///
+/// The results of each input line end with an empty line. A misspelled word
+/// is reported as `& WORD COUNT OFFSET: SUGGESTIONS`, or `# WORD OFFSET`
+/// without suggestions.
+fn typos_from_output(output: &str, chunks: &[TextChunk], file: &str, root: &Path) -> Vec<Typo> {
+    let mut typos = Vec::new();
+    let mut index = 0;
+    // Occurrences of each word in the current chunk so far, to tell repeated
+    // typos apart.
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for line in output.lines() {
+        if line.is_empty() {
+            index += 1;
+            seen.clear();
+            continue;
+        }
+        let Some(chunk) = chunks.get(index) else {
+            break;
+        };
+        let (word, suggestions) = match line.split_once(' ') {
+            Some(("&", rest)) => {
+                let (head, suggestions) = rest.split_once(": ").unwrap_or((rest, ""));
+                let suggestions = suggestions
+                    .split(", ")
+                    .filter(|s| !s.is_empty())
+                    .take(MAX_SUGGESTIONS)
+                    .map(str::to_string)
+                    .collect();
+                (head.split(' ').next().unwrap_or_default(), suggestions)
+            }
+            Some(("#", rest)) => (rest.split(' ').next().unwrap_or_default(), Vec::new()),
+            _ => continue,
+        };
+
+        let nth = seen.entry(word).or_default();
+        let location = chunk.location.as_ref().map(|location| {
+            locate_in_source(root, location, word, *nth).unwrap_or_else(|| location.clone())
+        });
+        *nth += 1;
+        typos.push(Typo {
+            file: file.to_string(),
+            location,
+            word: word.to_string(),
+            suggestions,
+        });
+    }
+    typos
+}
+
+/// Formats typos, one per line, prefixed with their location.
+///
+/// Typos without a source location are prefixed with their document
+/// instead. Returns an empty string if there are none.
+#[must_use]
+pub fn format_typos(typos: &[Typo]) -> String {
+    let mut output = String::new();
+    if typos.is_empty() {
+        return output;
+    }
+
+    writeln!(output, "Typos:").unwrap();
+    for typo in typos {
+        let location = match &typo.location {
+            Some(location) => location.to_string(),
+            None => typo.file.clone(),
+        };
+        write!(output, "  {location}: \"{}\"", typo.word).unwrap();
+        if !typo.suggestions.is_empty() {
+            write!(output, " → {}", typo.suggestions.join(", ")).unwrap();
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_typos_from_output() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("main.typ"),
+            "= Intro\n\nWe recieve *data*,\nand recieve mroe.\n",
+        )
+        .unwrap();
+        let chunk = |text: &str, line| TextChunk {
+            text: text.to_string(),
+            element: "par",
+            heading_level: None,
+            location: Some(SourceLocation {
+                file: "main.typ".to_string(),
+                line,
+                column: 1,
+            }),
+        };
+        let chunks = [
+            chunk("Intro", 1),
+            chunk("We recieve data, and recieve mroe.", 3),
+        ];
+        let output = "@(#) International Ispell Version 3.2.06 (but really Hunspell 1.7.2)\n\
+                      \n\
+                      & recieve 2 3: receive, relieve\n\
+                      & recieve 2 21: receive, relieve\n\
+                      # mroe 29\n\
+                      \n";
+        let typos = typos_from_output(output, &chunks, "main.typ", dir.path());
+        let locations: Vec<String> = typos
+            .iter()
+            .map(|typo| typo.location.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(locations, ["main.typ:3:4", "main.typ:4:5", "main.typ:4:13"]);
+        assert_eq!(typos[0].suggestions, ["receive", "relieve"]);
+        assert!(typos[2].suggestions.is_empty());
+
+        assert_eq!(
+            format_typos(&typos[1..]),
+            "Typos:\n  main.typ:4:5: \"recieve\" → receive, relieve\n  main.typ:4:13: \"mroe\"\n"
+        );
+        assert_eq!(format_typos(&[]), "");
+    }
+}
@@ -0,0 +1,400 @@
+//! Preferred terms and the variants they replace.
+//!
+//! Style guides settle on one spelling of a term, such as "email" over
+//! "e-mail", and documentation teams want the other spellings counted and
+//! kept at zero. A terms file maps each preferred term to its discouraged
+//! variants:
+//!
+//! ```toml
+//! email = ["e-mail", "E-Mail"]
+//! "sign in" = ["login to", "log-in"]
+//! ```
+//!
+//! Variants written in lowercase match regardless of case; variants with
+//! capitals only match as written. Variants only match whole words.
+//!
+//! Keywords given with `--keyword` are matched the same way, for writers who
+//! must use a phrase often enough, but not so often that it reads as
+//! stuffing.
+
+use crate::counter::CountOptions;
+use crate::wordlists::WordFilter;
+use anyhow::{Context, Result, bail};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use toml::{Table, Value};
+
+/// A preferred term and its discouraged variants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    /// The spelling to use
+    pub preferred: String,
+    /// Spellings to replace with the preferred one
+    pub variants: Vec<String>,
+}
+
+/// The terms of a terms file, in the order they appear in the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Terms(pub Vec<Term>);
+
+/// How often the variants of a term occur in a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TermUsage {
+    /// The preferred spelling
+    pub preferred: String,
+    /// Occurrences of each variant, in the order of the terms file
+    pub variants: Vec<(String, usize)>,
+}
+
+impl Terms {
+    /// Reads the terms from a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid terms
+    /// file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read terms file: {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid terms file: {}", path.display()))
+    }
+
+    /// Parses terms from TOML text.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the text is not valid TOML or a term doesn't map
+    /// to a list of variants.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use typst_count::terms::Terms;
+    ///
+    /// let terms = Terms::parse(r#"email = ["e-mail"]"#)?;
+    /// assert_eq!(terms.discouraged("Send an e-mail or an E-mail."), 2);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn parse(text: &str) -> Result<Self> {
+        let table: Table = text.parse()?;
+        let mut terms = Vec::new();
+        for (preferred, value) in table {
+            let Value::Array(values) = value else {
+                bail!("`{preferred}` must be a list of variants");
+            };
+            let variants = values
+                .into_iter()
+                .map(|value| match value {
+                    Value::String(variant) if !variant.trim().is_empty() => {
+                        Ok(variant.trim().to_string())
+                    }
+                    _ => bail!("The variants of `{preferred}` must be non-empty strings"),
+                })
+                .collect::<Result<_>>()?;
+            terms.push(Term {
+                preferred,
+                variants,
+            });
+        }
+        Ok(Self(terms))
+    }
+
+    /// Checks whether there are no terms.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Counts the occurrences of all discouraged variants in a text.
+    #[must_use]
+    pub fn discouraged(&self, text: &str) -> usize {
+        self.0
+            .iter()
+            .flat_map(|term| &term.variants)
+            .map(|variant| occurrences(text, variant))
+            .sum()
+    }
+
+    /// Counts the occurrences of each discouraged variant in some texts,
+    /// e.g. the counted elements of a document.
+    ///
+    /// Variants only match within a text, as for [`Terms::discouraged`] on
+    /// each of them.
+    #[must_use]
+    pub fn usage(&self, texts: &[impl AsRef<str>]) -> Vec<TermUsage> {
+        self.0
+            .iter()
+            .map(|term| TermUsage {
+                preferred: term.preferred.clone(),
+                variants: term
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let count = texts
+                            .iter()
+                            .map(|text| occurrences(text.as_ref(), variant))
+                            .sum();
+                        (variant.clone(), count)
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+/// A target range for the density of a keyword.
+///
+/// Parsed from `KEYWORD=MIN..MAX%`, e.g. `machine learning=1..3%`; either
+/// end of the range may be left out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordTarget {
+    /// The word or phrase, matched like a variant of a term
+    pub keyword: String,
+    /// Minimum density in percent
+    pub min: Option<f64>,
+    /// Maximum density in percent
+    pub max: Option<f64>,
+}
+
+impl KeywordTarget {
+    /// Returns the density of the keyword in some texts with a total number
+    /// of words, as occurrences per 100 words.
+    ///
+    /// Words of the texts that `filter` ignores, such as stopwords, are
+    /// taken off the total, split into words with `options` as when
+    /// counting.
+    #[must_use]
+    pub fn density(
+        &self,
+        texts: &[impl AsRef<str>],
+        words: usize,
+        filter: &WordFilter,
+        options: &CountOptions,
+    ) -> f64 {
+        let words = words.saturating_sub(filter.ignored_words(texts, options));
+        if words == 0 {
+            return 0.0;
+        }
+        let occurrences: usize = texts
+            .iter()
+            .map(|text| occurrences(text.as_ref(), &self.keyword))
+            .sum();
+        occurrences as f64 * 100.0 / words as f64
+    }
+
+    /// Checks the density of the keyword against the target range.
+    ///
+    /// # Returns
+    ///
+    /// A message describing the violation, or `None` if the density is in
+    /// range.
+    #[must_use]
+    pub fn check(
+        &self,
+        texts: &[impl AsRef<str>],
+        words: usize,
+        filter: &WordFilter,
+        options: &CountOptions,
+    ) -> Option<String> {
+        let density = self.density(texts, words, filter, options);
+        let keyword = &self.keyword;
+        if let Some(min) = self.min
+            && density < min
+        {
+            return Some(format!(
+                "Density of \"{keyword}\" below minimum ({density:.2}% < {min}%)"
+            ));
+        }
+        if let Some(max) = self.max
+            && density > max
+        {
+            return Some(format!(
+                "Density of \"{keyword}\" exceeds maximum ({density:.2}% > {max}%)"
+            ));
+        }
+        None
+    }
+}
+
+impl FromStr for KeywordTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((keyword, range)) = value.rsplit_once('=') else {
+            return Err(format!("expected KEYWORD=MIN..MAX%, found `{value}`"));
+        };
+        let keyword = keyword.trim();
+        let range = range.trim().trim_end_matches('%');
+        let Some((min, max)) = range.split_once("..") else {
+            return Err(format!("expected a range such as 1..3%, found `{range}`"));
+        };
+        let percent = |bound: &str| {
+            let bound = bound.trim().trim_end_matches('%');
+            if bound.is_empty() {
+                return Ok(None);
+            }
+            match bound.parse::<f64>() {
+                Ok(percent) if percent >= 0.0 => Ok(Some(percent)),
+                _ => Err(format!("invalid percentage `{bound}`")),
+            }
+        };
+        let (min, max) = (percent(min)?, percent(max)?);
+        if keyword.is_empty() {
+            return Err("the keyword is empty".to_string());
+        }
+        if min.is_none() && max.is_none() {
+            return Err(format!("the range of `{keyword}` has no bounds"));
+        }
+        if let (Some(min), Some(max)) = (min, max)
+            && min > max
+        {
+            return Err(format!("empty range `{range}`"));
+        }
+        Ok(Self {
+            keyword: keyword.to_string(),
+            min,
+            max,
+        })
+    }
+}
+
+/// Counts the whole-word occurrences of a variant in a text.
+///
+/// Runs of whitespace in the text match a space in the variant, so terms
+/// broken across lines are found.
+fn occurrences(text: &str, variant: &str) -> usize {
+    let fold = !variant.chars().any(char::is_uppercase);
+    let normalize = |text: &str| {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if fold { text.to_lowercase() } else { text }
+    };
+    let text = normalize(text);
+    let variant = normalize(variant);
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+    let mut count = 0;
+    let mut start = 0;
+    while let Some(offset) = text[start..].find(&variant) {
+        let begin = start + offset;
+        let end = begin + variant.len();
+        let before = text[..begin].chars().next_back();
+        let after = text[end..].chars().next();
+        // Only require a boundary where the variant itself starts or ends
+        // with a word character, so that variants like "-ize" still match.
+        let inside_word = (is_word(variant.chars().next()) && is_word(before))
+            || (is_word(variant.chars().next_back()) && is_word(after));
+        if inside_word {
+            start = begin + text[begin..].chars().next().map_or(1, char::len_utf8);
+        } else {
+            count += 1;
+            start = end;
+        }
+    }
+    count
+}
+
+/// Formats the occurrences of discouraged variants in each file.
+///
+/// Only variants that occur are listed, each with the preferred term to use
+/// instead. Returns an empty string if there are none.
+#[must_use]
+pub fn format_usage(files: &[(String, Vec<TermUsage>)]) -> String {
+    let mut output = String::new();
+    for (file, usages) in files {
+        let found: Vec<_> = usages
+            .iter()
+            .flat_map(|usage| {
+                usage
+                    .variants
+                    .iter()
+                    .filter(|(_, count)| *count > 0)
+                    .map(move |(variant, count)| (variant, *count, &usage.preferred))
+            })
+            .collect();
+        if found.is_empty() {
+            continue;
+        }
+        if output.is_empty() {
+            writeln!(output, "Discouraged terms:").unwrap();
+        }
+        writeln!(output, "  {file}:").unwrap();
+        for (variant, count, preferred) in found {
+            writeln!(output, "    {count}× \"{variant}\" (use \"{preferred}\")").unwrap();
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terms() {
+        let terms =
+            Terms::parse("email = [\"e-mail\"]\n\"sign in\" = [\"log in\", \"Login\"]").unwrap();
+        assert_eq!(terms.0.len(), 2);
+        assert_eq!(terms.0[1].preferred, "sign in");
+
+        let text = "E-mail us, or log\nin to reply by e-mail. Login, login, \
+                    e-mails and re-e-mail aside.";
+        let usage = terms.usage(&[text]);
+        assert_eq!(usage[0].variants, [("e-mail".to_string(), 3)]);
+        assert_eq!(
+            usage[1].variants,
+            [("log in".to_string(), 1), ("Login".to_string(), 1)]
+        );
+        assert_eq!(terms.discouraged(text), 5);
+
+        let report = format_usage(&[
+            ("a.typ".to_string(), usage),
+            ("b.typ".to_string(), terms.usage(&["Email us."])),
+        ]);
+        assert_eq!(
+            report,
+            "Discouraged terms:\n  a.typ:\n    3× \"e-mail\" (use \"email\")\n    \
+             1× \"log in\" (use \"sign in\")\n    1× \"Login\" (use \"sign in\")\n"
+        );
+
+        assert!(Terms::parse("email = \"e-mail\"").is_err());
+        assert!(Terms::parse("email = [\"\"]").is_err());
+    }
+
+    #[test]
+    fn test_keyword_target() {
+        let target: KeywordTarget = "machine learning=1..3%".parse().unwrap();
+        assert_eq!(target.keyword, "machine learning");
+        assert_eq!((target.min, target.max), (Some(1.0), Some(3.0)));
+
+        let texts = [
+            "Machine learning is learning by machines.",
+            "Machine\nlearning",
+        ];
+        let none = WordFilter::default();
+        let options = CountOptions::default();
+        assert!((target.density(&texts, 50, &none, &options) - 4.0).abs() < 1e-9);
+        assert_eq!(
+            target.check(&texts, 50, &none, &options).as_deref(),
+            Some("Density of \"machine learning\" exceeds maximum (4.00% > 3%)")
+        );
+        assert_eq!(target.check(&texts, 100, &none, &options), None);
+        let stopwords = WordFilter {
+            stopwords: ["is".to_string(), "by".to_string()].into(),
+            ..WordFilter::default()
+        };
+        assert!((target.density(&texts, 102, &stopwords, &options) - 2.0).abs() < 1e-9);
+        assert_eq!(
+            target.check(&texts, 400, &none, &options).as_deref(),
+            Some("Density of \"machine learning\" below minimum (0.50% < 1%)")
+        );
+
+        let at_least: KeywordTarget = "AI=0.5%..".parse().unwrap();
+        assert_eq!((at_least.min, at_least.max), (Some(0.5), None));
+        assert!("AI=1%".parse::<KeywordTarget>().is_err());
+        assert!("AI=3..1%".parse::<KeywordTarget>().is_err());
+        assert!("AI=..".parse::<KeywordTarget>().is_err());
+        assert!("=1..2%".parse::<KeywordTarget>().is_err());
+    }
+}
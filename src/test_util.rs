@@ -0,0 +1,52 @@
+//! Helpers for counting regression tests, enabled by the `test-util` feature.
+//!
+//! Documents are compiled from in-memory source text, so crates embedding
+//! typst-count can pin the counts of their templates and documents without
+//! writing files to disk:
+//!
+//! ```
+//! use typst_count::test_util::count_source;
+//!
+//! let count = count_source("= Results\nThe *bold* claim holds.");
+//! assert_eq!(count.words, 5);
+//! ```
+
+use crate::counter::{Count, CountOptions};
+
+/// Compiles in-memory source text and counts it with the default options.
+///
+/// # Panics
+///
+/// Panics with the compiler's error messages if the source fails to compile.
+#[must_use]
+pub fn count_source(source: &str) -> Count {
+    count_source_with(source, &CountOptions::default())
+}
+
+/// Compiles in-memory source text and counts it with the given options.
+///
+/// # Panics
+///
+/// Panics with the compiler's error messages if the source fails to compile.
+#[must_use]
+pub fn count_source_with(source: &str, options: &CountOptions) -> Count {
+    crate::compile_source(source, options).unwrap_or_else(|err| panic!("{err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_source() {
+        let count = count_source("Hello *world*.");
+        assert_eq!(count.words, 2);
+        assert_eq!(count.characters, 13);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to compile source")]
+    fn test_count_source_reports_errors() {
+        let _ = count_source("#include \"missing.typ\"");
+    }
+}
@@ -0,0 +1,197 @@
+//! Recounting documents as they change.
+//!
+//! `--watch` keeps a live table of the input files in the terminal. The
+//! files and everything they read, such as included chapters and images,
+//! are polled for changes, and only the files whose dependencies changed
+//! are recounted. Inputs can be glob patterns, quoted so the shell leaves
+//! them alone, e.g. `"chapters/*.typ"`; they are expanded again on every
+//! poll, so new chapters join the table as they are created. PDFs given with
+//! `--from-pdf` and files given with `--include-plain` are watched as well.
+//!
+//! Only the report goes to standard output, so `--format json` and the
+//! other machine-readable formats write one report per count; the screen is
+//! only cleared for the human-readable table. Errors and the watching status
+//! go to standard error.
+
+use crate::cli::{Cli, OutputFormat};
+use crate::counter::{Count, CountOptions};
+use crate::output::OutputFormatter;
+use crate::{count_pdf, count_plain, count_with_dependencies, discovery, exec, webhook};
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::{fs, io, thread};
+
+/// Time between two checks for changed files.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The last count of a watched file.
+struct Watched {
+    /// Count of the file, or the message of the error counting it
+    count: Result<Count, String>,
+    /// Files the count depends on, with their modification times when they
+    /// were read
+    dependencies: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Watched {
+    /// Counts a file and records what it depends on.
+    ///
+    /// PDFs given with `--from-pdf` and plain text files only depend on
+    /// themselves. If a document fails to compile, it is recounted when one
+    /// of the files of its last successful count, or the file itself,
+    /// changes.
+    fn count(path: &Path, args: &Cli, options: &CountOptions, previous: Option<Self>) -> Self {
+        let result = if args.from_pdf.iter().any(|pdf| pdf == path) {
+            count_pdf(path, options).map(|count| (count, vec![path.to_path_buf()]))
+        } else if discovery::is_plain(path) || args.include_plain.iter().any(|file| file == path) {
+            count_plain(path, options).map(|count| (count, vec![path.to_path_buf()]))
+        } else {
+            count_with_dependencies(path, options)
+        };
+        let (count, paths) = match result {
+            Ok((count, paths)) => (Ok(count), paths),
+            Err(e) => {
+                let mut paths: Vec<PathBuf> = previous
+                    .into_iter()
+                    .flat_map(|previous| previous.dependencies)
+                    .map(|(path, _)| path)
+                    .collect();
+                paths.push(path.to_path_buf());
+                (Err(format!("{e:#}")), paths)
+            }
+        };
+        let dependencies = paths
+            .into_iter()
+            .map(|path| {
+                let modified = modified(&path);
+                (path, modified)
+            })
+            .collect();
+        Self {
+            count,
+            dependencies,
+        }
+    }
+
+    /// Checks whether one of the files the count depends on changed.
+    fn is_stale(&self) -> bool {
+        self.dependencies
+            .iter()
+            .any(|(path, time)| modified(path) != *time)
+    }
+}
+
+/// Counts the input files and recounts them whenever they change, until the
+/// process is stopped.
+///
+/// The report is written again after each change, with the errors of files
+/// that fail to count listed below it on standard error. The human-readable
+/// table is redrawn in place.
+///
+/// # Errors
+///
+/// Returns an error if an input pattern is invalid or a directory cannot be
+/// read.
+pub fn run(args: &Cli) -> Result<()> {
+    let options = args.count_options();
+    let formatter =
+        OutputFormatter::new(args.format, args.mode).with_options(args.format_options());
+    let mut watched: Vec<(PathBuf, Watched)> = Vec::new();
+    let (mut stdout, mut stderr) = (io::stdout(), io::stderr());
+    loop {
+        let files = input_files(args)?;
+        let mut changed = files.len() != watched.len();
+        let mut previous = std::mem::take(&mut watched);
+        for path in files {
+            let last = previous
+                .iter()
+                .position(|(known, _)| *known == path)
+                .map(|index| previous.swap_remove(index).1);
+            let entry = match last {
+                Some(last) if !last.is_stale() => last,
+                last => {
+                    changed = true;
+                    Watched::count(&path, args, &options, last)
+                }
+            };
+            watched.push((path, entry));
+        }
+
+        if changed {
+            let results: Vec<(String, Count)> = watched
+                .iter()
+                .filter_map(|(path, entry)| {
+                    let count = entry.count.as_ref().ok()?;
+                    Some((path.display().to_string(), *count))
+                })
+                .collect();
+            let human = args.format == OutputFormat::Human;
+            let mut report = String::new();
+            if human {
+                // Clear the screen and move the cursor to its top left corner.
+                report += "\x1b[2J\x1b[H";
+            }
+            report += &formatter.format_output(&results, args.display);
+            if !report.ends_with('\n') {
+                report.push('\n');
+            }
+            stdout.write_all(report.as_bytes())?;
+            stdout.flush()?;
+
+            // Below the table, the status is set apart by an empty line.
+            let mut status = String::from(if human { "\n" } else { "" });
+            for (path, entry) in &watched {
+                if let Err(message) = &entry.count {
+                    writeln!(status, "Error: {}: {message}", path.display()).unwrap();
+                }
+            }
+            writeln!(
+                status,
+                "Watching {} files for changes; press Ctrl+C to stop",
+                watched.len()
+            )
+            .unwrap();
+            stderr.write_all(status.as_bytes())?;
+            if let Some(command) = &args.exec
+                && let Err(e) = exec::run(command, args, &results)
+            {
+                eprintln!("Warning: {e:#}");
+            }
+            if let Some(url) = &args.webhook
+                && let Err(e) = webhook::post(url, &args.json_report(&results))
+            {
+                eprintln!("Warning: {e:#}");
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Returns the files to watch, with glob patterns among the inputs expanded,
+/// followed by the PDFs and plain text files given as such.
+fn input_files(args: &Cli) -> Result<Vec<PathBuf>> {
+    let mut inputs = Vec::new();
+    for input in &args.input {
+        let pattern = input.to_string_lossy();
+        if input.exists() || !pattern.contains(['*', '?', '[']) {
+            inputs.push(input.clone());
+            continue;
+        }
+        let matches =
+            glob::glob(&pattern).with_context(|| format!("Invalid input pattern: {pattern}"))?;
+        inputs.extend(matches.filter_map(Result::ok));
+    }
+    let mut files = discovery::expand_inputs(&inputs, &args.discovery_options())?;
+    files.extend(args.from_pdf.iter().chain(&args.include_plain).cloned());
+    Ok(files)
+}
+
+/// Returns the modification time of a file, or `None` if it doesn't exist.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
@@ -0,0 +1,60 @@
+//! Posting reports to a webhook.
+//!
+//! `--webhook URL` sends the JSON report to a chat bot or writing dashboard
+//! after each run, without a script around typst-count. If
+//! `TYPST_COUNT_WEBHOOK_SECRET` is set, the report is signed with it, so
+//! the receiver can check that it comes from a trusted run: the
+//! `X-Typst-Count-Signature-256` header holds `sha256=` followed by the
+//! hexadecimal HMAC-SHA256 of the body, as GitHub signs its webhooks.
+
+use crate::http;
+use anyhow::{Result, bail};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::env;
+
+/// Environment variable holding the secret reports are signed with.
+pub const SECRET_VAR: &str = "TYPST_COUNT_WEBHOOK_SECRET";
+
+/// Name of the header carrying the signature.
+const SIGNATURE_HEADER: &str = "X-Typst-Count-Signature-256";
+
+/// Posts a JSON report to a URL, signed if a secret is set.
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the server responds with an
+/// error status.
+pub fn post(url: &str, json: &str) -> Result<()> {
+    let mut request = http::agent()?
+        .post(url)
+        .set("Content-Type", "application/json");
+    if let Some(secret) = env::var_os(SECRET_VAR) {
+        let signature = signature(secret.as_encoded_bytes(), json.as_bytes());
+        request = request.set(SIGNATURE_HEADER, &signature);
+    }
+    if let Err(e) = request.send_string(json) {
+        bail!("Failed to post the report to {url}: {}", http::describe(e));
+    }
+    Ok(())
+}
+
+/// Returns the signature header value of a body.
+fn signature(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(body);
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature() {
+        assert_eq!(
+            signature(b"It's a Secret to Everybody", b"Hello, World!"),
+            "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Stopwords and word lists for the frequency and density reports.
+//!
+//! Without them, `frequency` is topped by "the" and "of", and keyword
+//! densities are diluted by words that carry no meaning. Word lists are
+//! plain text files with one word per line; empty lines and lines starting
+//! with `#` are skipped. They are referenced per language in the
+//! configuration file:
+//!
+//! ```toml
+//! [word-lists.en]
+//! stopwords = "lists/stopwords-en.txt"
+//! include = ["lists/keep.txt"]
+//! exclude = ["lists/names.txt"]
+//! ```
+//!
+//! Stopwords are left out of the frequency list, as are phrases made up of
+//! nothing but stopwords. Excluded words, such as the names of the authors,
+//! are left out along with every phrase containing them. Words on an
+//! include list are always kept, even if they are also stopwords. Both
+//! stopwords and excluded words don't count towards the words a keyword
+//! density is computed from. Words are compared regardless of case.
+
+use crate::counter::CountOptions;
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// The words of the word lists that apply, lowercase.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordFilter {
+    /// Words without meaning of their own, e.g. "the"
+    pub stopwords: HashSet<String>,
+    /// Words that are always kept, even if they are stopwords
+    pub include: HashSet<String>,
+    /// Words that are never counted
+    pub exclude: HashSet<String>,
+}
+
+impl WordFilter {
+    /// Checks whether there are no words to filter.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.stopwords.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Adds the words of a word list file to a set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn read_list(words: &mut HashSet<String>, path: &Path) -> Result<()> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read word list: {}", path.display()))?;
+        words.extend(parse_list(&text));
+        Ok(())
+    }
+
+    /// Checks whether a word is left out, as a stopword or an excluded word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use typst_count::wordlists::WordFilter;
+    ///
+    /// let filter = WordFilter {
+    ///     stopwords: ["the".to_string(), "us".to_string()].into(),
+    ///     include: ["us".to_string()].into(),
+    ///     ..WordFilter::default()
+    /// };
+    /// assert!(filter.ignores("The"));
+    /// assert!(!filter.ignores("US"));
+    /// ```
+    #[must_use]
+    pub fn ignores(&self, word: &str) -> bool {
+        let word = word.to_lowercase();
+        !self.include.contains(&word)
+            && (self.stopwords.contains(&word) || self.exclude.contains(&word))
+    }
+
+    /// Checks whether a phrase is left out, because it contains an excluded
+    /// word or consists of stopwords only.
+    ///
+    /// The words are expected in lowercase, as in
+    /// [`analysis::count_phrases`](crate::analysis::count_phrases).
+    #[must_use]
+    pub fn ignores_phrase(&self, words: &[String]) -> bool {
+        let kept = |word: &String| self.include.contains(word);
+        words
+            .iter()
+            .any(|word| !kept(word) && self.exclude.contains(word))
+            || words
+                .iter()
+                .all(|word| !kept(word) && self.stopwords.contains(word))
+    }
+
+    /// Counts the words of some texts that are left out.
+    ///
+    /// Words are split as when counting, see [`CountOptions::tokens`], and
+    /// compared without surrounding punctuation.
+    #[must_use]
+    pub fn ignored_words(&self, texts: &[impl AsRef<str>], options: &CountOptions) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        texts
+            .iter()
+            .flat_map(|text| options.tokens(text.as_ref()))
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| !word.is_empty() && self.ignores(word))
+            .count()
+    }
+}
+
+/// Reads the words of a word list, lowercase.
+fn parse_list(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_filter() {
+        let filter = WordFilter {
+            stopwords: parse_list("# English\nthe\nof\n\nAS\nus\n").collect(),
+            include: parse_list("us").collect(),
+            exclude: parse_list("Smith").collect(),
+        };
+        assert_eq!(filter.stopwords.len(), 4);
+
+        let phrase = |text: &str| -> Vec<String> { text.split(' ').map(str::to_string).collect() };
+        assert!(filter.ignores_phrase(&phrase("of the")));
+        assert!(filter.ignores_phrase(&phrase("smith and jones")));
+        assert!(!filter.ignores_phrase(&phrase("the us")));
+        assert!(!filter.ignores_phrase(&phrase("state of the art")));
+
+        let options = CountOptions::default();
+        assert_eq!(
+            filter.ignored_words(&["The state of the art, as Smith put it.", "US"], &options),
+            5
+        );
+        assert_eq!(filter.ignored_words(&["state-of-the-art"], &options), 0);
+        let pattern = CountOptions {
+            word_pattern: Some(r"[\w']+".parse().unwrap()),
+            ..CountOptions::default()
+        };
+        assert_eq!(filter.ignored_words(&["state-of-the-art"], &pattern), 2);
+        assert_eq!(WordFilter::default().ignored_words(&["the"], &options), 0);
+    }
+}
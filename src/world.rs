@@ -1,11 +1,17 @@
 //! A minimal implementation of Typst's World trait for document compilation.
 //!
 //! This module provides a simple world implementation that allows loading and
-//! compiling Typst documents from the filesystem. It handles file resolution,
-//! source loading, package resolution, and provides the minimal context needed for compilation.
+//! compiling Typst documents from the filesystem or from memory. It handles file
+//! resolution, source loading, package resolution, and provides the minimal context
+//! needed for compilation.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use std::collections::HashMap;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, OnceLock};
 use typst::diag::{FileError, FileResult};
 use typst::foundations::{Bytes, Datetime};
 use typst::syntax::{FileId, Source, VirtualPath};
@@ -13,9 +19,176 @@ use typst::text::{Font, FontBook};
 use typst::utils::LazyHash;
 use typst::{Library, LibraryExt, World};
 use typst_kit::download::{Downloader, ProgressSink};
-use typst_kit::fonts::{FontSlot, Fonts};
+use typst_kit::fonts::FontSlot;
 use typst_kit::package::PackageStorage;
 
+/// The fonts available to documents, searched once per process.
+///
+/// Searching the system fonts is the slowest part of creating a world, and a
+/// run or a daemon compiles many documents, so all worlds share the fonts and
+/// the faces loaded from them.
+static FONTS: LazyLock<SystemFonts> = LazyLock::new(SystemFonts::search);
+
+/// A font book and the fonts it describes.
+struct SystemFonts {
+    /// Font book with discovered fonts
+    book: LazyHash<FontBook>,
+    /// Fonts in the order of the book, loaded when first requested
+    fonts: Vec<LazyFont>,
+    /// Contents of the font files loaded so far, by path
+    files: Mutex<FxHashMap<PathBuf, Bytes>>,
+}
+
+/// A font that is loaded when the compiler first requests it.
+struct LazyFont {
+    /// Where the font was found, and loads the embedded fonts; `None` for
+    /// fonts loaded up front
+    slot: Option<FontSlot>,
+    /// The loaded font, or `None` if it failed to load
+    font: OnceLock<Option<Font>>,
+}
+
+/// Fonts embedded with the `minimal-fonts` feature: the regular faces of a
+/// serif for text, a monospace font for raw text, and a font for equations.
+///
+/// Only these files are embedded, rather than all of Typst's fonts, to keep
+/// the binary small.
+#[cfg(feature = "minimal-fonts")]
+const MINIMAL_FONTS: [&[u8]; 3] = [
+    include_bytes!("fonts/NewCM10-Regular.otf"),
+    include_bytes!("fonts/DejaVuSansMono.ttf"),
+    include_bytes!("fonts/NewCMMath-Regular.otf"),
+];
+
+impl SystemFonts {
+    /// Loads the [`MINIMAL_FONTS`], without searching the system fonts.
+    ///
+    /// Counting doesn't depend on the exact faces, so this is enough for
+    /// documents whose fonts are not installed, e.g. in CI containers. Takes
+    /// precedence over the `embed-fonts` feature if both are enabled.
+    #[cfg(feature = "minimal-fonts")]
+    fn search() -> Self {
+        let fonts: Vec<Font> = MINIMAL_FONTS
+            .into_iter()
+            .flat_map(|data| Font::iter(Bytes::new(data)))
+            .collect();
+        Self {
+            book: LazyHash::new(FontBook::from_fonts(&fonts)),
+            fonts: fonts
+                .into_iter()
+                .map(|font| LazyFont {
+                    slot: None,
+                    font: OnceLock::from(Some(font)),
+                })
+                .collect(),
+            files: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Searches the system fonts, and the embedded fonts if enabled.
+    #[cfg(not(feature = "minimal-fonts"))]
+    fn search() -> Self {
+        let mut font_searcher = typst_kit::fonts::Fonts::searcher();
+        font_searcher.include_system_fonts(true);
+        #[cfg(feature = "embed-fonts")]
+        font_searcher.include_embedded_fonts(true);
+        let fonts = font_searcher.search();
+        Self {
+            book: LazyHash::new(fonts.book),
+            fonts: fonts
+                .fonts
+                .into_iter()
+                .map(|slot| LazyFont {
+                    slot: Some(slot),
+                    font: OnceLock::new(),
+                })
+                .collect(),
+            files: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    /// Returns the font at an index of the book, loading it on first use.
+    fn get(&self, index: usize) -> Option<Font> {
+        let lazy = self.fonts.get(index)?;
+        lazy.font
+            .get_or_init(|| {
+                let slot = lazy.slot.as_ref()?;
+                match slot.path() {
+                    Some(path) => Font::new(self.file(path)?, slot.index()),
+                    None => slot.get(),
+                }
+            })
+            .clone()
+    }
+
+    /// Returns the contents of a font file, loading it on first use.
+    ///
+    /// The faces of a collection such as Noto CJK share the file's contents
+    /// instead of each loading their own copy.
+    fn file(&self, path: &Path) -> Option<Bytes> {
+        let mut files = self.files.lock();
+        if let Some(data) = files.get(path) {
+            return Some(data.clone());
+        }
+        let data = read_font_file(path).ok()?;
+        files.insert(path.to_path_buf(), data.clone());
+        Some(data)
+    }
+}
+
+/// Returns the placeholder for a missing file, chosen by its extension.
+///
+/// Images are empty 1x1 images, so they decode in the format their
+/// extension announces. Data files parse to `null` or nothing at all.
+fn placeholder(path: &Path) -> &'static [u8] {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => include_bytes!("placeholders/placeholder.png"),
+        "jpg" | "jpeg" => include_bytes!("placeholders/placeholder.jpg"),
+        "gif" => include_bytes!("placeholders/placeholder.gif"),
+        "webp" => include_bytes!("placeholders/placeholder.webp"),
+        "svg" => include_bytes!("placeholders/placeholder.svg"),
+        "json" | "yaml" | "yml" => b"null",
+        "cbor" => &[0xf6],
+        "xml" => b"<placeholder/>",
+        _ => b"",
+    }
+}
+
+/// Family of Typst's default text font, preferred as the fallback.
+const DEFAULT_FAMILY: &str = "Libertinus Serif";
+
+/// Returns the family substituted for missing fonts.
+///
+/// This is Typst's default text font if available, or the first family
+/// found otherwise.
+#[must_use]
+pub fn fallback_family() -> Option<&'static str> {
+    let infos = || (0..FONTS.fonts.len()).filter_map(|index| FONTS.book.info(index));
+    infos()
+        .find(|info| info.family == DEFAULT_FAMILY)
+        .or_else(|| infos().next())
+        .map(|info| info.family.as_str())
+}
+
+/// Loads a font file by mapping it into memory, or by reading it where
+/// mapping is not available.
+///
+/// Of a mapped file, only the pages holding the tables the compiler parses
+/// are loaded, which is a small part of a large font.
+fn read_font_file(path: &Path) -> io::Result<Bytes> {
+    let file = std::fs::File::open(path)?;
+    // SAFETY: Like other programs using fonts, this assumes that installed
+    // font files are not modified while in use.
+    match unsafe { memmap2::Mmap::map(&file) } {
+        Ok(mapping) => Ok(Bytes::new(mapping)),
+        Err(_) => std::fs::read(path).map(Bytes::new),
+    }
+}
+
 /// A minimal implementation of Typst's `World` trait for standalone compilation.
 ///
 /// This struct provides the bare minimum functionality needed to compile Typst
@@ -41,16 +214,29 @@ use typst_kit::package::PackageStorage;
 pub struct SimpleWorld {
     /// The Typst standard library
     library: LazyHash<Library>,
-    /// Font book with discovered fonts
-    book: LazyHash<FontBook>,
-    /// Locations of and storage for lazily loaded fonts
-    fonts: Vec<FontSlot>,
     /// File ID of the main document
     main: FileId,
-    /// Root directory for resolving relative paths
-    root: PathBuf,
+    /// Root directory for resolving relative paths, `None` for in-memory documents
+    root: Option<PathBuf>,
     /// Package storage for @preview packages
     package_storage: PackageStorage,
+    /// Sources that have already been loaded, keyed by file ID
+    sources: Mutex<FxHashMap<FileId, Source>>,
+    /// Contents of in-memory files, which take precedence over the filesystem
+    files: FxHashMap<FileId, Bytes>,
+    /// Font book extended by substitutes for missing families, if any
+    book: Option<LazyHash<FontBook>>,
+    /// Indices of the fonts standing in for the substitutes in the book,
+    /// which follow the shared fonts
+    substitutes: Vec<usize>,
+    /// Whether files that are not found are served as placeholders
+    placeholders: bool,
+    /// Paths of the files served as placeholders so far
+    missing_assets: Mutex<Vec<PathBuf>>,
+    /// Sources compiled as empty files instead of their contents
+    skipped: Vec<FileId>,
+    /// Paths of the project files read so far
+    dependencies: Mutex<Vec<PathBuf>>,
 }
 
 impl SimpleWorld {
@@ -104,30 +290,195 @@ impl SimpleWorld {
         );
         let main = FileId::new_fake(vpath);
 
+        Ok(Self::with_main(main, Some(root)))
+    }
+
+    /// Creates a new `SimpleWorld` for compiling a Typst document held in memory.
+    ///
+    /// The document is compiled as `/main.typ`. It has no access to the
+    /// filesystem, so reading files other than those added with
+    /// [`with_file`](Self::with_file) fails, but packages can still be
+    /// imported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use typst_count::world::SimpleWorld;
+    ///
+    /// let world = SimpleWorld::from_source("= Hello\nWorld");
+    /// ```
+    #[must_use]
+    pub fn from_source(text: &str) -> Self {
+        let main = FileId::new(None, VirtualPath::new("main.typ"));
+        Self::with_main(main, None).with_file("main.typ", text)
+    }
+
+    /// Creates a new `SimpleWorld` for compiling a project held entirely in
+    /// memory.
+    ///
+    /// `files` maps the virtual path of every source file and asset of the
+    /// project to its contents, e.g. files fetched from a database or a git
+    /// object store, and `main` selects the document to compile. Like
+    /// [`from_source`](Self::from_source), the world has no access to the
+    /// filesystem.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `main` is not one of the files.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use typst::syntax::VirtualPath;
+    /// use typst_count::world::SimpleWorld;
+    ///
+    /// let files = HashMap::from([
+    ///     (VirtualPath::new("thesis.typ"), b"#include \"ch1.typ\"".to_vec()),
+    ///     (VirtualPath::new("ch1.typ"), b"= Introduction".to_vec()),
+    /// ]);
+    /// let world = SimpleWorld::from_files(&VirtualPath::new("thesis.typ"), files)?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn from_files(main: &VirtualPath, files: HashMap<VirtualPath, Vec<u8>>) -> Result<Self> {
+        if !files.contains_key(main) {
+            bail!(
+                "Main file {} is not among the files",
+                main.as_rootless_path().display()
+            );
+        }
+
+        let mut world = Self::with_main(FileId::new(None, main.clone()), None);
+        world.files.extend(
+            files
+                .into_iter()
+                .map(|(path, data)| (FileId::new(None, path), Bytes::new(data))),
+        );
+        Ok(world)
+    }
+
+    /// Adds an in-memory file that the document can include, import, or read.
+    ///
+    /// The path is relative to the root directory, e.g. `chapters/intro.typ`
+    /// or `refs.bib`. In-memory files take precedence over files on disk.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use typst_count::world::SimpleWorld;
+    ///
+    /// let world = SimpleWorld::from_source("#include \"intro.typ\"")
+    ///     .with_file("intro.typ", "= Introduction");
+    /// ```
+    #[must_use]
+    pub fn with_file(mut self, path: &str, data: impl Into<Vec<u8>>) -> Self {
+        let id = FileId::new(None, VirtualPath::new(path));
+        self.files.insert(id, Bytes::new(data.into()));
+        self
+    }
+
+    /// Substitutes the [`fallback_family`] for font families that are not
+    /// available.
+    ///
+    /// The faces of the fallback are added to the world's font book once
+    /// more under each of the `families`, so text set in them is laid out
+    /// instead of failing.
+    #[must_use]
+    pub fn with_font_substitutes(mut self, families: &[String]) -> Self {
+        let Some(fallback) = fallback_family() else {
+            return self;
+        };
+        let mut book = FontBook::clone(&FONTS.book);
+        let faces: Vec<usize> = FONTS.book.select_family(&fallback.to_lowercase()).collect();
+        for family in families {
+            for &index in &faces {
+                let Some(info) = FONTS.book.info(index) else {
+                    continue;
+                };
+                let mut info = info.clone();
+                info.family.clone_from(family);
+                book.push(info);
+                self.substitutes.push(index);
+            }
+        }
+        self.book = Some(LazyHash::new(book));
+        self
+    }
+
+    /// Serves placeholders for images and data files that are not found,
+    /// instead of failing the compilation.
+    ///
+    /// Images are replaced by an empty 1x1 image of their format, and data
+    /// files by empty data; the files served so far are listed by
+    /// [`missing_assets`](Self::missing_assets). Missing Typst sources still
+    /// fail the compilation.
+    #[must_use]
+    pub fn with_asset_placeholders(mut self) -> Self {
+        self.placeholders = true;
+        self
+    }
+
+    /// Returns the paths of the files served as placeholders so far.
+    #[must_use]
+    pub fn missing_assets(&self) -> Vec<PathBuf> {
+        self.missing_assets.lock().clone()
+    }
+
+    /// Compiles the given source files as if they were empty.
+    ///
+    /// Imports of a skipped file find none of its definitions, and includes
+    /// of it add no content.
+    #[must_use]
+    pub fn with_skipped_sources(mut self, files: &[FileId]) -> Self {
+        self.skipped.extend_from_slice(files);
+        self
+    }
+
+    /// Returns the paths of the project files read so far, such as includes
+    /// and images.
+    ///
+    /// Files of packages are left out, as they don't change.
+    #[must_use]
+    pub fn dependencies(&self) -> Vec<PathBuf> {
+        self.dependencies.lock().clone()
+    }
+
+    /// Records a file as read, unless it belongs to a package.
+    fn depend(&self, id: FileId, path: &Path) {
+        let mut dependencies = self.dependencies.lock();
+        if id.package().is_none() && !dependencies.iter().any(|known| known == path) {
+            dependencies.push(path.to_path_buf());
+        }
+    }
+
+    /// Initializes the library and package storage for a world.
+    ///
+    /// The fonts are shared by all worlds, see [`FONTS`].
+    fn with_main(main: FileId, root: Option<PathBuf>) -> Self {
         // Initialize package storage with default cache and no custom paths
         let downloader = Downloader::new("typst-count");
         let package_storage = PackageStorage::new(None, None, downloader);
 
-        // Initialize fonts with system and embedded fonts
-        let mut font_searcher = Fonts::searcher();
-        font_searcher.include_system_fonts(true);
-        #[cfg(feature = "embed-fonts")]
-        font_searcher.include_embedded_fonts(true);
-        let fonts = font_searcher.search();
-
-        Ok(Self {
+        Self {
             library: LazyHash::new(Library::builder().build()),
-            book: LazyHash::new(fonts.book),
-            fonts: fonts.fonts,
             main,
             root,
             package_storage,
-        })
+            sources: Mutex::new(FxHashMap::default()),
+            files: FxHashMap::default(),
+            book: None,
+            substitutes: Vec::new(),
+            placeholders: false,
+            missing_assets: Mutex::new(Vec::new()),
+            skipped: Vec::new(),
+            dependencies: Mutex::new(Vec::new()),
+        }
     }
 
     /// Resolves a file path for a given file ID.
     ///
     /// This handles both regular files (relative to root) and package files.
+    /// In-memory documents have no root, so their regular files are not found.
     fn resolve_path(&self, id: FileId) -> FileResult<PathBuf> {
         // Check if this is a package file
         if let Some(spec) = id.package() {
@@ -141,13 +492,13 @@ impl SimpleWorld {
             // The vpath for package files includes the full path within the package
             Ok(package_dir.join(id.vpath().as_rootless_path()))
         } else {
-            // Regular file resolution
-            let path = if id.vpath().as_rootless_path().is_absolute() {
-                id.vpath().as_rootless_path().to_path_buf()
-            } else {
-                self.root.join(id.vpath().as_rootless_path())
-            };
-            Ok(path)
+            // Regular file resolution, unavailable for in-memory documents
+            let path = id.vpath().as_rootless_path();
+            match &self.root {
+                None => Err(FileError::NotFound(path.to_path_buf())),
+                Some(_) if path.is_absolute() => Ok(path.to_path_buf()),
+                Some(root) => Ok(root.join(path)),
+            }
         }
     }
 }
@@ -160,7 +511,7 @@ impl World for SimpleWorld {
 
     /// Returns a reference to the font book.
     fn book(&self) -> &LazyHash<FontBook> {
-        &self.book
+        self.book.as_ref().unwrap_or(&FONTS.book)
     }
 
     /// Returns the file ID of the main document.
@@ -170,8 +521,11 @@ impl World for SimpleWorld {
 
     /// Loads the source code for a given file ID.
     ///
-    /// This method resolves the file path (either absolute or relative to the
-    /// root directory) and reads the file contents as a UTF-8 string.
+    /// In-memory files are decoded directly. Otherwise, this method resolves
+    /// the file path (either absolute or relative to the root directory) and
+    /// reads the file contents as a UTF-8 string. Loaded
+    /// sources are cached, so repeated lookups (e.g. when resolving spans
+    /// during counting) don't hit the filesystem again.
     ///
     /// # Arguments
     ///
@@ -182,15 +536,31 @@ impl World for SimpleWorld {
     /// A `Source` object containing the file's content and ID, or a file error
     /// if the file cannot be read.
     fn source(&self, id: FileId) -> FileResult<Source> {
-        let path = self.resolve_path(id)?;
-        let content = std::fs::read_to_string(&path).map_err(|e| FileError::from_io(e, &path))?;
-        Ok(Source::new(id, content))
+        if self.skipped.contains(&id) {
+            return Ok(Source::new(id, String::new()));
+        }
+        if let Some(source) = self.sources.lock().get(&id) {
+            return Ok(source.clone());
+        }
+
+        let content = if let Some(data) = self.files.get(&id) {
+            std::str::from_utf8(data)
+                .map_err(|_| FileError::InvalidUtf8)?
+                .to_string()
+        } else {
+            let path = self.resolve_path(id)?;
+            self.depend(id, &path);
+            std::fs::read_to_string(&path).map_err(|e| FileError::from_io(e, &path))?
+        };
+        let source = Source::new(id, content);
+        self.sources.lock().insert(id, source.clone());
+        Ok(source)
     }
 
     /// Loads binary data for a given file ID.
     ///
-    /// This method resolves the file path and reads the file contents as raw bytes.
-    /// Used for loading images, fonts, and other binary assets referenced by the document.
+    /// In-memory files are returned directly. Otherwise, this method resolves the
+    /// file path and reads the file contents as raw bytes. Used for loading images, fonts, and other binary assets referenced by the document.
     ///
     /// # Arguments
     ///
@@ -201,16 +571,35 @@ impl World for SimpleWorld {
     /// A `Bytes` object containing the file's binary content, or a file error
     /// if the file cannot be read.
     fn file(&self, id: FileId) -> FileResult<Bytes> {
+        if let Some(data) = self.files.get(&id) {
+            return Ok(data.clone());
+        }
+
         let path = self.resolve_path(id)?;
-        let content = std::fs::read(&path).map_err(|e| FileError::from_io(e, &path))?;
-        Ok(Bytes::new(content))
+        self.depend(id, &path);
+        match std::fs::read(&path) {
+            Ok(content) => Ok(Bytes::new(content)),
+            Err(e) if self.placeholders && e.kind() == io::ErrorKind::NotFound => {
+                let mut missing = self.missing_assets.lock();
+                if !missing.contains(&path) {
+                    missing.push(path.clone());
+                }
+                Ok(Bytes::new(placeholder(&path)))
+            }
+            Err(e) => Err(FileError::from_io(e, &path)),
+        }
     }
 
     /// Returns a font at the given index.
     ///
     /// Fonts are loaded lazily from the font book as needed by the compiler.
+    /// Substitutes added with [`with_font_substitutes`](Self::with_font_substitutes)
+    /// are the fonts they stand in for.
     fn font(&self, index: usize) -> Option<Font> {
-        self.fonts.get(index)?.get()
+        match index.checked_sub(FONTS.fonts.len()) {
+            Some(substitute) => FONTS.get(*self.substitutes.get(substitute)?),
+            None => FONTS.get(index),
+        }
     }
 
     /// Returns the current date for compilation.
@@ -0,0 +1,53 @@
+//! Words per author declared with `#metadata(..) <author>`.
+
+use std::fs;
+use typst_count::count_document_authors;
+use typst_count::counter::{AuthorCount, CountOptions};
+
+/// Compiles a document from source and counts the words per author.
+fn authors(source: &str) -> Vec<AuthorCount> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(&path, source).unwrap();
+    count_document_authors(&path, &CountOptions::default()).unwrap()
+}
+
+/// Returns an author's entry.
+fn author(name: Option<&str>, words: usize) -> AuthorCount {
+    AuthorCount {
+        author: name.map(ToString::to_string),
+        words,
+    }
+}
+
+#[test]
+fn test_sections_by_author() {
+    let counts = authors(
+        "= Introduction\n\
+         #metadata(\"Ada\") <author>\n\
+         Three words here.\n\
+         == Details\n\
+         Two more.\n\
+         = Method\n\
+         #metadata(\"Charles\") <author>\n\
+         Four words of method.\n\
+         = Conclusion\n\
+         Done.",
+    );
+
+    // Subsection headings belong to the author of the enclosing section, but
+    // the heading opening a section comes before its author's declaration.
+    assert_eq!(
+        counts,
+        [
+            author(Some("Ada"), 6),
+            author(Some("Charles"), 4),
+            author(None, 4),
+        ]
+    );
+}
+
+#[test]
+fn test_without_authors() {
+    assert_eq!(authors("Just some text."), [author(None, 3)]);
+}
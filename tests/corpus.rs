@@ -0,0 +1,100 @@
+//! Golden counts for the documents in `tests/corpus`.
+//!
+//! Every top-level `.typ` file in the corpus is compiled and counted with
+//! the default options. The expected counts are stated in comments at the
+//! top of the file, which are not part of the rendered text:
+//!
+//! ```typst
+//! // words: 13
+//! // characters: 91
+//! ```
+//!
+//! Files in subdirectories are only compiled when a top-level document
+//! includes them. A counting-rule change that alters any count makes this
+//! test fail; if the change is intended, update the comments accordingly.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use typst_count::compile_document;
+
+/// Expected counts stated in the header of a corpus document.
+#[derive(Debug, Default)]
+struct Expected {
+    words: Option<usize>,
+    characters: Option<usize>,
+}
+
+/// Reads the `// key: value` comments at the top of a corpus document.
+fn expected(path: &Path) -> Expected {
+    let source = fs::read_to_string(path).unwrap();
+    let mut expected = Expected::default();
+    for line in source.lines() {
+        let Some(comment) = line.strip_prefix("//") else {
+            break;
+        };
+        let Some((key, value)) = comment.split_once(':') else {
+            continue;
+        };
+        let value = value.trim().parse().ok();
+        match key.trim() {
+            "words" => expected.words = value,
+            "characters" => expected.characters = value,
+            _ => {}
+        }
+    }
+    expected
+}
+
+/// Returns the top-level documents of the corpus, sorted by name.
+fn documents() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let mut documents: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "typ"))
+        .collect();
+    documents.sort();
+    documents
+}
+
+#[test]
+fn test_corpus_golden_counts() {
+    let documents = documents();
+    assert!(!documents.is_empty(), "corpus is empty");
+
+    let mut failures = Vec::new();
+    for path in &documents {
+        let name = path.file_name().unwrap().to_string_lossy();
+        let expected = expected(path);
+        assert!(
+            expected.words.is_some() || expected.characters.is_some(),
+            "{name} has no expected counts"
+        );
+
+        let count = match compile_document(path, false) {
+            Ok(count) => count,
+            Err(err) => {
+                failures.push(format!("{name}: {err:#}"));
+                continue;
+            }
+        };
+        if let Some(words) = expected.words
+            && count.words != words
+        {
+            failures.push(format!(
+                "{name}: expected {words} words, got {}",
+                count.words
+            ));
+        }
+        if let Some(characters) = expected.characters
+            && count.characters != characters
+        {
+            failures.push(format!(
+                "{name}: expected {characters} characters, got {}",
+                count.characters
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
@@ -0,0 +1,8 @@
+// words: 7
+// characters: 50
+
+#set text(lang: "ja")
+
+日本語の文章には空白がありません。
+
+English words mixed with 中文 text.
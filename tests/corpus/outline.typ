@@ -0,0 +1,12 @@
+// words: 7
+// characters: 58
+
+#outline()
+
+= Introduction
+
+Outlines repeat headings.
+
+= Method
+
+Counting words.
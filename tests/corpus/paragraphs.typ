@@ -0,0 +1,8 @@
+// words: 13
+// characters: 91
+
+= Introduction
+
+This corpus document has plain paragraphs.
+
+A second paragraph follows the first.
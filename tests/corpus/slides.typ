@@ -0,0 +1,15 @@
+// words: 10
+// characters: 61
+
+#set page(paper: "presentation-16-9")
+#set text(size: 24pt)
+
+= Word Counts
+
+Slides have short lines.
+
+#pagebreak()
+
+= Results
+
+Every slide counts.
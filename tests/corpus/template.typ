@@ -0,0 +1,15 @@
+// words: 19
+// characters: 104
+
+#let article(title: none, body) = {
+  set page(numbering: "1")
+  set par(justify: true)
+  heading(outlined: false, title)
+  body
+}
+
+#show: article.with(title: [A Short Article])
+
+Templates wrap the body in show rules and set rules.
+
+*Bold* and _emphasised_ words count once.
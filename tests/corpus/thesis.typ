@@ -0,0 +1,14 @@
+// words: 30
+// characters: 198
+
+#set heading(numbering: "1.1")
+
+= Introduction
+
+This thesis studies word counts.
+
+#include "thesis/background.typ"
+
+= Conclusion
+
+Counting words is harder than it looks.
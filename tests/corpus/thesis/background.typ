@@ -0,0 +1,7 @@
+= Background
+
+Earlier work split text on whitespace.
+
+== Related Tools
+
+Other tools count the source markup instead.
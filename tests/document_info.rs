@@ -0,0 +1,47 @@
+//! Title and authors read from the compiled document.
+
+use std::fs;
+use typst_count::counter::CountOptions;
+use typst_count::document_info;
+use typst_count::metadata::DocumentInfo;
+
+/// Compiles a document from source and reads its title and authors.
+fn info(source: &str) -> DocumentInfo {
+    info_with(source, &CountOptions::default()).unwrap()
+}
+
+/// Compiles a document from source with the given options and reads its
+/// title and authors.
+fn info_with(source: &str, options: &CountOptions) -> anyhow::Result<DocumentInfo> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(&path, source).unwrap();
+    document_info(&path, options)
+}
+
+#[test]
+fn test_title_and_authors() {
+    let info = info(
+        "#set document(title: [On Counting], author: (\"Ada Lovelace\", \"Charles Babbage\"))\n\
+         Some text.",
+    );
+    assert_eq!(info.title.as_deref(), Some("On Counting"));
+    assert_eq!(info.authors, ["Ada Lovelace", "Charles Babbage"]);
+}
+
+#[test]
+fn test_without_document_settings() {
+    assert_eq!(info("Some text."), DocumentInfo::default());
+}
+
+#[test]
+fn test_with_count_options() {
+    let source = "#set document(title: [Figures])\n#image(\"missing.png\")";
+    assert!(info_with(source, &CountOptions::default()).is_err());
+    let options = CountOptions {
+        allow_missing_assets: true,
+        ..CountOptions::default()
+    };
+    let info = info_with(source, &options).unwrap();
+    assert_eq!(info.title.as_deref(), Some("Figures"));
+}
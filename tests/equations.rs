@@ -0,0 +1,20 @@
+//! Inline and display equations, reported with the equation metrics.
+
+use std::fs;
+use typst_count::compile_document_with;
+use typst_count::counter::{CountOptions, Metric};
+
+#[test]
+fn test_inline_and_display_equations() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(&path, "Let $x$ and $y$ be given.\n\n$ x = y $").unwrap();
+    let options = CountOptions {
+        metrics: vec![Metric::InlineEquations, Metric::DisplayEquations],
+        ..CountOptions::default()
+    };
+    let count = compile_document_with(&path, &options).unwrap();
+
+    assert_eq!(count.metrics.get(Metric::InlineEquations), Some(2));
+    assert_eq!(count.metrics.get(Metric::DisplayEquations), Some(1));
+}
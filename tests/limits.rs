@@ -0,0 +1,56 @@
+//! Limits declared inside documents with labelled metadata.
+
+use std::fs;
+use typst_count::counter::{CountOptions, DeclaredLimit};
+use typst_count::document_limits;
+
+/// Compiles a document from source and returns its declared limits.
+fn limits(source: &str) -> Vec<DeclaredLimit> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(&path, source).unwrap();
+    document_limits(&path, &CountOptions::default()).unwrap()
+}
+
+#[test]
+fn test_limit_on_labelled_heading() {
+    let limits = limits(
+        "= Abstract <abstract>\n\
+         #metadata((max-words: 3)) <limit-abstract>\n\
+         This abstract has five words.\n\
+         = Introduction\n\
+         The introduction is not part of it.",
+    );
+
+    assert_eq!(limits.len(), 1);
+    let limit = &limits[0];
+    assert_eq!(limit.part, "abstract");
+    assert_eq!(limit.max_words, Some(3));
+    assert_eq!(limit.words, 5);
+    assert_eq!(limit.location.as_ref().unwrap().line, 1);
+    assert_eq!(
+        limit.violations(),
+        ["Section `abstract` word count exceeds maximum (5 > 3)"]
+    );
+}
+
+#[test]
+fn test_limit_on_heading_text() {
+    let limits = limits(
+        "#metadata((min-words: 2, max-characters: 100)) <limit-conclusion>\n\
+         = Introduction\n\
+         Some introductory words.\n\
+         = Conclusion\n\
+         It works.",
+    );
+
+    assert_eq!(limits.len(), 1);
+    assert_eq!(limits[0].words, 2);
+    assert_eq!(limits[0].location.as_ref().unwrap().line, 4);
+    assert!(limits[0].violations().is_empty());
+}
+
+#[test]
+fn test_unrelated_metadata_is_ignored() {
+    assert!(limits("#metadata((max-words: 3)) <config>\nSome text.").is_empty());
+}
@@ -0,0 +1,27 @@
+//! Standard manuscript pages, reported with the `manuscript-pages` metric.
+
+use std::fs;
+use typst_count::compile_document_with;
+use typst_count::counter::{CountOptions, Metric};
+
+#[test]
+fn test_manuscript_pages() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(&path, "word ".repeat(600)).unwrap();
+    let options = CountOptions {
+        metrics: vec![Metric::ManuscriptPages],
+        ..CountOptions::default()
+    };
+
+    let count = compile_document_with(&path, &options).unwrap();
+    assert_eq!(count.words, 600);
+    assert_eq!(count.metrics.get(Metric::ManuscriptPages), Some(3));
+
+    let options = CountOptions {
+        words_per_page: Some(300),
+        ..options
+    };
+    let count = compile_document_with(&path, &options).unwrap();
+    assert_eq!(count.metrics.get(Metric::ManuscriptPages), Some(2));
+}
@@ -0,0 +1,34 @@
+//! Content excluded with `CountOptions::exclude_marked`.
+
+use std::fs;
+use typst_count::compile_document_with;
+use typst_count::counter::CountOptions;
+
+/// Compiles a document from source and counts its words, skipping content
+/// with the given markers.
+fn words(source: &str, markers: &[&str]) -> usize {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(&path, source).unwrap();
+    let options = CountOptions {
+        exclude_marked: markers.iter().map(ToString::to_string).collect(),
+        ..CountOptions::default()
+    };
+    compile_document_with(&path, &options).unwrap().words
+}
+
+#[test]
+fn test_labelled_block() {
+    let source = "Finished text.\n\n#block[Unfinished *draft* passage.] <todo>\n\nMore text.";
+    assert_eq!(words(source, &["todo"]), 4);
+    assert_eq!(words(source, &["<todo>"]), 4);
+}
+
+#[test]
+fn test_element() {
+    // The text of inline elements is removed from the enclosing paragraph.
+    let source = "Keep this. #strike[Drop this.]";
+    assert_eq!(words(source, &["strike"]), 2);
+    // Angle brackets only match labels.
+    assert_eq!(words(source, &["<strike>"]), 4);
+}
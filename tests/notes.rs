@@ -0,0 +1,38 @@
+//! Words in notes, reported with the `note-words` and `body-words` metrics.
+
+use std::fs;
+use typst_count::compile_document_with;
+use typst_count::counter::{Count, CountOptions, Metric};
+
+/// Compiles a document from source and counts it with the note metrics.
+fn count(source: &str, note_markers: &[&str]) -> Count {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(&path, source).unwrap();
+    let options = CountOptions {
+        metrics: vec![Metric::NoteWords, Metric::BodyWords],
+        note_markers: note_markers.iter().map(ToString::to_string).collect(),
+        ..CountOptions::default()
+    };
+    compile_document_with(&path, &options).unwrap()
+}
+
+#[test]
+fn test_footnotes() {
+    let count = count("Main text.#footnote[A short note on it.]", &[]);
+    let notes = count.metrics.get(Metric::NoteWords).unwrap();
+    let body = count.metrics.get(Metric::BodyWords).unwrap();
+
+    assert!(notes >= 5);
+    assert_eq!(notes + body, count.words);
+}
+
+#[test]
+fn test_marked_notes() {
+    let source = "Main text.\n\n= An endnote of five words <endnote>";
+    assert_eq!(count(source, &[]).metrics.get(Metric::NoteWords), Some(0));
+    assert_eq!(
+        count(source, &["<endnote>"]).metrics.get(Metric::NoteWords),
+        Some(5)
+    );
+}
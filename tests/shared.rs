@@ -0,0 +1,78 @@
+//! Files included by several inputs, counted once with `--dedupe-shared`.
+
+use clap::Parser;
+use std::fs;
+use typst_count::cli::Cli;
+use typst_count::counter::{CountOptions, Metric};
+use typst_count::output::calculate_total;
+use typst_count::{Deduplicated, count_document_sources, dedupe_shared, process_files};
+
+#[test]
+fn test_dedupe_shared() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("glossary.typ"), "Shared glossary entry.").unwrap();
+    let a = dir.path().join("a.typ");
+    let b = dir.path().join("b.typ");
+    fs::write(&a, "Alpha text.\n\n#include \"glossary.typ\"").unwrap();
+    fs::write(&b, "Beta.\n\n#include \"glossary.typ\"").unwrap();
+
+    let sources = count_document_sources(&a, &CountOptions::default()).unwrap();
+    let words: Vec<_> = sources
+        .iter()
+        .map(|(path, count)| (path.file_name().unwrap().to_str().unwrap(), count.words))
+        .collect();
+    assert_eq!(words, [("a.typ", 2), ("glossary.typ", 3)]);
+
+    let args = Cli::parse_from([
+        "typst-count".as_ref(),
+        a.as_os_str(),
+        b.as_os_str(),
+        "--dedupe-shared".as_ref(),
+    ]);
+    let counted = process_files(&args).unwrap();
+    assert_eq!(counted[1].1.words, 4);
+
+    let Deduplicated {
+        results,
+        shared_words,
+    } = dedupe_shared(&args).unwrap();
+    assert_eq!(results[0].1.words, 5);
+    assert_eq!(results[1].1.words, 1);
+    assert_eq!(shared_words.get(&b.display().to_string()), Some(&3));
+    assert!(!shared_words.contains_key(&a.display().to_string()));
+}
+
+#[test]
+fn test_dedupe_shared_metrics() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("glossary.typ"),
+        "- Shared entry $x$\n- Another entry",
+    )
+    .unwrap();
+    let a = dir.path().join("a.typ");
+    let b = dir.path().join("b.typ");
+    fs::write(&a, "Alpha text $y$.\n\n#include \"glossary.typ\"").unwrap();
+    fs::write(&b, "Beta.\n\n#include \"glossary.typ\"").unwrap();
+
+    let args = Cli::parse_from([
+        "typst-count".as_ref(),
+        a.as_os_str(),
+        b.as_os_str(),
+        "--dedupe-shared".as_ref(),
+        "--metric=list-words,inline-equations".as_ref(),
+    ]);
+    let counted = calculate_total(&process_files(&args).unwrap());
+    assert_eq!(counted.metrics.get(Metric::InlineEquations), Some(3));
+
+    let Deduplicated { results, .. } = dedupe_shared(&args).unwrap();
+    let glossary = results[0].1.metrics.get(Metric::ListWords).unwrap();
+    assert!(glossary > 0);
+    assert_eq!(results[1].1.metrics.get(Metric::ListWords), Some(0));
+    assert_eq!(results[1].1.metrics.get(Metric::InlineEquations), Some(0));
+
+    let total = calculate_total(&results);
+    assert_eq!(total.metrics.get(Metric::InlineEquations), Some(2));
+    assert_eq!(total.metrics.get(Metric::ListWords), Some(glossary));
+    assert_eq!(total.words, counted.words - glossary);
+}
@@ -0,0 +1,69 @@
+//! Counts of documents compiled from memory.
+
+use std::collections::HashMap;
+use typst::syntax::VirtualPath;
+use typst_count::counter::CountOptions;
+use typst_count::{compile_files, compile_source, compile_source_with_files};
+
+#[test]
+fn test_compile_source() {
+    let count = compile_source("= Title\nSome *bold* text.", &CountOptions::default()).unwrap();
+    assert_eq!(count.words, 4);
+    assert_eq!(count.characters, 20);
+}
+
+#[test]
+fn test_compile_source_with_files() {
+    let files = [
+        (
+            "chapters/intro.typ",
+            "#import \"../names.typ\": tool\nWe use #tool.".as_bytes(),
+        ),
+        ("names.typ", "#let tool = [typst-count]".as_bytes()),
+    ];
+    let source = "= Thesis\n#include \"chapters/intro.typ\"";
+    let count = compile_source_with_files(source, &files, &CountOptions::default()).unwrap();
+    assert_eq!(count.words, 4);
+}
+
+#[test]
+fn test_compile_source_without_filesystem() {
+    let error = compile_source("#include \"Cargo.toml\"", &CountOptions::default()).unwrap_err();
+    assert!(error.to_string().starts_with("Failed to compile source"));
+}
+
+#[test]
+fn test_compile_files() {
+    let files = HashMap::from([
+        (
+            VirtualPath::new("thesis.typ"),
+            b"= Thesis\n#include \"chapters/ch1.typ\"\n#include \"chapters/ch2.typ\"".to_vec(),
+        ),
+        (
+            VirtualPath::new("chapters/ch1.typ"),
+            b"First chapter text.".to_vec(),
+        ),
+        (
+            VirtualPath::new("chapters/ch2.typ"),
+            b"Second chapter.".to_vec(),
+        ),
+    ]);
+    let count = compile_files(
+        &VirtualPath::new("thesis.typ"),
+        files,
+        &CountOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(count.words, 6);
+}
+
+#[test]
+fn test_compile_files_missing_main() {
+    let files = HashMap::from([(VirtualPath::new("ch1.typ"), b"Text.".to_vec())]);
+    let result = compile_files(
+        &VirtualPath::new("main.typ"),
+        files,
+        &CountOptions::default(),
+    );
+    assert!(result.is_err());
+}
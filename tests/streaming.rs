@@ -0,0 +1,62 @@
+//! Counted text streamed to a callback while counting, or pulled from an
+//! iterator.
+
+use std::fs;
+use typst::layout::PagedDocument;
+use typst_count::counter::{CountOptions, TextChunk, extract_chunks, iter_text};
+use typst_count::world::SimpleWorld;
+use typst_count::{compile_document_with, stream_document};
+
+#[test]
+fn test_chunks_add_up_to_the_count() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(
+        &path,
+        "= Introduction\nSome *bold* text.\n\n#footnote[A note.]\n\n$x + y$ and more.",
+    )
+    .unwrap();
+    let options = CountOptions {
+        exclude_footnotes: true,
+        ..CountOptions::default()
+    };
+
+    let mut chunks: Vec<TextChunk> = Vec::new();
+    let count = stream_document(&path, &options, |chunk| chunks.push(chunk)).unwrap();
+    assert_eq!(count, compile_document_with(&path, &options).unwrap());
+
+    let words: usize = chunks
+        .iter()
+        .map(|chunk| chunk.text.split_whitespace().count())
+        .sum();
+    assert_eq!(words, count.words);
+    assert_eq!(chunks[0].element, "heading");
+    assert_eq!(chunks[0].text, "Introduction");
+    assert_eq!(chunks[0].location.as_ref().unwrap().line, 1);
+    assert!(chunks.iter().all(|chunk| !chunk.text.contains("note")));
+}
+
+#[test]
+fn test_iter_text() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(
+        &path,
+        "= Methods
+First *paragraph*.
+
+Second paragraph.",
+    )
+    .unwrap();
+    let world = SimpleWorld::new(&path).unwrap();
+    let document = typst::compile::<PagedDocument>(&world).output.unwrap();
+    let options = CountOptions::default();
+
+    let mut chunks = iter_text(&world, &document.introspector, &options);
+    assert_eq!(chunks.next().unwrap().text, "Methods");
+    assert_eq!(chunks.next().unwrap().text, "First paragraph.");
+    assert_eq!(
+        iter_text(&world, &document.introspector, &options).collect::<Vec<_>>(),
+        extract_chunks(&world, &document.introspector, &options)
+    );
+}
@@ -0,0 +1,70 @@
+//! Counts of documents whose text is wrapped in styling elements.
+//!
+//! Wrapper elements repeat the text of their parent paragraph in the
+//! document's element tree, so every wrapper must be skipped to avoid
+//! counting that text twice.
+
+use std::fs;
+use typst_count::compile_document_with;
+use typst_count::counter::{Count, CountOptions};
+
+/// Compiles a document from source and counts it.
+fn count_with(source: &str, options: &CountOptions) -> Count {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("main.typ");
+    fs::write(&path, source).unwrap();
+    compile_document_with(&path, options).unwrap()
+}
+
+/// Compiles a document from source and counts it with default options.
+fn count(source: &str) -> Count {
+    count_with(source, &CountOptions::default())
+}
+
+#[test]
+fn test_plain_paragraph() {
+    assert_eq!(count("Four words of text.").words, 4);
+}
+
+#[test]
+fn test_strong_and_emph() {
+    assert_eq!(count("Some *bold* and _italic_ words.").words, 5);
+}
+
+#[test]
+fn test_smallcaps() {
+    assert_eq!(count("#smallcaps[Hello world] and more.").words, 4);
+}
+
+#[test]
+fn test_highlight() {
+    assert_eq!(count("#highlight[Some text] here.").words, 3);
+}
+
+#[test]
+fn test_box() {
+    assert_eq!(count("Inline #box[boxed words] text.").words, 4);
+}
+
+#[test]
+fn test_text_show_rule_wrapper() {
+    let source = "#show heading: it => text(fill: red, it.body)\n\
+                  = Styled heading\n\
+                  Body text.";
+    assert_eq!(count(source).words, 4);
+}
+
+#[test]
+fn test_nested_wrappers() {
+    let source = "A #highlight[*very _deeply_ nested*] #smallcaps[#box[phrase]] here.";
+    assert_eq!(count(source).words, 6);
+}
+
+#[test]
+fn test_configured_styling_elements() {
+    let options = CountOptions {
+        styling_elements: vec!["heading".to_string()],
+        ..CountOptions::default()
+    };
+    assert_eq!(count_with("= Title\nBody text.", &options).words, 2);
+}